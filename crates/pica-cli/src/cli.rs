@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, value_parser};
+use pica_record::io::Encoding;
 use pica_record::prelude::*;
 
 use crate::commands::*;
@@ -17,6 +18,22 @@ pub(crate) struct Args {
     #[arg(short, long, value_name = "FILE")]
     pub(crate) config: Option<PathBuf>,
 
+    /// Read only those files of a directory or members of an archive,
+    /// which match the given glob pattern (e.g. "*.dat.gz")
+    #[arg(long, global = true, value_name = "PATTERN")]
+    pub(crate) glob: Option<String>,
+
+    /// The character encoding of the input (utf-8, iso-8859-1 or
+    /// windows-1252). Records are transcoded to UTF-8; records that
+    /// can't be decoded unambiguously are treated as invalid.
+    #[arg(long, global = true, value_name = "ENCODING")]
+    pub(crate) encoding: Option<Encoding>,
+
+    /// Detect the format of inputs without a known file extension
+    /// (e.g. PICA-XML on stdin) instead of reading normalized PICA+
+    #[arg(long, global = true)]
+    pub(crate) detect_format: bool,
+
     #[command(subcommand)]
     pub(crate) cmd: Command,
 }
@@ -30,13 +47,20 @@ pub(crate) enum Command {
     Convert(Box<Convert>),
     Count(Box<Count>),
     Describe(Box<Describe>),
+    Diff(Box<Diff>),
     Explode(Box<Explode>),
     Filter(Box<Filter>),
     Frequency(Box<Frequency>),
     Hash(Box<Hash>),
+    Index(Box<Index>),
     Invalid(Box<Invalid>),
+    Lookup(Box<Lookup>),
+    Merge3(Box<Merge3>),
+    Normalize(Box<Normalize>),
     Partition(Box<Partition>),
+    Patch(Box<Patch>),
     Print(Box<Print>),
+    Route(Box<Route>),
     Sample(Box<Sample>),
     Select(Box<Select>),
     Slice(Box<Slice>),
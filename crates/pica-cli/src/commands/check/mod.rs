@@ -34,6 +34,11 @@ pub(crate) struct Check {
     #[arg(long, short)]
     tags: Option<String>,
 
+    /// Add the columns `source`, `index` and `offset` to the CSV/TSV
+    /// output, which point to the location of the checked record.
+    #[arg(long)]
+    provenance: bool,
+
     /// Write output to FILENAME instead of stdout
     #[arg(short, long, value_name = "FILENAME")]
     output: Option<PathBuf>,
@@ -51,14 +56,14 @@ impl Check {
         let skip_invalid =
             self.filter_opts.skip_invalid || config.skip_invalid;
         let mut progress = Progress::new(self.progress);
-        let mut writer = Writer::from_path(self.output)?;
+        let mut writer =
+            Writer::from_path(self.output, self.provenance)?;
         let mut count = 0;
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
         let options = MatcherOptions::from(&self.filter_opts);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
         let mut rulesets = self
             .rules
@@ -101,7 +106,7 @@ impl Check {
 
         'outer: for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
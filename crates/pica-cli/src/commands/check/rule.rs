@@ -74,6 +74,7 @@ impl Rule {
         if result {
             writer.write_record(Record {
                 ppn: record.ppn(),
+                provenance: record.provenance().copied(),
                 rule: &self.id,
                 level: &self.level,
                 message,
@@ -98,13 +99,14 @@ impl Rule {
                 writer.write_record(Record {
                     rule: &self.id,
                     level: &self.level,
+                    provenance: None,
                     message,
                     ppn,
                 })?;
             }
         }
 
-        writer.finish()?;
+        writer.flush()?;
         Ok(())
     }
 }
@@ -1,10 +1,10 @@
-use std::fs::File;
-use std::io::{BufWriter, Write, stdout};
 use std::path::PathBuf;
 
 use bstr::BStr;
+use pica_record::Provenance;
 
 use super::rule::Level;
+use crate::output::{Output, has_extension};
 
 #[derive(Debug, serde::Serialize)]
 pub(crate) struct Record<'a, 'b> {
@@ -12,6 +12,37 @@ pub(crate) struct Record<'a, 'b> {
     pub(crate) rule: &'b str,
     pub(crate) level: &'b Level,
     pub(crate) message: Option<String>,
+    #[serde(skip)]
+    pub(crate) provenance: Option<Provenance<'a>>,
+}
+
+/// A [Record] with the additional provenance columns (`source`,
+/// `index` and `offset`) of the checked record.
+#[derive(Debug, serde::Serialize)]
+struct ProvenanceRecord<'a, 'b> {
+    ppn: Option<&'a BStr>,
+    rule: &'b str,
+    level: &'b Level,
+    message: Option<String>,
+    source: Option<&'a str>,
+    index: Option<usize>,
+    offset: Option<u64>,
+}
+
+impl<'a, 'b> From<Record<'a, 'b>> for ProvenanceRecord<'a, 'b> {
+    fn from(record: Record<'a, 'b>) -> Self {
+        let provenance = record.provenance;
+
+        Self {
+            ppn: record.ppn,
+            rule: record.rule,
+            level: record.level,
+            message: record.message,
+            source: provenance.and_then(|p| p.source()),
+            index: provenance.map(|p| p.index()),
+            offset: provenance.and_then(|p| p.offset()),
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -23,51 +54,63 @@ pub(crate) enum Error {
 }
 
 pub(crate) struct CsvWriter {
-    inner: csv::Writer<Box<dyn Write>>,
+    inner: csv::Writer<Output>,
+    provenance: bool,
 }
 
 impl CsvWriter {
     pub(crate) fn from_path(
         path: Option<PathBuf>,
         delimiter: u8,
+        provenance: bool,
     ) -> Result<Self, Error> {
-        let wtr: Box<dyn Write> = match path {
-            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
-            None => Box::new(stdout().lock()),
-        };
+        let wtr = Output::from_path_or_stdout(path, false)?;
 
         let wtr = csv::WriterBuilder::new()
             .delimiter(delimiter)
             .has_headers(true)
             .from_writer(wtr);
 
-        Ok(Self { inner: wtr })
+        Ok(Self {
+            inner: wtr,
+            provenance,
+        })
     }
 
     pub(crate) fn write_record(
         &mut self,
         record: Record,
     ) -> Result<(), Error> {
-        Ok(self.inner.serialize(record)?)
+        if self.provenance {
+            let record = ProvenanceRecord::from(record);
+            Ok(self.inner.serialize(record)?)
+        } else {
+            Ok(self.inner.serialize(record)?)
+        }
     }
 
-    pub(crate) fn finish(&mut self) -> Result<(), Error> {
+    pub(crate) fn flush(&mut self) -> Result<(), Error> {
         Ok(self.inner.flush()?)
     }
+
+    pub(crate) fn finish(self) -> Result<(), Error> {
+        let output = self
+            .inner
+            .into_inner()
+            .map_err(csv::IntoInnerError::into_error)?;
+        Ok(output.finish()?)
+    }
 }
 
 pub(crate) struct TxtWriter {
-    inner: csv::Writer<Box<dyn Write>>,
+    inner: csv::Writer<Output>,
 }
 
 impl TxtWriter {
     pub(crate) fn from_path(
         path: Option<PathBuf>,
     ) -> Result<Self, Error> {
-        let wtr: Box<dyn Write> = match path {
-            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
-            None => Box::new(stdout().lock()),
-        };
+        let wtr = Output::from_path_or_stdout(path, false)?;
 
         let wtr = csv::WriterBuilder::new()
             .has_headers(false)
@@ -84,9 +127,17 @@ impl TxtWriter {
         Ok(self.inner.write_record([ppn])?)
     }
 
-    pub(crate) fn finish(&mut self) -> Result<(), Error> {
+    pub(crate) fn flush(&mut self) -> Result<(), Error> {
         Ok(self.inner.flush()?)
     }
+
+    pub(crate) fn finish(self) -> Result<(), Error> {
+        let output = self
+            .inner
+            .into_inner()
+            .map_err(csv::IntoInnerError::into_error)?;
+        Ok(output.finish()?)
+    }
 }
 
 pub(crate) enum Writer {
@@ -95,21 +146,23 @@ pub(crate) enum Writer {
 }
 
 impl Writer {
+    /// Creates a new writer. The provenance columns are only written
+    /// to CSV/TSV outputs.
     pub(crate) fn from_path(
         path: Option<PathBuf>,
+        provenance: bool,
     ) -> Result<Self, Error> {
-        let path_str = if let Some(ref path) = path {
-            path.to_str().unwrap_or_default()
-        } else {
-            ""
-        };
+        let ext =
+            |ext| path.as_ref().is_some_and(|p| has_extension(p, ext));
 
-        if path_str.ends_with(".txt") {
+        if ext("txt") {
             Ok(Self::Txt(TxtWriter::from_path(path)?))
-        } else if path_str.ends_with(".tsv") {
-            Ok(Self::Csv(CsvWriter::from_path(path, b'\t')?))
+        } else if ext("tsv") {
+            Ok(Self::Csv(CsvWriter::from_path(
+                path, b'\t', provenance,
+            )?))
         } else {
-            Ok(Self::Csv(CsvWriter::from_path(path, b',')?))
+            Ok(Self::Csv(CsvWriter::from_path(path, b',', provenance)?))
         }
     }
 
@@ -123,7 +176,14 @@ impl Writer {
         }
     }
 
-    pub(crate) fn finish(&mut self) -> Result<(), Error> {
+    pub(crate) fn flush(&mut self) -> Result<(), Error> {
+        match self {
+            Self::Csv(wtr) => wtr.flush(),
+            Self::Txt(wtr) => wtr.flush(),
+        }
+    }
+
+    pub(crate) fn finish(self) -> Result<(), Error> {
         match self {
             Self::Csv(wtr) => wtr.finish(),
             Self::Txt(wtr) => wtr.finish(),
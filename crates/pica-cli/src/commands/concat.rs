@@ -107,7 +107,7 @@ impl Concat {
 
         for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
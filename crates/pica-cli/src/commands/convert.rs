@@ -1,22 +1,17 @@
 use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Write, stdout};
 use std::process::ExitCode;
 
 use clap::{Parser, ValueEnum};
+use pica_record::io::{
+    BinaryWriter, ImportWriter, InputFormat, JsonWriter,
+    PlainTextWriter, XmlWriter,
+};
 use pica_record::prelude::*;
 
-use self::binary::BinaryWriter;
-use self::import::ImportWriter;
-use self::json::JsonWriter;
-use self::plain::PlainWriter;
-use self::xml::XmlWriter;
 use crate::prelude::*;
 
-mod binary;
-mod import;
-mod json;
-mod plain;
-mod xml;
-
 #[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
 enum Format {
     Binary,
@@ -27,6 +22,26 @@ enum Format {
     Xml,
 }
 
+fn writer(output: Option<OsString>) -> io::Result<Box<dyn Write>> {
+    Ok(match output {
+        Some(filename) => Box::new(File::create(filename)?),
+        None => Box::new(stdout().lock()),
+    })
+}
+
+impl From<Format> for InputFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Binary => Self::Binary,
+            Format::Import => Self::Import,
+            Format::Json => Self::Json,
+            Format::Plain => Self::Plain,
+            Format::Plus => Self::Plus,
+            Format::Xml => Self::Xml,
+        }
+    }
+}
+
 /// Convert PICA+ into other formats.
 #[derive(Parser, Debug)]
 pub(crate) struct Convert {
@@ -34,16 +49,11 @@ pub(crate) struct Convert {
     #[arg(short, long)]
     skip_invalid: bool,
 
-    /// The input format. Currently only PICA+ is supported.
-    #[arg(
-        short,
-        long,
-        value_enum,
-        default_value = "plus",
-        hide_default_value = true,
-        value_name = "FORMAT"
-    )]
-    from: Format,
+    /// The input format. If not set, the format is derived from the
+    /// file extension; other inputs are read as normalized PICA+,
+    /// unless `--detect-format` is set.
+    #[arg(short, long, value_enum, value_name = "FORMAT")]
+    from: Option<Format>,
 
     /// The output format.
     #[arg(
@@ -74,27 +84,34 @@ impl Convert {
         let skip_invalid = self.skip_invalid || config.skip_invalid;
         let mut progress = Progress::new(self.progress);
 
-        if self.from != Format::Plus {
-            return Err(CliError::Other(format!(
-                "convert from {:?} is not supported",
-                self.from
-            )));
+        let mut builder = config.reader_builder();
+        if let Some(format) = self.from {
+            builder = builder.format(format.into());
         }
 
         let mut writer: Box<dyn ByteRecordWrite> = match self.to {
             Format::Plus => {
                 WriterBuilder::new().from_path_or_stdout(self.output)?
             }
-            Format::Binary => Box::new(BinaryWriter::new(self.output)?),
-            Format::Import => Box::new(ImportWriter::new(self.output)?),
-            Format::Json => Box::new(JsonWriter::new(self.output)?),
-            Format::Plain => Box::new(PlainWriter::new(self.output)?),
-            Format::Xml => Box::new(XmlWriter::new(self.output)?),
+            Format::Binary => {
+                Box::new(BinaryWriter::new(writer(self.output)?))
+            }
+            Format::Import => {
+                Box::new(ImportWriter::new(writer(self.output)?))
+            }
+            Format::Json => {
+                Box::new(JsonWriter::new(writer(self.output)?)?)
+            }
+            Format::Plain => {
+                Box::new(PlainTextWriter::new(writer(self.output)?))
+            }
+            Format::Xml => {
+                Box::new(XmlWriter::new(writer(self.output)?)?)
+            }
         };
 
         for filename in self.filenames {
-            let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+            let mut reader = builder.from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
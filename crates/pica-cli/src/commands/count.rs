@@ -1,6 +1,5 @@
 use std::ffi::OsString;
-use std::fs::OpenOptions;
-use std::io::{self, Write};
+use std::io::Write;
 use std::process::ExitCode;
 
 use clap::Parser;
@@ -64,23 +63,13 @@ impl Count {
             self.filter_opts.skip_invalid || config.skip_invalid;
         let mut progress = Progress::new(self.progress);
 
-        let mut writer: Box<dyn Write> = match self.output {
-            Some(path) => Box::new(
-                OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .truncate(!self.append)
-                    .append(self.append)
-                    .open(path)?,
-            ),
-            None => Box::new(io::stdout().lock()),
-        };
+        let mut writer =
+            Output::from_path_or_stdout(self.output, self.append)?;
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
         let options = MatcherOptions::from(&self.filter_opts);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
         let mut records = 0;
         let mut fields = 0;
@@ -88,7 +77,7 @@ impl Count {
 
         for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
@@ -145,7 +134,7 @@ impl Count {
         }
 
         progress.finish();
-        writer.flush()?;
+        writer.finish()?;
 
         Ok(ExitCode::SUCCESS)
     }
@@ -1,5 +1,4 @@
 use std::ffi::OsString;
-use std::fs::File;
 use std::process::ExitCode;
 
 use clap::Parser;
@@ -7,6 +6,7 @@ use hashbrown::HashMap;
 use pica_record::prelude::*;
 use polars::prelude::*;
 
+use crate::output::has_extension;
 use crate::prelude::*;
 
 /// Creates a frequency table of all subfield codes.
@@ -56,16 +56,15 @@ impl Describe {
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
         let options = MatcherOptions::from(&self.filter_opts);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
         let mut fields: HashMap<String, HashMap<char, usize>> =
             HashMap::new();
 
         'outer: for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(mut result) = reader.next_byte_record() {
                 match result {
@@ -166,27 +165,29 @@ impl Describe {
 
                 println!("{df}");
             }
-            Some(path)
-                if path.to_string_lossy().ends_with(".tsv")
-                    || self.tsv =>
-            {
-                let mut writer = CsvWriter::new(File::create(path)?)
-                    .with_separator(b'\t');
-                writer.finish(&mut df)?;
+            Some(path) if has_extension(&path, "tsv") || self.tsv => {
+                let mut output =
+                    Output::from_path_or_stdout(Some(path), false)?;
+                CsvWriter::new(&mut output)
+                    .with_separator(b'\t')
+                    .finish(&mut df)?;
+                output.finish()?;
             }
-            Some(path)
-                if path.to_string_lossy().ends_with(".csv")
-                    || self.csv =>
-            {
-                let mut writer = CsvWriter::new(File::create(path)?);
-                writer.finish(&mut df)?;
+            Some(path) if has_extension(&path, "csv") || self.csv => {
+                let mut output =
+                    Output::from_path_or_stdout(Some(path), false)?;
+                CsvWriter::new(&mut output).finish(&mut df)?;
+                output.finish()?;
             }
             Some(path) => {
-                let mut writer = IpcWriter::new(File::create(path)?)
+                let mut output =
+                    Output::from_path_or_stdout(Some(path), false)?;
+                IpcWriter::new(&mut output)
                     .with_compression(Some(IpcCompression::ZSTD(
                         Default::default(),
-                    )));
-                writer.finish(&mut df)?;
+                    )))
+                    .finish(&mut df)?;
+                output.finish()?;
             }
         }
 
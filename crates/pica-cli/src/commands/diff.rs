@@ -0,0 +1,490 @@
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use bstr::{BStr, BString, ByteSlice};
+use clap::{Parser, ValueEnum};
+use hashbrown::{HashMap, HashSet};
+use pica_record::diff::{FieldChange, SubfieldChange};
+use pica_record::prelude::*;
+use pica_record::primitives::{FieldRef, RecordRef};
+use serde_json::{Value, json};
+
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum DiffFormat {
+    #[default]
+    Text,
+    Csv,
+    Tsv,
+    Json,
+}
+
+/// Compare two sets of records
+///
+/// The diff command joins the records of OLD and NEW by a key (by
+/// default the PPN) and reports all records, which were added, deleted
+/// or modified. For modified records, the added, removed and modified
+/// fields and subfields are listed. Records without a key are ignored.
+#[derive(Parser, Debug)]
+pub(crate) struct Diff {
+    /// Skip invalid records that can't be decoded
+    #[arg(short, long)]
+    skip_invalid: bool,
+
+    /// The path expression of the key, which is used to join the
+    /// records of both inputs. If a record has more than one key
+    /// value, the first value is used.
+    #[arg(long, short, default_value = "003@.0")]
+    key: String,
+
+    /// The output format (possible values: "text", "csv", "tsv" and
+    /// "json").
+    #[arg(long, short, value_enum, default_value_t)]
+    format: DiffFormat,
+
+    /// Show progress bar (requires `-o`/`--output`).
+    #[arg(short, long, requires = "output")]
+    progress: bool,
+
+    /// Write output to FILENAME instead of stdout
+    #[arg(short, long, value_name = "FILENAME")]
+    output: Option<OsString>,
+
+    /// The file with the old versions of the records
+    old: OsString,
+
+    /// The file with the new versions of the records
+    new: OsString,
+}
+
+impl Diff {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid = self.skip_invalid || config.skip_invalid;
+        let mut progress = Progress::new(self.progress);
+        let path = Path::new(&self.key)?;
+        let options = MatcherOptions::default();
+
+        let (order, mut old) = read_records(
+            config,
+            self.old,
+            &path,
+            skip_invalid,
+            &mut progress,
+            "old",
+        )?;
+
+        let mut report = Report::new(
+            Output::from_path_or_stdout(self.output, false)?,
+            self.format,
+        )?;
+
+        let mut seen = HashSet::new();
+        let mut reader =
+            config.reader_builder().from_sources(self.new)?;
+        while let Some(result) = reader.next_byte_record() {
+            match result {
+                Err(e) if e.skip_parse_err(skip_invalid) => {
+                    progress.update(true);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+                Ok(ref record) => {
+                    progress.update(false);
+
+                    let Some(key) = record.first(&path, &options)
+                    else {
+                        continue;
+                    };
+
+                    if !seen.insert(key.to_owned()) {
+                        bail!("duplicate key '{key}' in new records");
+                    }
+
+                    match old.remove(key) {
+                        None => report.added(key)?,
+                        Some(data) => {
+                            // SAFETY: The data was written from a valid
+                            // record, so it's safe to call unwrap.
+                            let old =
+                                RecordRef::from_bytes(&data).unwrap();
+                            let changes = old.diff(record);
+                            if !changes.is_empty() {
+                                report.modified(key, &changes)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for key in order.iter() {
+            if old.contains_key(key) {
+                report.deleted(key.as_bstr())?;
+            }
+        }
+
+        progress.finish();
+        report.finish()?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// The records of an input in PICA+, indexed by key.
+pub(crate) type Records = HashMap<BString, Vec<u8>>;
+
+/// Reads the records of an input and indexes them by key.
+///
+/// Records without a key are ignored. Besides the records, the keys
+/// are returned in the order of the input. It's an error if the input
+/// contains more than one record with the same key; the `name` of the
+/// input is used in the error message.
+pub(crate) fn read_records(
+    config: &Config,
+    filename: OsString,
+    path: &Path,
+    skip_invalid: bool,
+    progress: &mut Progress,
+    name: &str,
+) -> Result<(Vec<BString>, Records), CliError> {
+    let options = MatcherOptions::default();
+    let mut order: Vec<BString> = vec![];
+    let mut records = Records::new();
+
+    let mut reader = config.reader_builder().from_sources(filename)?;
+    while let Some(result) = reader.next_byte_record() {
+        match result {
+            Err(e) if e.skip_parse_err(skip_invalid) => {
+                progress.update(true);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+            Ok(ref record) => {
+                progress.update(false);
+
+                let Some(key) = record.first(path, &options) else {
+                    continue;
+                };
+
+                if records.contains_key(key) {
+                    bail!("duplicate key '{key}' in {name} records");
+                }
+
+                let mut data = vec![];
+                record.write_to(&mut data)?;
+                order.push(key.to_owned());
+                records.insert(key.to_owned(), data);
+            }
+        }
+    }
+
+    Ok((order, records))
+}
+
+/// A writer for the diff report.
+enum Report {
+    Text(Output),
+    Csv(Box<csv::Writer<Output>>),
+    Json(Output, bool),
+}
+
+impl Report {
+    fn new(output: Output, format: DiffFormat) -> io::Result<Self> {
+        let delimiter = match format {
+            DiffFormat::Text => return Ok(Self::Text(output)),
+            DiffFormat::Json => return Ok(Self::Json(output, true)),
+            DiffFormat::Csv => b',',
+            DiffFormat::Tsv => b'\t',
+        };
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(output);
+        writer.write_record([
+            "key", "change", "field", "code", "old", "new",
+        ])?;
+
+        Ok(Self::Csv(Box::new(writer)))
+    }
+
+    fn added(&mut self, key: &BStr) -> io::Result<()> {
+        self.record(key, "added", "+")
+    }
+
+    fn deleted(&mut self, key: &BStr) -> io::Result<()> {
+        self.record(key, "deleted", "-")
+    }
+
+    fn record(
+        &mut self,
+        key: &BStr,
+        change: &str,
+        sign: &str,
+    ) -> io::Result<()> {
+        match self {
+            Self::Text(out) => writeln!(out, "{sign} {key}"),
+            Self::Csv(writer) => Ok(writer.write_record([
+                &key.to_string(),
+                change,
+                "",
+                "",
+                "",
+                "",
+            ])?),
+            Self::Json(..) => self.write_json(json!({
+                "key": key.to_string(),
+                "change": change,
+            })),
+        }
+    }
+
+    fn modified(
+        &mut self,
+        key: &BStr,
+        changes: &[FieldChange],
+    ) -> io::Result<()> {
+        let key = key.to_string();
+
+        match self {
+            Self::Text(out) => {
+                writeln!(out, "~ {key}")?;
+                for change in changes {
+                    match change {
+                        FieldChange::Added(field) => {
+                            writeln!(out, "  + {}", plain(field))?;
+                        }
+                        FieldChange::Removed(field) => {
+                            writeln!(out, "  - {}", plain(field))?;
+                        }
+                        FieldChange::Reordered { old, new } => {
+                            writeln!(
+                                out,
+                                "  ~ {} {} -> {}",
+                                label(new),
+                                plain_subfields(old),
+                                plain_subfields(new)
+                            )?;
+                        }
+                        FieldChange::Modified {
+                            new, changes, ..
+                        } => {
+                            for change in changes {
+                                let (kind, code, old, new_value) =
+                                    parts(change);
+                                let value = match kind {
+                                    "added" => format!("+ {new_value}"),
+                                    "deleted" => format!("- {old}"),
+                                    _ => {
+                                        format!("{old} -> {new_value}")
+                                    }
+                                };
+
+                                writeln!(
+                                    out,
+                                    "  ~ {} ${code} {value}",
+                                    label(new)
+                                )?;
+                            }
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+            Self::Csv(writer) => {
+                for change in changes {
+                    match change {
+                        FieldChange::Added(field) => writer
+                            .write_record([
+                                key.as_str(),
+                                "added",
+                                &label(field),
+                                "",
+                                "",
+                                &plain_subfields(field),
+                            ])?,
+                        FieldChange::Removed(field) => writer
+                            .write_record([
+                                key.as_str(),
+                                "deleted",
+                                &label(field),
+                                "",
+                                &plain_subfields(field),
+                                "",
+                            ])?,
+                        FieldChange::Reordered { old, new } => {
+                            writer.write_record([
+                                key.as_str(),
+                                "reordered",
+                                &label(new),
+                                "",
+                                &plain_subfields(old),
+                                &plain_subfields(new),
+                            ])?
+                        }
+                        FieldChange::Modified {
+                            new, changes, ..
+                        } => {
+                            for change in changes {
+                                let (kind, code, old, new_value) =
+                                    parts(change);
+                                writer.write_record([
+                                    key.as_str(),
+                                    kind,
+                                    &label(new),
+                                    &code,
+                                    &old,
+                                    &new_value,
+                                ])?;
+                            }
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+            Self::Json(..) => {
+                let fields: Vec<Value> = changes
+                    .iter()
+                    .map(|change| match change {
+                        FieldChange::Added(field) => json!({
+                            "field": label(field),
+                            "change": "added",
+                            "new": plain_subfields(field),
+                        }),
+                        FieldChange::Removed(field) => json!({
+                            "field": label(field),
+                            "change": "deleted",
+                            "old": plain_subfields(field),
+                        }),
+                        FieldChange::Reordered { old, new } => json!({
+                            "field": label(new),
+                            "change": "reordered",
+                            "old": plain_subfields(old),
+                            "new": plain_subfields(new),
+                        }),
+                        FieldChange::Modified {
+                            new, changes, ..
+                        } => {
+                            let subfields: Vec<Value> = changes
+                                .iter()
+                                .map(|change| {
+                                    let (kind, code, old, new) =
+                                        parts(change);
+                                    let mut value = json!({
+                                        "code": code,
+                                        "change": kind,
+                                    });
+
+                                    if kind != "added" {
+                                        value["old"] = old.into();
+                                    }
+
+                                    if kind != "deleted" {
+                                        value["new"] = new.into();
+                                    }
+
+                                    value
+                                })
+                                .collect();
+
+                            json!({
+                                "field": label(new),
+                                "change": "modified",
+                                "subfields": subfields,
+                            })
+                        }
+                    })
+                    .collect();
+
+                self.write_json(json!({
+                    "key": key,
+                    "change": "modified",
+                    "fields": fields,
+                }))
+            }
+        }
+    }
+
+    fn write_json(&mut self, value: Value) -> io::Result<()> {
+        let Self::Json(out, first) = self else {
+            unreachable!()
+        };
+
+        out.write_all(if *first { b"[" } else { b"," })?;
+        serde_json::to_writer(&mut *out, &value)?;
+        *first = false;
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Text(out) => out.finish(),
+            Self::Csv(writer) => (*writer)
+                .into_inner()
+                .map_err(csv::IntoInnerError::into_error)?
+                .finish(),
+            Self::Json(mut out, first) => {
+                out.write_all(if first { b"[]\n" } else { b"]\n" })?;
+                out.finish()
+            }
+        }
+    }
+}
+
+/// Returns the kind of change, the subfield code, the old and the new
+/// value of a subfield change.
+fn parts(
+    change: &SubfieldChange,
+) -> (&'static str, String, String, String) {
+    match change {
+        SubfieldChange::Added(new) => (
+            "added",
+            new.code().to_string(),
+            String::new(),
+            new.value().to_str_lossy().to_string(),
+        ),
+        SubfieldChange::Removed(old) => (
+            "deleted",
+            old.code().to_string(),
+            old.value().to_str_lossy().to_string(),
+            String::new(),
+        ),
+        SubfieldChange::Modified { old, new } => (
+            "modified",
+            new.code().to_string(),
+            old.value().to_str_lossy().to_string(),
+            new.value().to_str_lossy().to_string(),
+        ),
+    }
+}
+
+/// Returns the tag and occurrence of a field (e.g. `041A/01`).
+pub(crate) fn label(field: &FieldRef) -> String {
+    match field.occurrence() {
+        Some(occ) => format!("{}/{occ}", field.tag()),
+        None => field.tag().to_string(),
+    }
+}
+
+/// Returns the subfields of a field in PICA-Plain (e.g. `$aX$bY`).
+pub(crate) fn plain_subfields(field: &FieldRef) -> String {
+    field
+        .subfields()
+        .iter()
+        .map(|subfield| {
+            format!(
+                "${}{}",
+                subfield.code(),
+                subfield.value().replace("$", "$$").to_str_lossy()
+            )
+        })
+        .collect()
+}
+
+/// Returns the field in PICA-Plain (e.g. `041A/01 $aX$bY`).
+fn plain(field: &FieldRef) -> String {
+    format!("{} {}", label(field), plain_subfields(field))
+}
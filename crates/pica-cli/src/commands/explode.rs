@@ -43,27 +43,6 @@ pub(crate) struct Explode {
     pub(crate) filter_opts: FilterOpts,
 }
 
-macro_rules! push_record {
-    ($records:expr, $main:expr, $local:expr, $acc:expr) => {
-        if !$acc.is_empty() {
-            let mut record = $main.clone();
-            if !$local.is_empty() {
-                record.extend_from_slice(&$local);
-            }
-            record.extend_from_slice(&$acc);
-            $records.push(record);
-        }
-    };
-
-    ($records:expr, $main:expr, $acc:expr) => {
-        if !$acc.is_empty() {
-            let mut record = $main.clone();
-            record.extend_from_slice(&$acc);
-            $records.push(record);
-        }
-    };
-}
-
 #[inline(always)]
 fn process_main<'a>(
     record: &'a ByteRecord<'a>,
@@ -74,70 +53,34 @@ fn process_main<'a>(
 fn process_local<'a>(
     record: &'a ByteRecord<'a>,
 ) -> Vec<Vec<&'a FieldRef<'a>>> {
-    let mut iter = record.fields().iter().peekable();
-    let mut records = vec![];
-    let mut main = vec![];
-    let mut acc = vec![];
-
-    while let Some(cur) = iter.next() {
-        match cur.level() {
-            Level::Main => main.push(cur),
-            Level::Local => acc.push(cur),
-            Level::Copy => {
-                acc.push(cur);
-
-                if let Some(next) = iter.peek()
-                    && next.level() == Level::Local
-                {
-                    push_record!(records, main, acc);
-                    acc.clear();
-                }
-            }
-        }
-    }
-
-    push_record!(records, main, acc);
-    records
+    record
+        .locals()
+        .map(|local| {
+            record
+                .main()
+                .chain(local.fields().iter().copied())
+                .collect()
+        })
+        .collect()
 }
 
 fn process_copy<'a>(
     record: &'a ByteRecord<'a>,
 ) -> Vec<Vec<&'a FieldRef<'a>>> {
-    let mut iter = record.fields().iter().peekable();
     let mut records = vec![];
-    let mut main = vec![];
-    let mut local = vec![];
-    let mut copy = vec![];
-    let mut count = None;
-
-    while let Some(cur) = iter.next() {
-        match cur.level() {
-            Level::Main => main.push(cur),
-            Level::Local => {
-                local.push(cur);
-            }
-            Level::Copy => {
-                if count != cur.occurrence() {
-                    push_record!(records, main, local, copy);
-                    count = cur.occurrence();
-                    copy.clear();
-                }
-
-                copy.push(cur);
 
-                if let Some(next) = iter.peek()
-                    && next.level() == Level::Local
-                {
-                    push_record!(records, main, local, copy);
-                    count = None;
-                    local.clear();
-                    copy.clear();
-                }
-            }
+    for local in record.locals() {
+        for copy in local.copies() {
+            records.push(
+                record
+                    .main()
+                    .chain(local.local_fields())
+                    .chain(copy.fields().iter().copied())
+                    .collect(),
+            );
         }
     }
 
-    push_record!(records, main, local, copy);
     records
 }
 
@@ -158,9 +101,8 @@ impl Explode {
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
         let options = MatcherOptions::from(&self.filter_opts);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
         let process = match self.level {
             Level::Main => process_main,
@@ -169,7 +111,8 @@ impl Explode {
         };
 
         'outer: for path in self.filenames {
-            let mut reader = ReaderBuilder::new().from_path(path)?;
+            let mut reader =
+                config.reader_builder().from_sources(path)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
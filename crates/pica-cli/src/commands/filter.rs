@@ -128,9 +128,7 @@ pub(crate) struct Filter {
     gzip: bool,
 
     /// Append to the given file, do not overwrite
-    ///
-    /// Warning: This option can't be used when writing to a gzip file.
-    #[arg(long, conflicts_with = "gzip")]
+    #[arg(long)]
     append: bool,
 
     /// Write simultaneously to the file FILENAME and stdout
@@ -161,7 +159,7 @@ impl Filter {
     pub(crate) fn execute(self, config: &Config) -> CliResult {
         let skip_invalid = self.skip_invalid || config.skip_invalid;
         let mut progress = Progress::new(self.progress);
-        let translit = translit(config.normalization.clone());
+        let translit = translit(config.normalization);
         let discard = parse_predicates(self.discard)?;
         let keep = parse_predicates(self.keep)?;
 
@@ -220,7 +218,8 @@ impl Filter {
         let mut count = 0;
 
         'outer: for path in filenames {
-            let mut reader = ReaderBuilder::new().from_path(path)?;
+            let mut reader =
+                config.reader_builder().from_sources(path)?;
 
             while let Some(mut result) = reader.next_byte_record() {
                 match result {
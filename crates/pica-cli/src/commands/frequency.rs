@@ -1,7 +1,5 @@
 use std::cmp::Ordering;
 use std::ffi::OsString;
-use std::fs::File;
-use std::io::{self, Write};
 use std::process::ExitCode;
 
 use bstr::BString;
@@ -116,12 +114,10 @@ impl Frequency {
         let mut seen = HashSet::new();
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
-        let translit =
-            crate::translit::translit(config.normalization.clone());
+        let translit = crate::translit::translit(config.normalization);
         let query = Query::new(translit(self.query))?;
 
         let mut ftable: HashMap<Vec<BString>, u64> = HashMap::new();
@@ -136,19 +132,18 @@ impl Frequency {
             .merge(self.merge);
 
         let matcher_options = MatcherOptions::from(&options);
-        let writer: Box<dyn Write> = match self.output {
-            Some(filename) => Box::new(File::create(filename)?),
-            None => Box::new(io::stdout()),
-        };
-
         let mut writer = csv::WriterBuilder::new()
             .delimiter(if self.tsv { b'\t' } else { b',' })
-            .from_writer(writer);
+            .from_writer(Output::from_path_or_stdout(
+                self.output,
+                false,
+            )?);
 
         let mut count = 0;
 
         'outer: for path in self.filenames {
-            let mut reader = ReaderBuilder::new().from_path(path)?;
+            let mut reader =
+                config.reader_builder().from_sources(path)?;
             while let Some(result) = reader.next_byte_record() {
                 match result {
                     Err(e) if e.skip_parse_err(skip_invalid) => {
@@ -215,7 +210,7 @@ impl Frequency {
             });
         }
 
-        let translit = crate::translit::translit(self.nf.clone());
+        let translit = crate::translit::translit(self.nf);
         for (i, (values, freq)) in ftable_sorted.iter().enumerate() {
             if self.num > 0 && i >= self.num {
                 break;
@@ -236,7 +231,10 @@ impl Frequency {
         }
 
         progress.finish();
-        writer.flush()?;
+        writer
+            .into_inner()
+            .map_err(csv::IntoInnerError::into_error)?
+            .finish()?;
 
         Ok(ExitCode::SUCCESS)
     }
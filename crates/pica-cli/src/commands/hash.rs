@@ -1,7 +1,5 @@
 use std::ffi::OsString;
 use std::fmt::Write as _;
-use std::fs::File;
-use std::io::{self, Write};
 use std::process::ExitCode;
 
 use clap::Parser;
@@ -11,8 +9,28 @@ use pica_record::prelude::*;
 use crate::prelude::*;
 
 /// Compute SHA-256 checksum of records.
+///
+/// By default, the checksum covers the whole record. Volatile fields
+/// (e.g. the date of the last change 001B) can be excluded from the
+/// checksum by the `--keep` and `--discard` options. With the
+/// `--canonical` flag, the record is normalized (see the normalize
+/// command) before the checksum is computed, so that the order of the
+/// fields and whitespace don't change the checksum.
 #[derive(Parser, Debug)]
 pub(crate) struct Hash {
+    /// Hash only fields specified by a list of predicates.
+    #[arg(long, short)]
+    keep: Option<String>,
+
+    /// Exclude fields specified by a list of predicates from the hash.
+    #[arg(long, short)]
+    discard: Option<String>,
+
+    /// Normalize the record and sort the fields into canonical order
+    /// before computing the hash.
+    #[arg(long)]
+    canonical: bool,
+
     /// Comma-separated list of column names.
     #[arg(long, short = 'H', default_value = "ppn,hash")]
     header: String,
@@ -46,26 +64,30 @@ impl Hash {
         let mut progress = Progress::new(self.progress);
         let mut count = 0;
 
+        let nf = config.normalization.unwrap_or_default();
+        let hash_options = HashOptions::new()
+            .keep(parse_predicates(self.keep)?)
+            .discard(parse_predicates(self.discard)?)
+            .canonical(self.canonical)
+            .normalize(self.canonical.then_some(nf));
+
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
         let options = MatcherOptions::from(&self.filter_opts);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
-
-        let writer: Box<dyn Write> = match self.output {
-            Some(filename) => Box::new(File::create(filename)?),
-            None => Box::new(io::stdout()),
-        };
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
         let mut writer = WriterBuilder::new()
             .delimiter(if self.tsv { b'\t' } else { b',' })
-            .from_writer(writer);
+            .from_writer(Output::from_path_or_stdout(
+                self.output,
+                false,
+            )?);
 
         writer.write_record(self.header.split(',').map(str::trim))?;
 
         'outer: for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
@@ -87,7 +109,9 @@ impl Hash {
                             continue;
                         }
 
-                        let hash = record.sha256().iter().fold(
+                        let hash = record.sha256_with(&hash_options);
+
+                        let hash = hash.iter().fold(
                             String::new(),
                             |mut out, b| {
                                 let _ = write!(out, "{b:02x}");
@@ -115,7 +139,10 @@ impl Hash {
         }
 
         progress.finish();
-        writer.flush()?;
+        writer
+            .into_inner()
+            .map_err(csv::IntoInnerError::into_error)?
+            .finish()?;
 
         Ok(ExitCode::SUCCESS)
     }
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use pica_record::io::IndexBuilder;
+use pica_record::prelude::*;
+
+use crate::prelude::*;
+
+/// Build an index of the records of a file
+///
+/// The index command creates an index file, which maps the values of a
+/// path expression (by default the PPN) to the position of the records
+/// within FILENAME. The index is used by the `lookup` command to read
+/// single records without scanning the whole file. Only uncompressed,
+/// gzip and BGZF compressed files can be indexed.
+#[derive(Parser, Debug)]
+pub(crate) struct Index {
+    /// Skip invalid records that can't be decoded
+    #[arg(short, long)]
+    skip_invalid: bool,
+
+    /// A path expression, whose values are used as keys
+    #[arg(long, short, default_value = "003@.0")]
+    path: Path,
+
+    /// Write the index to FILENAME instead of `<FILENAME>.idx`
+    #[arg(short, long, value_name = "FILENAME")]
+    output: Option<PathBuf>,
+
+    /// A file in normalized PICA+ format
+    filename: PathBuf,
+}
+
+impl Index {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid = self.skip_invalid || config.skip_invalid;
+        let output = self.output.unwrap_or_else(|| {
+            pica_record::io::Index::sidecar(&self.filename)
+        });
+
+        let index = IndexBuilder::new(self.path)
+            .skip_invalid(skip_invalid)
+            .from_path(&self.filename)?;
+
+        index.to_path(output)?;
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -1,13 +1,10 @@
-use std::ffi::{OsStr, OsString};
+use std::ffi::OsString;
 use std::fs::File;
-use std::io::{
-    self, BufRead, BufReader, BufWriter, Read, Write, stdin,
-};
-use std::path::{Path, PathBuf};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
 use std::process::ExitCode;
 
 use clap::Parser;
-use flate2::read::GzDecoder;
 use pica_record::ByteRecord;
 
 use crate::config::Config;
@@ -18,9 +15,19 @@ use crate::progress::Progress;
 ///
 /// Read lines from files or stdin and write input lines, which can't be
 /// decoded as normalized PICA+. The output is given in chronological
-/// order.
+/// order. With `--csv` or `--tsv` a report is written instead, which
+/// contains the location and the kind of each error next to the
+/// invalid line.
 #[derive(Debug, Parser)]
 pub(crate) struct Invalid {
+    /// Write a comma-separated (CSV) report of all errors
+    #[arg(long, conflicts_with = "tsv")]
+    csv: bool,
+
+    /// Write a tab-separated (TSV) report of all errors
+    #[arg(long)]
+    tsv: bool,
+
     /// Show progress bar (requires `-o`/`--output`).
     #[arg(short, long, requires = "output")]
     progress: bool,
@@ -34,23 +41,6 @@ pub(crate) struct Invalid {
     filenames: Vec<OsString>,
 }
 
-fn reader(filename: &OsString) -> io::Result<BufReader<Box<dyn Read>>> {
-    let path = PathBuf::from(filename);
-    let ext = path.extension().and_then(OsStr::to_str);
-    let inner: Box<dyn Read> = match ext {
-        Some("gz") => Box::new(GzDecoder::new(File::open(path)?)),
-        _ => {
-            if path.to_str() != Some("-") {
-                Box::new(File::open(path)?)
-            } else {
-                Box::new(stdin().lock())
-            }
-        }
-    };
-
-    Ok(BufReader::new(inner))
-}
-
 fn writer<P>(output: Option<P>) -> io::Result<BufWriter<Box<dyn Write>>>
 where
     P: AsRef<Path>,
@@ -63,22 +53,80 @@ where
 }
 
 impl Invalid {
-    pub(crate) fn execute(self, _config: &Config) -> CliResult {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
         let mut progress = Progress::new(self.progress);
-        let mut writer = writer(self.output)?;
+        let encoding = config.encoding.unwrap_or_default();
         let mut buf = Vec::<u8>::new();
 
-        for filename in self.filenames.iter() {
-            let mut reader = reader(filename)?;
+        let (mut writer, mut report) = if self.csv || self.tsv {
+            let mut report = csv::WriterBuilder::new()
+                .delimiter(if self.tsv { b'\t' } else { b',' })
+                .from_writer(writer(self.output)?);
+            report.write_record([
+                "source", "record", "line", "offset", "tag", "kind",
+                "message", "data",
+            ])?;
+            (None, Some(report))
+        } else {
+            (Some(writer(self.output)?), None)
+        };
+
+        let inputs = self
+            .filenames
+            .iter()
+            .map(|filename| config.reader_builder().sources(filename))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        for reader in inputs.into_iter().flatten() {
+            let reader = reader?;
+            let source =
+                reader.source().unwrap_or_default().to_string();
+            let mut reader = reader.into_inner();
+
+            let mut line = 0;
             loop {
                 match reader.read_until(b'\n', &mut buf)? {
                     0 => break,
                     _ => {
-                        if ByteRecord::from_bytes(&buf).is_ok() {
-                            progress.update(false);
-                        } else {
-                            writer.write_all(&buf)?;
-                            progress.update(true);
+                        line += 1;
+
+                        let result =
+                            encoding.decode(&buf).and_then(|data| {
+                                ByteRecord::from_bytes(&data)
+                                    .map(|_| ())
+                            });
+
+                        match result {
+                            Ok(_) => progress.update(false),
+                            Err(e) => {
+                                if let Some(ref mut report) = report {
+                                    let line = line.to_string();
+                                    let offset = e
+                                        .byte_offset()
+                                        .map(|n| n.to_string())
+                                        .unwrap_or_default();
+
+                                    report.write_record([
+                                        source.as_bytes(),
+                                        line.as_bytes(),
+                                        line.as_bytes(),
+                                        offset.as_bytes(),
+                                        e.field_tag()
+                                            .unwrap_or_default()
+                                            .as_bytes(),
+                                        e.kind().as_str().as_bytes(),
+                                        e.message().as_bytes(),
+                                        buf.strip_suffix(b"\n")
+                                            .unwrap_or(&buf),
+                                    ])?;
+                                } else if let Some(ref mut writer) =
+                                    writer
+                                {
+                                    writer.write_all(&buf)?;
+                                }
+
+                                progress.update(true);
+                            }
                         }
                     }
                 }
@@ -87,8 +135,15 @@ impl Invalid {
             }
         }
 
+        if let Some(ref mut report) = report {
+            report.flush()?;
+        }
+
+        if let Some(ref mut writer) = writer {
+            writer.flush()?;
+        }
+
         progress.finish();
-        writer.flush()?;
 
         Ok(ExitCode::SUCCESS)
     }
@@ -0,0 +1,96 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use pica_record::io::Index;
+use pica_record::prelude::*;
+
+use crate::prelude::*;
+
+/// Look up records by key using an index
+///
+/// The lookup command reads the records with the given keys from
+/// FILENAME by means of an index, which was created by the `index`
+/// command. The records are written in the order of the given keys;
+/// keys that aren't contained in the index are ignored.
+///
+/// A record of a gzip-compressed file is read by decompressing its gzip
+/// member from the start, so the cost of a lookup grows with the size
+/// of the member. For fast random access, write the file in BGZF format
+/// (e.g. `pica convert FILENAME -o FILENAME.bgz`) before indexing it.
+#[derive(Parser, Debug)]
+pub(crate) struct Lookup {
+    /// Skip invalid records that can't be decoded
+    #[arg(short, long)]
+    skip_invalid: bool,
+
+    /// Read the index from FILENAME instead of `<FILENAME>.idx`
+    #[arg(short = 'I', long, value_name = "FILENAME")]
+    index: Option<PathBuf>,
+
+    /// Compress output in gzip format
+    #[arg(long, short)]
+    gzip: bool,
+
+    /// Append to the given file, do not overwrite
+    #[arg(long)]
+    append: bool,
+
+    /// Write output to FILENAME instead of stdout
+    #[arg(short, long, value_name = "FILENAME")]
+    output: Option<OsString>,
+
+    /// The indexed file in normalized PICA+ format
+    filename: PathBuf,
+
+    /// One or more keys (e.g. PPNs)
+    #[arg(required = true)]
+    keys: Vec<String>,
+}
+
+impl Lookup {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid = self.skip_invalid || config.skip_invalid;
+        let index = Index::from_path(
+            self.index
+                .unwrap_or_else(|| Index::sidecar(&self.filename)),
+        )?;
+
+        let path = Path::new(index.key())?;
+        let options = MatcherOptions::default();
+        let mut reader = index.reader(&self.filename)?;
+
+        let mut writer = WriterBuilder::new()
+            .gzip(self.gzip)
+            .append(self.append)
+            .from_path_or_stdout(self.output)?;
+
+        for key in self.keys.iter() {
+            for entry in index.get(key) {
+                match reader.read_entry(entry) {
+                    Err(e) if e.skip_parse_err(skip_invalid) => {
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                    Ok(record) => {
+                        if !record
+                            .path(&path, &options)
+                            .any(|value| value == key.as_bytes())
+                        {
+                            bail!(
+                                "index of '{}' is out of date",
+                                self.filename.display()
+                            );
+                        }
+
+                        writer.write_byte_record(&record)?;
+                    }
+                }
+            }
+        }
+
+        writer.finish()?;
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,268 @@
+use std::ffi::OsString;
+use std::io;
+use std::process::ExitCode;
+
+use clap::Parser;
+use hashbrown::HashSet;
+use pica_record::merge::MergeConflict;
+use pica_record::prelude::*;
+use pica_record::primitives::{FieldRef, RecordRef};
+
+use crate::commands::diff::{label, plain_subfields, read_records};
+use crate::commands::patch::Conflicts;
+use crate::prelude::*;
+
+/// Merge two changed versions of records
+///
+/// The merge3 command joins the records of BASE, OURS and THEIRS by a
+/// key (by default the PPN) and merges the changes of OURS and THEIRS
+/// relative to BASE on field level. Fields changed in only one version
+/// are taken from this version and fields added by any version are
+/// kept. If both versions changed the same field differently, added
+/// different fields, or one version deleted a record the other one
+/// changed, a conflict is reported and the version of OURS is kept.
+#[derive(Parser, Debug)]
+pub(crate) struct Merge3 {
+    /// Skip invalid records that can't be decoded
+    #[arg(short, long)]
+    skip_invalid: bool,
+
+    /// The path expression of the key, which is used to join the
+    /// records of all inputs. If a record has more than one key value,
+    /// the first value is used.
+    #[arg(long, short, default_value = "003@.0")]
+    key: String,
+
+    /// Write conflicts as CSV to FILENAME instead of stderr
+    #[arg(short, long, value_name = "FILENAME")]
+    conflicts: Option<OsString>,
+
+    /// Compress output in gzip format
+    #[arg(long, short)]
+    gzip: bool,
+
+    /// Append to the given file, do not overwrite
+    #[arg(long)]
+    append: bool,
+
+    /// Show progress bar (requires `-o`/`--output`).
+    #[arg(short, long, requires = "output")]
+    progress: bool,
+
+    /// Write output to FILENAME instead of stdout
+    #[arg(short, long, value_name = "FILENAME")]
+    output: Option<OsString>,
+
+    /// The file with the common base versions of the records
+    base: OsString,
+
+    /// The file with our versions of the records
+    ours: OsString,
+
+    /// The file with their versions of the records
+    theirs: OsString,
+}
+
+impl Merge3 {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid = self.skip_invalid || config.skip_invalid;
+        let mut progress = Progress::new(self.progress);
+        let path = Path::new(&self.key)?;
+        let options = MatcherOptions::default();
+
+        let (_, base) = read_records(
+            config,
+            self.base,
+            &path,
+            skip_invalid,
+            &mut progress,
+            "base",
+        )?;
+
+        let (order, mut theirs) = read_records(
+            config,
+            self.theirs,
+            &path,
+            skip_invalid,
+            &mut progress,
+            "their",
+        )?;
+
+        let mut conflicts = Conflicts::new(
+            self.conflicts,
+            &["key", "field", "base", "ours", "theirs"],
+        )?;
+        let mut writer = WriterBuilder::new()
+            .gzip(self.gzip)
+            .append(self.append)
+            .from_path_or_stdout(self.output)?;
+
+        let mut seen = HashSet::new();
+        let mut reader =
+            config.reader_builder().from_sources(self.ours)?;
+        while let Some(result) = reader.next_byte_record() {
+            match result {
+                Err(e) if e.skip_parse_err(skip_invalid) => {
+                    progress.update(true);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+                Ok(ref record) => {
+                    progress.update(false);
+
+                    let Some(key) = record.first(&path, &options)
+                    else {
+                        writer.write_byte_record(record)?;
+                        continue;
+                    };
+
+                    if !seen.insert(key.to_owned()) {
+                        bail!("duplicate key '{key}' in our records");
+                    }
+
+                    let lhs = base.get(key);
+                    let rhs = theirs.remove(key);
+
+                    match (lhs, rhs) {
+                        (Some(base), Some(theirs)) => {
+                            let (base, theirs) =
+                                (parse(base), parse(&theirs));
+                            let merge = base.merge3(record, &theirs);
+                            for conflict in merge.conflicts() {
+                                field_conflict(
+                                    &mut conflicts,
+                                    key,
+                                    conflict,
+                                )?;
+                            }
+
+                            writer.write_byte_record(
+                                &ByteRecord::from(RecordRef::from(
+                                    merge.record(),
+                                )),
+                            )?;
+                        }
+                        (Some(base), None) => {
+                            if parse(base).diff(record).is_empty() {
+                                continue;
+                            }
+
+                            record_conflict(
+                                &mut conflicts,
+                                key,
+                                "modified",
+                                "deleted",
+                            )?;
+                            writer.write_byte_record(record)?;
+                        }
+                        (None, Some(theirs)) => {
+                            if !parse(&theirs).diff(record).is_empty() {
+                                record_conflict(
+                                    &mut conflicts,
+                                    key,
+                                    "added",
+                                    "added",
+                                )?;
+                            }
+
+                            writer.write_byte_record(record)?;
+                        }
+                        (None, None) => {
+                            writer.write_byte_record(record)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for key in order.iter() {
+            let Some(data) = theirs.get(key) else {
+                continue;
+            };
+
+            match base.get(key) {
+                None => writer.write_byte_record(&ByteRecord::from(
+                    parse(data),
+                ))?,
+                Some(base) => {
+                    if !parse(base).diff(&parse(data)).is_empty() {
+                        record_conflict(
+                            &mut conflicts,
+                            key,
+                            "deleted",
+                            "modified",
+                        )?;
+                    }
+                }
+            }
+        }
+
+        progress.finish();
+        conflicts.finish()?;
+        writer.finish()?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Parses a record, which was read from one of the inputs.
+fn parse(data: &[u8]) -> RecordRef<'_> {
+    // SAFETY: The data was written from a valid record, so it's safe
+    // to call unwrap.
+    RecordRef::from_bytes(data).unwrap()
+}
+
+/// Reports a conflict on field level.
+fn field_conflict(
+    conflicts: &mut Conflicts,
+    key: &[u8],
+    conflict: &MergeConflict,
+) -> io::Result<()> {
+    let value = |field: Option<&FieldRef>| {
+        field.map(plain_subfields).unwrap_or_default()
+    };
+
+    let deleted = |value: &str| {
+        if value.is_empty() {
+            "(deleted)".to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let key = String::from_utf8_lossy(key);
+    let field = conflict
+        .base()
+        .or(conflict.ours())
+        .or(conflict.theirs())
+        .map(label)
+        .unwrap_or_default();
+
+    let base = value(conflict.base());
+    let ours = value(conflict.ours());
+    let theirs = value(conflict.theirs());
+
+    conflicts.write(
+        &[&key, &field, &base, &ours, &theirs],
+        format_args!(
+            "{key}: {field}: base {}, ours {}, theirs {}",
+            if base.is_empty() { "(none)" } else { &base },
+            deleted(&ours),
+            deleted(&theirs),
+        ),
+    )
+}
+
+/// Reports a conflict on record level.
+fn record_conflict(
+    conflicts: &mut Conflicts,
+    key: &[u8],
+    ours: &str,
+    theirs: &str,
+) -> io::Result<()> {
+    let key = String::from_utf8_lossy(key);
+    conflicts.write(
+        &[&key, "", "", ours, theirs],
+        format_args!("{key}: ours {ours}, theirs {theirs}"),
+    )
+}
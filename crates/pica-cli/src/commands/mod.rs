@@ -5,13 +5,20 @@ pub(crate) use config::Config;
 pub(crate) use convert::Convert;
 pub(crate) use count::Count;
 pub(crate) use describe::Describe;
+pub(crate) use diff::Diff;
 pub(crate) use explode::Explode;
 pub(crate) use filter::Filter;
 pub(crate) use frequency::Frequency;
 pub(crate) use hash::Hash;
+pub(crate) use index::Index;
 pub(crate) use invalid::Invalid;
+pub(crate) use lookup::Lookup;
+pub(crate) use merge3::Merge3;
+pub(crate) use normalize::Normalize;
 pub(crate) use partition::Partition;
+pub(crate) use patch::Patch;
 pub(crate) use print::Print;
+pub(crate) use route::Route;
 pub(crate) use sample::Sample;
 pub(crate) use select::Select;
 pub(crate) use slice::Slice;
@@ -24,13 +31,20 @@ mod config;
 mod convert;
 mod count;
 mod describe;
+pub(crate) mod diff;
 mod explode;
 mod filter;
 mod frequency;
 mod hash;
+mod index;
 mod invalid;
+mod lookup;
+mod merge3;
+pub(crate) mod normalize;
 mod partition;
+mod patch;
 mod print;
+mod route;
 mod sample;
 mod select;
 mod slice;
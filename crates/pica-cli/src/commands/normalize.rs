@@ -0,0 +1,157 @@
+use std::ffi::OsString;
+use std::process::ExitCode;
+
+use bstr::ByteSlice;
+use clap::Parser;
+use pica_record::prelude::*;
+use pica_record::primitives::{Record, RecordRef, SubfieldValue};
+
+use crate::prelude::*;
+
+/// Bring records into a normalized form
+///
+/// The normalize command sorts the fields of each record into canonical
+/// order (by level, tag and occurrence, whereby local data blocks and
+/// copies are kept together). All subfield values are transliterated
+/// into a Unicode normal form and leading and trailing whitespace is
+/// removed. Subfields with an empty value and fields without any
+/// subfields are removed.
+///
+/// Normalized records can be compared by their checksum (see the hash
+/// command), even if they come from different sources.
+#[derive(Parser, Debug)]
+pub(crate) struct Normalize {
+    /// Transliterate values into the selected normal form NF
+    /// (possible values: "nfd", "nfkd", "nfc" and "nfkc"). If not
+    /// set, the normal form of the config is used, or "nfc" if it
+    /// isn't configured.
+    #[arg(long = "translit", value_name = "NF")]
+    nf: Option<NormalizationForm>,
+
+    /// Keep the order of the fields
+    #[arg(long)]
+    keep_order: bool,
+
+    /// Compress output in gzip format
+    #[arg(long, short)]
+    gzip: bool,
+
+    /// Append to the given file, do not overwrite
+    #[arg(long)]
+    append: bool,
+
+    /// Show progress bar (requires `-o`/`--output`).
+    #[arg(short, long, requires = "output")]
+    progress: bool,
+
+    /// Write output to FILENAME instead of stdout
+    #[arg(short, long, value_name = "FILENAME")]
+    output: Option<OsString>,
+
+    /// Read one or more files in normalized PICA+ format
+    ///
+    /// If no filenames were given or a filename is "-", data is read
+    /// from standard input (stdin).
+    #[arg(default_value = "-", hide_default_value = true)]
+    filenames: Vec<OsString>,
+
+    #[command(flatten, next_help_heading = "Filter options")]
+    pub(crate) filter_opts: FilterOpts,
+}
+
+/// Normalizes the subfield values of a record.
+///
+/// The values are transliterated into the given normal form (values
+/// which aren't valid UTF-8 are left as they are) and leading and
+/// trailing whitespace is removed. Afterwards, empty subfields and
+/// fields without subfields are removed.
+fn normalize_values(
+    record: &mut Record,
+    nf: Option<NormalizationForm>,
+) {
+    let translit = translit::<String>(nf);
+
+    for field in record.fields_mut() {
+        for subfield in field.subfields_mut() {
+            if let Ok(value) = subfield.value().to_str() {
+                let value = translit(value.to_string());
+                subfield
+                    .set_value(SubfieldValue::from_unchecked(&value));
+            }
+        }
+    }
+
+    record.trim();
+}
+
+impl Normalize {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid =
+            self.filter_opts.skip_invalid || config.skip_invalid;
+        let mut progress = Progress::new(self.progress);
+        let mut count = 0;
+
+        let nf = self.nf.or(config.normalization).unwrap_or_default();
+
+        let filter_set = FilterSet::try_from(&self.filter_opts)?;
+        let options = MatcherOptions::from(&self.filter_opts);
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
+
+        let mut writer = WriterBuilder::new()
+            .gzip(self.gzip)
+            .append(self.append)
+            .from_path_or_stdout(self.output)?;
+
+        'outer: for filename in self.filenames {
+            let mut reader =
+                config.reader_builder().from_sources(filename)?;
+
+            while let Some(result) = reader.next_byte_record() {
+                match result {
+                    Err(e) if e.skip_parse_err(skip_invalid) => {
+                        progress.update(true);
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                    Ok(ref record) => {
+                        progress.update(false);
+
+                        if !filter_set.check(record) {
+                            continue;
+                        }
+
+                        if let Some(ref matcher) = matcher
+                            && !matcher.is_match(record, &options)
+                        {
+                            continue;
+                        }
+
+                        let mut record =
+                            Record::from((**record).clone());
+                        normalize_values(&mut record, Some(nf));
+                        if !self.keep_order {
+                            record.sort();
+                        }
+
+                        writer.write_byte_record(&ByteRecord::from(
+                            RecordRef::from(&record),
+                        ))?;
+
+                        count += 1;
+                        if self.filter_opts.limit > 0
+                            && count >= self.filter_opts.limit
+                        {
+                            break 'outer;
+                        }
+                    }
+                }
+            }
+        }
+
+        progress.finish();
+        writer.finish()?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -64,9 +64,8 @@ impl Partition {
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
         let options = MatcherOptions::from(&self.filter_opts);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
         let template = self.template.unwrap_or(if self.gzip {
             "{}.dat.gz".into()
@@ -83,7 +82,7 @@ impl Partition {
 
         'outer: for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
@@ -0,0 +1,309 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use bstr::BString;
+use clap::Parser;
+use hashbrown::{HashMap, HashSet};
+use pica_record::patch::{Change, Operation};
+use pica_record::prelude::*;
+use pica_record::primitives::{Record, RecordRef};
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+/// Apply a set of changes to records
+///
+/// The patch command reads a change set (CSV or TSV) and applies the
+/// changes to the records, which are joined by a key (by default the
+/// PPN). Each change adds, removes or replaces a field or a subfield.
+/// The old value of a change acts as a guard: if a record no longer
+/// contains the expected value, the change isn't applied and a
+/// conflict is reported. Records without changes are written as they
+/// are.
+///
+/// The change set has the columns `key`, `change`, `field`, `code`,
+/// `old` and `new`, so that the CSV output of the diff command can be
+/// used as a change set.
+#[derive(Parser, Debug)]
+pub(crate) struct Patch {
+    /// Skip invalid records that can't be decoded
+    #[arg(short, long)]
+    skip_invalid: bool,
+
+    /// The path expression of the key, which is used to join the
+    /// records with the change set. If a record has more than one key
+    /// value, the first value is used.
+    #[arg(long, short, default_value = "003@.0")]
+    key: String,
+
+    /// Write conflicts as CSV to FILENAME instead of stderr
+    #[arg(short, long, value_name = "FILENAME")]
+    conflicts: Option<OsString>,
+
+    /// Compress output in gzip format
+    #[arg(long, short)]
+    gzip: bool,
+
+    /// Append to the given file, do not overwrite
+    #[arg(long)]
+    append: bool,
+
+    /// Show progress bar (requires `-o`/`--output`).
+    #[arg(short, long, requires = "output")]
+    progress: bool,
+
+    /// Write output to FILENAME instead of stdout
+    #[arg(short, long, value_name = "FILENAME")]
+    output: Option<OsString>,
+
+    /// The change set in CSV format (or TSV, if the filename ends
+    /// with `.tsv`)
+    changes: PathBuf,
+
+    /// Read one or more files in normalized PICA+ format
+    ///
+    /// If no filenames were given or a filename is "-", data is read
+    /// from standard input (stdin).
+    #[arg(default_value = "-", hide_default_value = true)]
+    filenames: Vec<OsString>,
+}
+
+/// A row of the change set.
+#[derive(Debug, Deserialize)]
+struct Row {
+    key: String,
+    change: String,
+    field: String,
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    old: String,
+    #[serde(default)]
+    new: String,
+}
+
+impl Row {
+    /// Creates the change described by the row.
+    fn change(&self) -> Result<Change, String> {
+        let op = self
+            .change
+            .parse::<Operation>()
+            .map_err(|e| e.to_string())?;
+
+        let mut chars = self.code.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => None,
+            (Some(code), None) => Some(code),
+            _ => return Err(format!("invalid code '{}'", self.code)),
+        };
+
+        let old = (!self.old.is_empty()).then_some(self.old.as_str());
+        let new = (!self.new.is_empty()).then_some(self.new.as_str());
+
+        Change::new(op, &self.field, code, old, new)
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Patch {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid = self.skip_invalid || config.skip_invalid;
+        let mut progress = Progress::new(self.progress);
+        let path = Path::new(&self.key)?;
+        let options = MatcherOptions::default();
+
+        let delimiter = match self.changes.extension() {
+            Some(ext) if ext == "tsv" => b'\t',
+            _ => b',',
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .from_path(&self.changes)?;
+
+        let mut order: Vec<BString> = vec![];
+        let mut changes: HashMap<BString, Vec<(Row, Change)>> =
+            HashMap::new();
+
+        for (i, row) in reader.deserialize::<Row>().enumerate() {
+            let row = row?;
+
+            // Changes of whole records (e.g. from the diff command)
+            // can't be applied and are ignored.
+            if row.field.is_empty() {
+                continue;
+            }
+
+            let change = match row.change() {
+                Ok(change) => change,
+                Err(e) => {
+                    bail!("invalid change in line {}: {e}", i + 2)
+                }
+            };
+
+            let key = BString::from(row.key.as_str());
+            if !changes.contains_key(&key) {
+                order.push(key.clone());
+            }
+
+            changes.entry(key).or_default().push((row, change));
+        }
+
+        let mut conflicts = Conflicts::new(
+            self.conflicts,
+            &[
+                "key", "change", "field", "code", "old", "new",
+                "conflict",
+            ],
+        )?;
+        let mut seen = HashSet::new();
+
+        let mut writer = WriterBuilder::new()
+            .gzip(self.gzip)
+            .append(self.append)
+            .from_path_or_stdout(self.output)?;
+
+        for filename in self.filenames {
+            let mut reader =
+                config.reader_builder().from_sources(filename)?;
+
+            while let Some(result) = reader.next_byte_record() {
+                match result {
+                    Err(e) if e.skip_parse_err(skip_invalid) => {
+                        progress.update(true);
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                    Ok(ref record) => {
+                        progress.update(false);
+
+                        let Some((key, changes)) = record
+                            .first(&path, &options)
+                            .and_then(|key| changes.get_key_value(key))
+                        else {
+                            writer.write_byte_record(record)?;
+                            continue;
+                        };
+
+                        let mut record =
+                            Record::from((**record).clone());
+                        for (row, change) in changes {
+                            if let Err(e) = change.apply(&mut record) {
+                                write_conflict(
+                                    &mut conflicts,
+                                    row,
+                                    &e.to_string(),
+                                )?;
+                            }
+                        }
+
+                        writer.write_byte_record(&ByteRecord::from(
+                            RecordRef::from(&record),
+                        ))?;
+
+                        seen.insert(key);
+                    }
+                }
+            }
+        }
+
+        for key in order.iter().filter(|key| !seen.contains(key)) {
+            for (row, _) in changes[key].iter() {
+                write_conflict(
+                    &mut conflicts,
+                    row,
+                    "record not found",
+                )?;
+            }
+        }
+
+        progress.finish();
+        conflicts.finish()?;
+        writer.finish()?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Reports a change, which can't be applied.
+fn write_conflict(
+    conflicts: &mut Conflicts,
+    row: &Row,
+    conflict: &str,
+) -> io::Result<()> {
+    let code = if row.code.is_empty() {
+        String::new()
+    } else {
+        format!(" ${}", row.code)
+    };
+
+    conflicts.write(
+        &[
+            &row.key,
+            &row.change,
+            &row.field,
+            &row.code,
+            &row.old,
+            &row.new,
+            conflict,
+        ],
+        format_args!(
+            "{}: {} {}{code}: {conflict}",
+            row.key, row.change, row.field
+        ),
+    )
+}
+
+/// A writer for conflicts.
+///
+/// Conflicts are written either as CSV to a file or as plain text to
+/// stderr.
+pub(crate) enum Conflicts {
+    Csv(Box<csv::Writer<Output>>),
+    Stderr(io::Stderr),
+}
+
+impl Conflicts {
+    /// Creates a new conflict writer. If a path is given, the CSV
+    /// header is written to the file.
+    pub(crate) fn new(
+        path: Option<OsString>,
+        header: &[&str],
+    ) -> Result<Self, CliError> {
+        let Some(path) = path else {
+            return Ok(Self::Stderr(io::stderr()));
+        };
+
+        let mut writer = csv::Writer::from_writer(
+            Output::from_path_or_stdout(Some(path), false)?,
+        );
+
+        writer.write_record(header)?;
+        Ok(Self::Csv(Box::new(writer)))
+    }
+
+    /// Writes a conflict, either as a CSV row or as a message.
+    pub(crate) fn write(
+        &mut self,
+        row: &[&str],
+        message: fmt::Arguments,
+    ) -> io::Result<()> {
+        match self {
+            Self::Csv(writer) => Ok(writer.write_record(row)?),
+            Self::Stderr(out) => writeln!(out, "conflict: {message}"),
+        }
+    }
+
+    pub(crate) fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Csv(writer) => (*writer)
+                .into_inner()
+                .map_err(csv::IntoInnerError::into_error)?
+                .finish(),
+            Self::Stderr(mut out) => out.flush(),
+        }
+    }
+}
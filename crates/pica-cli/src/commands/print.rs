@@ -41,9 +41,8 @@ impl Print {
         let mut count = 0;
 
         let filter_set = FilterSet::try_from(&self.filter_opts)?;
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
         let options = MatcherOptions::from(&self.filter_opts);
 
         let mut writer: BufWriter<Box<dyn Write>> =
@@ -55,7 +54,7 @@ impl Print {
 
         'outer: for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
@@ -76,7 +75,7 @@ impl Print {
                             continue;
                         }
 
-                        let translit = translit(self.nf.clone());
+                        let translit = translit(self.nf);
 
                         for field in record.fields() {
                             field.tag().write_to(&mut writer)?;
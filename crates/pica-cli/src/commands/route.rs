@@ -0,0 +1,181 @@
+use std::ffi::OsString;
+use std::fs::{self, read_to_string};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Parser;
+use hashbrown::HashSet;
+use pica_record::io::MultiWriter;
+use pica_record::prelude::*;
+
+use crate::prelude::*;
+
+/// A routing table, which maps filter expressions to output files.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RoutingTable {
+    #[serde(rename = "route", default)]
+    routes: Vec<Entry>,
+}
+
+/// An entry of a routing table.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    filter: Option<String>,
+    output: PathBuf,
+}
+
+impl RoutingTable {
+    fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, CliError> {
+        let content = read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| {
+            let filename = path.as_ref().to_string_lossy();
+            CliError::Other(format!(
+                "invalid routing table {filename}: {e}"
+            ))
+        })
+    }
+}
+
+/// Route records into multiple files in a single pass
+///
+/// The routes are read from a routing table (TOML), which consists of
+/// a list of `[[route]]` entries. Each entry has an `output` filename
+/// and an optional `filter` expression. A record is written to the
+/// first route whose filter matches the record; a route without a
+/// filter receives all remaining records. Use `--all` to write a
+/// record to every matching route. Records that don't match any route
+/// are dropped.
+///
+/// The compression format of an output file is derived from the
+/// extension of the filename (`.gz`, `.zst`, `.xz` or `.bz2`).
+#[derive(Parser, Debug)]
+pub(crate) struct Route {
+    /// Write a record to every matching route (instead of only the
+    /// first one)
+    #[arg(long)]
+    all: bool,
+
+    /// Show progress bar
+    #[arg(short, long)]
+    progress: bool,
+
+    /// Write output files into OUTDIR
+    ///
+    /// Relative filenames of the routing table are resolved against
+    /// this directory. If the directory doesn't exist, it will be
+    /// created automatically.
+    #[arg(long, short, value_name = "outdir", default_value = ".")]
+    outdir: PathBuf,
+
+    /// The routing table (TOML)
+    routes: PathBuf,
+
+    /// Read one or more files in normalized PICA+ format
+    ///
+    /// If no filenames were given or a filename is "-", data is read
+    /// from standard input (stdin).
+    #[arg(default_value = "-", hide_default_value = true)]
+    filenames: Vec<OsString>,
+
+    #[command(flatten, next_help_heading = "Filter options")]
+    pub(crate) filter_opts: FilterOpts,
+}
+
+impl Route {
+    pub(crate) fn execute(self, config: &Config) -> CliResult {
+        let skip_invalid =
+            self.filter_opts.skip_invalid || config.skip_invalid;
+        let mut progress = Progress::new(self.progress);
+        let mut count = 0;
+
+        let filter_set = FilterSet::try_from(&self.filter_opts)?;
+        let options = MatcherOptions::from(&self.filter_opts);
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
+
+        let table = RoutingTable::from_path(&self.routes)?;
+        if table.routes.is_empty() {
+            bail!(
+                "routing table {} doesn't contain any routes",
+                self.routes.display()
+            );
+        }
+
+        if !self.outdir.exists() {
+            fs::create_dir_all(&self.outdir)?;
+        }
+
+        let mut seen = HashSet::new();
+        let mut writer = MultiWriter::new()
+            .options(MatcherOptions::from(&self.filter_opts))
+            .first_match(!self.all);
+
+        for entry in table.routes.into_iter() {
+            let path = self.outdir.join(&entry.output);
+            if !seen.insert(path.clone()) {
+                bail!(
+                    "duplicate output '{}' in routing table {}",
+                    entry.output.display(),
+                    self.routes.display()
+                );
+            }
+
+            let matcher = match entry.filter {
+                Some(filter) => Some(
+                    RecordMatcherBuilder::with_transform(
+                        filter,
+                        translit(config.normalization),
+                    )?
+                    .build(),
+                ),
+                None => None,
+            };
+
+            writer.add(matcher, WriterBuilder::new().from_path(path)?);
+        }
+
+        'outer: for filename in self.filenames {
+            let mut reader =
+                config.reader_builder().from_sources(filename)?;
+
+            while let Some(result) = reader.next_byte_record() {
+                match result {
+                    Err(e) if e.skip_parse_err(skip_invalid) => {
+                        progress.update(true);
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                    Ok(ref record) => {
+                        progress.update(false);
+
+                        if !filter_set.check(record) {
+                            continue;
+                        }
+
+                        if let Some(ref matcher) = matcher
+                            && !matcher.is_match(record, &options)
+                        {
+                            continue;
+                        }
+
+                        writer.write_byte_record(record)?;
+                        count += 1;
+
+                        if self.filter_opts.limit > 0
+                            && count >= self.filter_opts.limit
+                        {
+                            break 'outer;
+                        }
+                    }
+                }
+            }
+        }
+
+        progress.finish();
+        writer.finish()?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
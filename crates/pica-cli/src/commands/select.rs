@@ -1,8 +1,6 @@
 use std::collections::hash_map::DefaultHasher;
 use std::ffi::OsString;
-use std::fs::OpenOptions;
 use std::hash::{Hash, Hasher};
-use std::io::{self, Write};
 use std::process::ExitCode;
 
 use clap::{Parser, ValueEnum};
@@ -108,23 +106,6 @@ pub(crate) struct Select {
     filter_opts: FilterOpts,
 }
 
-fn writer(
-    filename: Option<OsString>,
-    append: bool,
-) -> io::Result<Box<dyn Write>> {
-    Ok(match filename {
-        Some(filename) => Box::new(
-            OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(!append)
-                .append(append)
-                .open(filename)?,
-        ),
-        None => Box::new(io::stdout().lock()),
-    })
-}
-
 impl Select {
     pub(crate) fn execute(self, config: &Config) -> CliResult {
         let skip_invalid =
@@ -144,17 +125,19 @@ impl Select {
             .merge(self.merge);
 
         let matcher_options = MatcherOptions::from(&options);
-        let matcher = self
-            .filter_opts
-            .matcher(config.normalization.clone(), None)?;
+        let matcher =
+            self.filter_opts.matcher(config.normalization, None)?;
 
-        let translit = translit(config.normalization.clone());
+        let translit = translit(config.normalization);
         let query = Query::new(translit(self.query))?;
 
         let mut writer = csv::WriterBuilder::new()
             .delimiter(if self.tsv { b'\t' } else { b',' })
             .quote_style(QuoteStyle::from(self.quote_style))
-            .from_writer(writer(self.output, self.append)?);
+            .from_writer(Output::from_path_or_stdout(
+                self.output,
+                self.append,
+            )?);
 
         if let Some(header) = self.header {
             writer.write_record(header.split(',').map(str::trim))?;
@@ -162,7 +145,7 @@ impl Select {
 
         'outer: for filename in self.filenames {
             let mut reader =
-                ReaderBuilder::new().from_path(filename)?;
+                config.reader_builder().from_sources(filename)?;
 
             while let Some(result) = reader.next_byte_record() {
                 match result {
@@ -212,7 +195,7 @@ impl Select {
                                     writer.write_record(
                                         row.iter().map(|s| {
                                             (crate::translit::translit(
-                                                self.nf.clone(),
+                                                self.nf,
                                             ))(
                                                 s.to_string()
                                             )
@@ -234,7 +217,10 @@ impl Select {
         }
 
         progress.finish();
-        writer.flush()?;
+        writer
+            .into_inner()
+            .map_err(csv::IntoInnerError::into_error)?
+            .finish()?;
 
         Ok(ExitCode::SUCCESS)
     }
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 use directories::ProjectDirs;
+use pica_record::io::{Encoding, ReaderBuilder};
 use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
@@ -20,6 +21,21 @@ pub(crate) struct Config {
     /// unicode normalization form before applied on a record.
     pub(crate) normalization: Option<NormalizationForm>,
 
+    /// A glob pattern, which selects the files of a directory or the
+    /// members of an archive (set by the `--glob` option).
+    #[serde(skip)]
+    pub(crate) glob: Option<String>,
+
+    /// The character encoding of the input (set by the `--encoding`
+    /// option).
+    #[serde(skip)]
+    pub(crate) encoding: Option<Encoding>,
+
+    /// Whether to detect the format of the input (set by the
+    /// `--detect-format` option).
+    #[serde(skip)]
+    pub(crate) detect_format: bool,
+
     /// This structure should always be constructed using a public
     /// constructor or using the update syntax:
     ///
@@ -81,6 +97,20 @@ impl Config {
         }
     }
 
+    /// Creates a new [ReaderBuilder] from the config.
+    pub(crate) fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        if let Some(ref pattern) = self.glob {
+            builder = builder.glob(pattern.clone());
+        }
+
+        if let Some(encoding) = self.encoding {
+            builder = builder.encoding(encoding);
+        }
+
+        builder.detect_format(self.detect_format)
+    }
+
     /// Saves the config.
     pub(crate) fn save(&self) -> io::Result<()> {
         use std::fs::{File, create_dir_all};
@@ -10,6 +10,7 @@ mod cli;
 mod commands;
 mod config;
 mod error;
+mod output;
 pub(crate) mod prelude;
 mod progress;
 mod translit;
@@ -18,13 +19,16 @@ mod utils;
 fn run() -> CliResult {
     let args = Args::parse();
 
-    #[allow(unused_mut)]
     let mut config = if let Some(ref path) = args.config {
         Config::from_path(path).unwrap_or(Config::new(path))
     } else {
         Config::discover()?
     };
 
+    config.glob = args.glob;
+    config.encoding = args.encoding;
+    config.detect_format = args.detect_format;
+
     match args.cmd {
         Command::Check(cmd) => cmd.execute(&config),
         Command::Completions(cmd) => cmd.execute(&mut Args::command()),
@@ -33,13 +37,20 @@ fn run() -> CliResult {
         Command::Convert(cmd) => cmd.execute(&config),
         Command::Count(cmd) => cmd.execute(&config),
         Command::Describe(cmd) => cmd.execute(&config),
+        Command::Diff(cmd) => cmd.execute(&config),
         Command::Explode(cmd) => cmd.execute(&config),
         Command::Filter(cmd) => cmd.execute(&config),
         Command::Frequency(cmd) => cmd.execute(&config),
         Command::Hash(cmd) => cmd.execute(&config),
+        Command::Index(cmd) => cmd.execute(&config),
         Command::Invalid(cmd) => cmd.execute(&config),
+        Command::Lookup(cmd) => cmd.execute(&config),
+        Command::Merge3(cmd) => cmd.execute(&config),
+        Command::Normalize(cmd) => cmd.execute(&config),
         Command::Partition(cmd) => cmd.execute(&config),
+        Command::Patch(cmd) => cmd.execute(&config),
         Command::Print(cmd) => cmd.execute(&config),
+        Command::Route(cmd) => cmd.execute(&config),
         Command::Sample(cmd) => cmd.execute(&config),
         Command::Slice(cmd) => cmd.execute(&config),
         Command::Split(cmd) => cmd.execute(&config),
@@ -0,0 +1,218 @@
+use std::ffi::OsStr;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Mutex, Once};
+
+use pica_record::io::{Compression, Encoder};
+
+/// A writer for tabular outputs (CSV, TSV, etc.).
+///
+/// The output is compressed according to the extension of the path
+/// (e.g. `.csv.gz` or `.tsv.zst`). A file is written atomically: the
+/// data is written into a temporary file next to the target, which
+/// replaces the target only when the output is finished (see
+/// [Output::finish]). If the output is dropped without being
+/// finished (e.g. due to an error) or the process is interrupted
+/// (`Ctrl-C`), the temporary file is removed and an existing target is
+/// left untouched.
+///
+/// An uncompressed output in append mode is written directly to the
+/// target.
+pub(crate) struct Output {
+    inner: Option<Encoder<Box<dyn Write + Send>>>,
+    staged: Option<Staged>,
+}
+
+/// A temporary file, which replaces the target on success.
+struct Staged {
+    temp: PathBuf,
+    target: PathBuf,
+}
+
+impl Output {
+    /// Creates a new output, which writes to the given path, if
+    /// given, otherwise to `stdout`.
+    ///
+    /// In append mode, an uncompressed output is appended to the
+    /// target directly. Otherwise, the existing content of the target
+    /// is copied into the temporary file first and the output is
+    /// appended as a new member (resp. frame or stream) of the
+    /// compressed file.
+    pub(crate) fn from_path_or_stdout<P: AsRef<Path>>(
+        path: Option<P>,
+        append: bool,
+    ) -> io::Result<Self> {
+        let Some(path) = path else {
+            let inner: Box<dyn Write + Send> = Box::new(io::stdout());
+            return Ok(Self {
+                inner: Some(Compression::None.encoder(inner, None)?),
+                staged: None,
+            });
+        };
+
+        let target = path.as_ref().to_path_buf();
+        let compression = Compression::from_path(&target);
+
+        if append && compression == Compression::None {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&target)?;
+
+            let inner: Box<dyn Write + Send> =
+                Box::new(BufWriter::new(file));
+            return Ok(Self {
+                inner: Some(compression.encoder(inner, None)?),
+                staged: None,
+            });
+        }
+
+        let staged = Staged::new(target);
+        if append && staged.target.exists() {
+            fs::copy(&staged.target, &staged.temp)?;
+        }
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(&staged.temp)?;
+
+        let inner: Box<dyn Write + Send> =
+            Box::new(BufWriter::new(file));
+        Ok(Self {
+            inner: Some(compression.encoder(inner, None)?),
+            staged: Some(staged),
+        })
+    }
+
+    /// Finishes the output.
+    ///
+    /// The compressed stream is finished and the temporary file is
+    /// moved to the target path.
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        if let Some(inner) = self.inner.take() {
+            inner.finish()?.flush()?;
+        }
+
+        if let Some(staged) = self.staged.take() {
+            return staged.commit();
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.inner {
+            Some(ref mut inner) => inner.write(buf),
+            None => Ok(0),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner {
+            Some(ref mut inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        if let Some(staged) = self.staged.take() {
+            // The temporary file must be closed before it is removed.
+            drop(self.inner.take());
+            staged.discard();
+        }
+    }
+}
+
+/// The temporary files of all unfinished outputs, which are removed
+/// when the process is interrupted.
+static STAGED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+impl Staged {
+    /// Creates a new temporary file for the given target and
+    /// registers it for removal on interrupt.
+    fn new(target: PathBuf) -> Self {
+        static HANDLER: Once = Once::new();
+        HANDLER.call_once(|| {
+            // If the handler can't be installed (e.g. because another
+            // handler is already set), temporary files are only
+            // removed on drop.
+            let _ = ctrlc::set_handler(|| {
+                if let Ok(paths) = STAGED.lock() {
+                    for path in paths.iter() {
+                        let _ = fs::remove_file(path);
+                    }
+                }
+
+                process::exit(130);
+            });
+        });
+
+        let temp = temp_path(&target);
+        if let Ok(mut paths) = STAGED.lock() {
+            paths.push(temp.clone());
+        }
+
+        Self { temp, target }
+    }
+
+    /// Moves the temporary file to the target path.
+    fn commit(self) -> io::Result<()> {
+        let result = fs::rename(&self.temp, &self.target);
+        if result.is_err() {
+            let _ = fs::remove_file(&self.temp);
+        }
+
+        self.unregister();
+        result
+    }
+
+    /// Removes the temporary file.
+    fn discard(self) {
+        let _ = fs::remove_file(&self.temp);
+        self.unregister();
+    }
+
+    fn unregister(&self) {
+        if let Ok(mut paths) = STAGED.lock() {
+            paths.retain(|path| path != &self.temp);
+        }
+    }
+}
+
+/// Returns the path of the temporary file for the given target. The
+/// temporary file is placed in the same directory as the target, so
+/// that it can be renamed atomically.
+fn temp_path(target: &Path) -> PathBuf {
+    let filename = target
+        .file_name()
+        .map(OsStr::to_string_lossy)
+        .unwrap_or_default();
+
+    target.with_file_name(format!(".{filename}.{}.tmp", process::id()))
+}
+
+/// Returns `true` if the path (without a compression extension like
+/// `.gz`) has the given extension.
+pub(crate) fn has_extension<P: AsRef<Path>>(
+    path: P,
+    ext: &str,
+) -> bool {
+    let path = path.as_ref();
+    let path = match Compression::from_path(path) {
+        Compression::None => path,
+        _ => Path::new(path.file_stem().unwrap_or_default()),
+    };
+
+    path.extension().and_then(OsStr::to_str) == Some(ext)
+}
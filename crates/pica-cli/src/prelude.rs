@@ -1,6 +1,8 @@
 pub(crate) use crate::cli::FilterOpts;
 pub(crate) use crate::config::Config;
 pub(crate) use crate::error::{CliError, CliResult, bail};
+pub(crate) use crate::output::Output;
 pub(crate) use crate::progress::Progress;
-pub(crate) use crate::translit::{NormalizationForm, translit};
+pub(crate) use crate::translit::translit;
 pub(crate) use crate::utils::{FilterSet, parse_predicates};
+pub(crate) use pica_record::NormalizationForm;
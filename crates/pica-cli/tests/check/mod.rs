@@ -1,7 +1,9 @@
-use std::fs::read_to_string;
+use std::fs::{File, read_to_string};
+use std::io::Read;
 
 use assert_fs::TempDir;
 use assert_fs::prelude::*;
+use flate2::read::GzDecoder;
 use predicates::prelude::PredicateBooleanExt;
 
 use crate::prelude::*;
@@ -250,6 +252,93 @@ fn check_tsv_output() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn check_compressed_output() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let output = temp_dir.child("out.tsv.gz");
+
+    let ruleset = temp_dir.child("rules.toml");
+    ruleset
+        .write_str(
+            r#"
+            [rule.R001]
+            check = "filter"
+            filter = '002@.0 =^ "Tp"'
+        "#,
+        )
+        .unwrap();
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["check", "-s"])
+        .args(["-R", ruleset.to_str().unwrap()])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", output.to_str().unwrap()])
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    let mut data = String::new();
+    GzDecoder::new(File::open(output.path())?)
+        .read_to_string(&mut data)?;
+    assert_eq!(
+        data,
+        "ppn\trule\tlevel\tmessage\n118540238\tR001\terror\t\n118607626\tR001\terror\t\n"
+    );
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn check_provenance() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let output = temp_dir.child("out.tsv");
+
+    let ruleset = temp_dir.child("rules.toml");
+    ruleset
+        .write_str(
+            r#"
+            [rule.R001]
+            check = "filter"
+            filter = '002@.0 =^ "Tp"'
+        "#,
+        )
+        .unwrap();
+
+    let filename = data_dir().join("DUMP.dat.gz");
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["check", "-s", "--provenance"])
+        .args(["-R", ruleset.to_str().unwrap()])
+        .arg(&filename)
+        .args(["-o", output.to_str().unwrap()])
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    let filename = filename.to_str().unwrap();
+    assert_eq!(
+        read_to_string(output)?,
+        format!(
+            "ppn\trule\tlevel\tmessage\tsource\tindex\toffset\n\
+            118540238\tR001\terror\t\t{filename}\t1\t0\n\
+            118607626\tR001\terror\t\t{filename}\t2\t9800\n"
+        )
+    );
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
 #[test]
 fn check_termination() -> TestResult {
     let temp_dir = TempDir::new().unwrap();
@@ -176,6 +176,293 @@ fn convert_from_plus_to_binary() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn convert_from_xml_to_plus() -> TestResult {
+    let mut cmd = pica_cmd();
+    let temp_dir = TempDir::new().unwrap();
+    let out = temp_dir.child("ada.dat");
+
+    let assert = cmd
+        .arg("convert")
+        .args(["--from", "xml", "--to", "plus"])
+        .args(["-o", out.to_str().unwrap()])
+        .arg(data_dir().join("ada.xml"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    // The PICA-XML fixture doesn't carry the field occurrences.
+    let expected = read_to_string(data_dir().join("ada.dat"))?
+        .replace("047A/03", "047A")
+        .replace("070A/03", "070A");
+    assert_eq!(expected, read_to_string(out.path())?);
+
+    // Without `--from`, the input is read as normalized PICA+, unless
+    // `--detect-format` is set.
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--to", "plus"])
+        .write_stdin(read_to_string(data_dir().join("ada.xml"))?)
+        .assert();
+
+    assert.failure().code(2).stdout(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--detect-format", "--to", "plus"])
+        .write_stdin(read_to_string(data_dir().join("ada.xml"))?)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(expected))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn convert_from_xml_invalid() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "xml"])
+        .write_stdin("<collection><record><datafield>")
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains(
+            "invalid PICA-XML at byte offset 31: missing attribute 'tag'",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn convert_from_json_to_plus() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "json", "--to", "plus"])
+        .arg(data_dir().join("ada.json"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::path::eq_file(data_dir().join("ada.dat")))
+        .stderr(predicates::str::is_empty());
+
+    // JSON Lines
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--detect-format", "--to", "plus"])
+        .write_stdin(
+            "[[\"003@\",null,\"0\",\"123\"]]\n\
+             [[\"003@\",null,\"0\",\"456\"],[\"047A\",\"/03\",\"e\",\"X\"]]\n",
+        )
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e\n003@ \x1f0456\x1e047A/03 \x1feX\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn convert_from_json_invalid() -> TestResult {
+    let data = "[\n  [[\"003@\",null,\"0\",\"1\"]],\n  \
+                [[\"003@\",null,\"0\",2]],\n  \
+                [[\"003@\",null,\"0\",\"3\"]]\n]";
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "-s", "--from", "json"])
+        .write_stdin(data)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f01\x1e\n003@ \x1f03\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "json"])
+        .write_stdin(data)
+        .assert();
+
+    assert.failure().code(2).stderr(predicates::str::contains(
+        "invalid record 2 at line 3, column 21",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn convert_from_json_empty_record() -> TestResult {
+    let data = "[]\n[[\"003@\",null,\"0\",\"123\"]]\n[]\n";
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "-s", "--from", "json"])
+        .write_stdin(data)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("003@ \x1f0123\x1e\n"))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "json"])
+        .write_stdin(data)
+        .assert();
+
+    assert.failure().code(2).stderr(predicates::str::contains(
+        "invalid record 1 at line 1, column 1",
+    ));
+
+    // An empty array of records
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "json"])
+        .write_stdin("[ ]\n")
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn convert_from_plain_to_plus() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "plain", "--to", "plus"])
+        .arg(data_dir().join("ada.plain"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::path::eq_file(data_dir().join("ada.dat")))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "-s", "--detect-format", "--to", "plus"])
+        .write_stdin(
+            "003@ $0123\n021A $aUS-$$$hX\n\n003@ 0456\n\n\n003@ $0789\n",
+        )
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e021A \x1faUS-$\x1fhX\x1e\n003@ \x1f0789\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "plain"])
+        .write_stdin("003@ $0123\n\n003@ 0456\n")
+        .assert();
+
+    assert.failure().code(2).stderr(predicates::str::contains(
+        "invalid record 2 at line 3",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn convert_from_import_to_plus() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "import", "--to", "plus"])
+        .arg(data_dir().join("ada.import"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::path::eq_file(data_dir().join("ada.dat")))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--detect-format", "--to", "plus"])
+        .write_stdin(
+            "\x1d\n\x1e003@ \x1f0123\n\x1d\n\x1e003@ \x1f0456\n",
+        )
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e\n003@ \x1f0456\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn convert_from_binary_to_plus() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--from", "binary", "--to", "plus"])
+        .arg(data_dir().join("ada.bin"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::path::eq_file(data_dir().join("ada.dat")))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["convert", "--detect-format", "--to", "plus"])
+        .write_stdin("003@ \x1f0123\x1e\x1d003@ \x1f0456\x1e\x1d")
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e\n003@ \x1f0456\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
 #[test]
 fn convert_skip_invalid() -> TestResult {
     let mut cmd = pica_cmd();
@@ -24,6 +24,160 @@ fn count_write_stdout() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn count_archive() -> TestResult {
+    for filename in ["archive.tar", "archive.tar.gz", "archive.zip"] {
+        let mut cmd = pica_cmd();
+        let assert = cmd
+            .args(["count", "--records", "--glob", "*.dat*"])
+            .arg(data_dir().join(filename))
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::ord::eq("2\n"))
+            .stderr(predicates::str::is_empty());
+    }
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "--records"])
+        .arg(data_dir().join("archive.tar"))
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("archive.tar!/README.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn count_encoding() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "--records", "--encoding", "windows-1252"])
+        .arg(data_dir().join("latin1.dat"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("2\n"))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "-s", "--records", "--encoding", "latin1"])
+        .arg(data_dir().join("latin1.dat"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("1\n"))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "--records", "--encoding", "ebcdic"])
+        .arg(data_dir().join("latin1.dat"))
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("unknown encoding 'ebcdic'"));
+
+    Ok(())
+}
+
+#[test]
+fn count_directory() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir
+        .child("2024/01/ada.dat")
+        .write_file(&data_dir().join("ada.dat"))?;
+    temp_dir
+        .child("2024/02/DUMP.dat.gz")
+        .write_file(&data_dir().join("DUMP.dat.gz"))?;
+    temp_dir
+        .child("archive.zip")
+        .write_file(&data_dir().join("archive.zip"))?;
+    temp_dir.child("README.md").write_str("# Records\n")?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "-s", "--records", "--glob", "*.dat*"])
+        .arg(temp_dir.path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("15\n"))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn count_compressed() -> TestResult {
+    for filename in ["DUMP.dat.zst", "DUMP.dat.xz", "DUMP.dat.bz2"] {
+        let mut cmd = pica_cmd();
+        let assert = cmd
+            .args(["count", "-s"])
+            .arg(data_dir().join(filename))
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::ord::eq(
+                "records: 12\nfields: 1035\nsubfields: 3973\n",
+            ))
+            .stderr(predicates::str::is_empty());
+    }
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "-s"])
+        .write_stdin(std::fs::read(data_dir().join("DUMP.dat.zst"))?)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "records: 12\nfields: 1035\nsubfields: 3973\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn count_xml() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert =
+        cmd.arg("count").arg(data_dir().join("ada.xml")).assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "records: 1\nfields: 55\nsubfields: 151\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
 #[test]
 fn count_write_output() -> TestResult {
     let temp_dir = TempDir::new().unwrap();
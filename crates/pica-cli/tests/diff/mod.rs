@@ -0,0 +1,284 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+const OLD: &str = "\
+003@ \x1f0123\x1e028A \x1faLovelace\x1fdAda\x1e050C \x1fafoo\x1e\n\
+003@ \x1f0456\x1e002@ \x1f0Tp1\x1e\n\
+003@ \x1f0789\x1e002@ \x1f0Ts1\x1e\n";
+
+const NEW: &str = "\
+003@ \x1f0789\x1e002@ \x1f0Ts1\x1e\n\
+003@ \x1f0123\x1e028A \x1faLovelace\x1fdAugusta Ada\x1fcLady\x1e\
+060R \x1fa1815\x1e\n\
+003@ \x1f0999\x1e002@ \x1f0Tp1\x1e\n";
+
+fn setup(temp_dir: &TempDir) -> anyhow::Result<()> {
+    temp_dir.child("old.dat").write_str(OLD)?;
+    temp_dir.child("new.dat").write_str(NEW)?;
+    Ok(())
+}
+
+#[test]
+fn diff_text() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("diff")
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "~ 123\n  \
+             ~ 028A $d Ada -> Augusta Ada\n  \
+             ~ 028A $c + Lady\n  \
+             - 050C $afoo\n  \
+             + 060R $a1815\n\
+             + 999\n\
+             - 456\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn diff_csv() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["diff", "--format", "csv"])
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "key,change,field,code,old,new\n\
+             123,modified,028A,d,Ada,Augusta Ada\n\
+             123,added,028A,c,,Lady\n\
+             123,deleted,050C,,$afoo,\n\
+             123,added,060R,,,$a1815\n\
+             999,added,,,,\n\
+             456,deleted,,,,\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn diff_json() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let mut cmd = pica_cmd();
+    let output = cmd
+        .args(["diff", "--format", "json"])
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .output()?;
+
+    assert!(output.status.success());
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        value,
+        serde_json::json!([
+            {
+                "key": "123",
+                "change": "modified",
+                "fields": [
+                    {
+                        "field": "028A",
+                        "change": "modified",
+                        "subfields": [
+                            {
+                                "code": "d",
+                                "change": "modified",
+                                "old": "Ada",
+                                "new": "Augusta Ada"
+                            },
+                            { "code": "c", "change": "added", "new": "Lady" }
+                        ]
+                    },
+                    { "field": "050C", "change": "deleted", "old": "$afoo" },
+                    { "field": "060R", "change": "added", "new": "$a1815" }
+                ]
+            },
+            { "key": "999", "change": "added" },
+            { "key": "456", "change": "deleted" }
+        ])
+    );
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn diff_key() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["diff", "--key", "002@.0"])
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("~ Tp1\n  ~ 003@ $0 456 -> 999\n"))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn diff_same() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("diff")
+        .arg(data_dir().join("ada.dat"))
+        .arg(data_dir().join("ada.dat"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn diff_duplicate_key() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["diff", "--key", "002@.0", "-s"])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .arg(data_dir().join("ada.dat"))
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("duplicate key"));
+
+    Ok(())
+}
+
+#[test]
+fn diff_duplicate_key_new() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let data = std::fs::read(data_dir().join("ada.dat"))?;
+    let new = temp_dir.child("new.dat");
+    new.write_binary(&[data.as_slice(), data.as_slice()].concat())?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("diff")
+        .arg(data_dir().join("ada.dat"))
+        .arg(new.path())
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains(
+            "duplicate key '119232022' in new records",
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn diff_reordered() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    temp_dir.child("old.dat").write_str(
+        "003@ \x1f0123\x1e028A \x1faLovelace\x1fdAda\x1e\n",
+    )?;
+    temp_dir.child("new.dat").write_str(
+        "003@ \x1f0123\x1e028A \x1fdAda\x1faLovelace\x1e\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("diff")
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "~ 123\n  ~ 028A $aLovelace$dAda -> $dAda$aLovelace\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["diff", "--format", "csv"])
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "key,change,field,code,old,new\n\
+             123,reordered,028A,,$aLovelace$dAda,$dAda$aLovelace\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let output = cmd
+        .args(["diff", "--format", "json"])
+        .arg(temp_dir.child("old.dat").path())
+        .arg(temp_dir.child("new.dat").path())
+        .output()?;
+
+    assert!(output.status.success());
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        value,
+        serde_json::json!([{
+            "key": "123",
+            "change": "modified",
+            "fields": [{
+                "field": "028A",
+                "change": "reordered",
+                "old": "$aLovelace$dAda",
+                "new": "$dAda$aLovelace"
+            }]
+        }])
+    );
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
@@ -666,6 +666,43 @@ fn filter_gzip() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn filter_compressed() -> TestResult {
+    for ext in ["zst", "xz", "bz2"] {
+        let mut cmd = pica_cmd();
+        let temp_dir = TempDir::new().unwrap();
+        let out = temp_dir.child(format!("out.dat.{ext}"));
+
+        let assert = cmd
+            .args(["filter", "-s", "003@.0 == '118540238'"])
+            .args(["-o", out.to_str().unwrap()])
+            .arg(data_dir().join("DUMP.dat.gz"))
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::str::is_empty())
+            .stderr(predicates::str::is_empty());
+
+        let mut cmd = pica_cmd();
+        let assert = cmd
+            .args(["select", "003@.0"])
+            .arg(out.to_str().unwrap())
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::ord::eq("118540238\n"))
+            .stderr(predicates::str::is_empty());
+
+        temp_dir.close().unwrap();
+    }
+
+    Ok(())
+}
+
 #[test]
 fn filter_append() -> TestResult {
     // Flag
@@ -714,6 +751,42 @@ fn filter_append() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn filter_append_gzip() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let out = temp_dir.child("out.dat.gz");
+
+    for filename in ["ada.dat", "goethe.dat"] {
+        let mut cmd = pica_cmd();
+        let assert = cmd
+            .args(["filter", "-s", "003@?", "--append"])
+            .args(["-o", out.to_str().unwrap()])
+            .arg(data_dir().join(filename))
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::str::is_empty())
+            .stderr(predicates::str::is_empty());
+    }
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["select", "003@.0"])
+        .arg(out.to_str().unwrap())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("119232022\n118540238\n"))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
 #[test]
 fn filter_tee() -> TestResult {
     let mut cmd = pica_cmd();
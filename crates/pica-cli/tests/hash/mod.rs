@@ -328,3 +328,87 @@ fn hash_filter_set_source() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn hash_discard() -> TestResult {
+    let hash = |data: &str| -> anyhow::Result<Vec<u8>> {
+        Ok(pica_cmd()
+            .args(["hash", "--discard", "001B"])
+            .write_stdin(data)
+            .output()?
+            .stdout)
+    };
+
+    let a = hash("001B \x1f01999:01-01-24\x1e003@ \x1f0123\x1e\n")?;
+    let b = hash("001B \x1f01999:02-01-24\x1e003@ \x1f0123\x1e\n")?;
+    let c = hash("003@ \x1f0123\x1e\n")?;
+
+    assert_eq!(a, b);
+    assert_eq!(a, c);
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["hash", "-s", "--discard", "001B"])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::ne(format!("ppn,hash\n{HASHES}")))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn hash_keep() -> TestResult {
+    let hash = |data: &str| -> anyhow::Result<Vec<u8>> {
+        Ok(pica_cmd()
+            .args(["hash", "--keep", "003@, 028A/*"])
+            .write_stdin(data)
+            .output()?
+            .stdout)
+    };
+
+    let a = hash("003@ \x1f0123\x1e028A \x1faX\x1e050C \x1faY\x1e\n")?;
+    let b = hash("003@ \x1f0123\x1e028A \x1faX\x1e050C \x1faZ\x1e\n")?;
+    let c = hash("003@ \x1f0123\x1e028A \x1faY\x1e050C \x1faZ\x1e\n")?;
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    Ok(())
+}
+
+#[test]
+fn hash_canonical() -> TestResult {
+    let hash = |args: &[&str], data: &str| -> anyhow::Result<Vec<u8>> {
+        Ok(pica_cmd()
+            .arg("hash")
+            .args(args)
+            .write_stdin(data)
+            .output()?
+            .stdout)
+    };
+
+    let a = "003@ \x1f0123\x1e002@ \x1f0Tp1\x1e\n";
+    let b = "002@ \x1f0Tp1 \x1e003@ \x1f0123\x1e012A \x1fa\x1e\n";
+
+    assert_ne!(hash(&[], a)?, hash(&[], b)?);
+    assert_eq!(hash(&["--canonical"], a)?, hash(&["--canonical"], b)?);
+
+    let normalized =
+        pica_cmd().arg("normalize").write_stdin(a).output()?;
+    assert_eq!(
+        hash(&["--canonical"], a)?,
+        hash(&[], &String::from_utf8(normalized.stdout)?)?
+    );
+
+    let a = "003@ \x1f0123\x1e021A \x1faAm\u{e9}lie\x1e\n";
+    let b = "003@ \x1f0123\x1e021A \x1faAme\u{301}lie\x1e\n";
+    assert_ne!(hash(&[], a)?, hash(&[], b)?);
+    assert_eq!(hash(&["--canonical"], a)?, hash(&["--canonical"], b)?);
+
+    Ok(())
+}
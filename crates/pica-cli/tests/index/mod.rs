@@ -0,0 +1,101 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+#[test]
+fn index_default() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let data = tempdir.child("DUMP.dat.gz");
+    data.write_binary(&std::fs::read(data_dir().join("DUMP.dat.gz"))?)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["index", "-s"])
+        .arg(data.to_str().unwrap())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    tempdir
+        .child("DUMP.dat.gz.idx")
+        .assert(predicates::path::exists());
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn index_output() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let out = tempdir.child("ada.idx");
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["index", "--path", "003U.a"])
+        .arg(data_dir().join("ada.dat"))
+        .args(["-o", out.to_str().unwrap()])
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    out.assert(predicates::path::exists());
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn index_invalid() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let out = tempdir.child("DUMP.idx");
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("index")
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", out.to_str().unwrap()])
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::starts_with(
+            "error: parse error: invalid record on line 12",
+        ));
+
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn index_unsupported_compression() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let out = tempdir.child("DUMP.idx");
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["index", "-s"])
+        .arg(data_dir().join("DUMP.dat.zst"))
+        .args(["-o", out.to_str().unwrap()])
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains(
+            "only uncompressed, gzip or BGZF compressed files are \
+             supported",
+        ));
+
+    tempdir.close().unwrap();
+    Ok(())
+}
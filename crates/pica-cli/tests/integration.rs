@@ -7,13 +7,20 @@ mod config;
 mod convert;
 mod count;
 mod describe;
+mod diff;
 mod explode;
 mod filter;
 mod frequency;
 mod hash;
+mod index;
 mod invalid;
+mod lookup;
+mod merge3;
+mod normalize;
 mod partition;
+mod patch;
 mod print;
+mod route;
 mod sample;
 mod select;
 mod slice;
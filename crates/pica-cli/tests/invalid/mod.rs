@@ -131,3 +131,91 @@ fn read_gzip_file() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn write_csv_report() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["invalid", "--csv"])
+        .write_stdin(
+            "003@ \x1f0123\x1e\n003@ \x1f!4\x1e\n003@ \x1f0456\n",
+        )
+        .assert();
+
+    let expected = "source,record,line,offset,tag,kind,message,data\n\
+        <stdin>,2,2,6,003@,invalid-subfield-code,\
+        invalid subfield code '!' at byte offset 6,\
+        003@ \x1f!4\x1e\n\
+        <stdin>,3,3,10,003@,unexpected-newline,\
+        unexpected newline at byte offset 10,003@ \x1f0456\n";
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(expected))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn write_tsv_report() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["invalid", "--tsv"])
+        .arg(data_dir().join("invalid.dat"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::starts_with(
+            "source\trecord\tline\toffset\ttag\tkind\tmessage\tdata\n",
+        ))
+        .stdout(predicates::str::contains(
+            "\t1\t1\t0\t003!\tinvalid-tag\t",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn write_csv_report_archive() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["invalid", "--csv"])
+        .arg(data_dir().join("archive.zip"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::contains(
+            "archive.zip!/README.txt,1,1,0,PICA+,invalid-tag,",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn write_csv_report_encoding() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["invalid", "--csv", "--encoding", "iso-8859-1"])
+        .arg(data_dir().join("latin1.dat"))
+        .assert();
+
+    let assert =
+        assert.success().code(0).stderr(predicates::str::is_empty());
+
+    // The data column contains the (invalid) input line unchanged.
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    assert!(stdout.contains(
+        "latin1.dat,2,2,49,041A,invalid-encoding,ambiguous byte 0x96"
+    ));
+    assert!(!stdout.contains("latin1.dat,1,"));
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+#[test]
+fn lookup_gzip() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let index = tempdir.child("DUMP.idx");
+
+    let mut cmd = pica_cmd();
+    cmd.args(["index", "-s"])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", index.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["lookup", "-I", index.to_str().unwrap()])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["040128997", "118540238", "123456789X"])
+        .assert();
+
+    let output = assert
+        .success()
+        .code(0)
+        .stderr(predicates::str::is_empty())
+        .get_output()
+        .stdout
+        .clone();
+
+    let mut cmd = pica_cmd();
+    let assert =
+        cmd.args(["select", "003@.0"]).write_stdin(output).assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("040128997\n118540238\n"))
+        .stderr(predicates::str::is_empty());
+
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn lookup_bgzf() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let data = tempdir.child("DUMP.dat.bgz");
+
+    let mut cmd = pica_cmd();
+    cmd.args(["filter", "-s", "003@?"])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", data.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let mut cmd = pica_cmd();
+    cmd.arg("index")
+        .arg(data.to_str().unwrap())
+        .assert()
+        .success();
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("lookup")
+        .arg(data.to_str().unwrap())
+        .args(["118540238", "040128997"])
+        .assert();
+
+    let output = assert
+        .success()
+        .code(0)
+        .stderr(predicates::str::is_empty())
+        .get_output()
+        .stdout
+        .clone();
+
+    let mut cmd = pica_cmd();
+    let assert =
+        cmd.args(["select", "003@.0"]).write_stdin(output).assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("118540238\n040128997\n"))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["count", "--records"])
+        .arg(data.to_str().unwrap())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq("12\n"))
+        .stderr(predicates::str::is_empty());
+
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn lookup_bgzf_gz_extension() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let bgzf = tempdir.child("y.dat.bgz");
+    let data = tempdir.child("y.dat.gz");
+
+    let mut cmd = pica_cmd();
+    cmd.arg("concat")
+        .arg(data_dir().join("ada.dat"))
+        .args(["-o", bgzf.to_str().unwrap()])
+        .assert()
+        .success();
+
+    std::fs::rename(bgzf.path(), data.path())?;
+
+    let mut cmd = pica_cmd();
+    cmd.arg("index")
+        .arg(data.to_str().unwrap())
+        .assert()
+        .success();
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("lookup")
+        .arg(data.to_str().unwrap())
+        .arg("119232022")
+        .assert();
+
+    let expected =
+        predicates::path::eq_file(data_dir().join("ada.dat"));
+    assert
+        .success()
+        .code(0)
+        .stdout(expected)
+        .stderr(predicates::str::is_empty());
+
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn lookup_sidecar() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let data = tempdir.child("ada.dat");
+    data.write_binary(&std::fs::read(data_dir().join("ada.dat"))?)?;
+
+    let mut cmd = pica_cmd();
+    cmd.arg("index")
+        .arg(data.to_str().unwrap())
+        .assert()
+        .success();
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("lookup")
+        .arg(data.to_str().unwrap())
+        .arg("119232022")
+        .assert();
+
+    let expected =
+        predicates::path::eq_file(data_dir().join("ada.dat"));
+    assert
+        .success()
+        .code(0)
+        .stdout(expected)
+        .stderr(predicates::str::is_empty());
+
+    tempdir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn lookup_out_of_date() -> TestResult {
+    let tempdir = TempDir::new().unwrap();
+    let data = tempdir.child("data.dat");
+    data.write_binary(&std::fs::read(data_dir().join("ada.dat"))?)?;
+
+    let mut cmd = pica_cmd();
+    cmd.arg("index")
+        .arg(data.to_str().unwrap())
+        .assert()
+        .success();
+
+    let mut record = std::fs::read(data_dir().join("ada.dat"))?;
+    record.splice(0..0, b"003@ \x1f0123\x1e\n".iter().copied());
+    data.write_binary(&record)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("lookup")
+        .arg(data.to_str().unwrap())
+        .arg("119232022")
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("is out of date"));
+
+    tempdir.close().unwrap();
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+const BASE: &str = "\
+003@ \x1f01\x1e002@ \x1f0Tp1\x1e028A \x1faLovelace\x1fdAda\x1e\
+050C \x1fafoo\x1e\n\
+003@ \x1f02\x1e002@ \x1f0Tp1\x1e\n\
+003@ \x1f03\x1e002@ \x1f0Tp1\x1e\n\
+003@ \x1f04\x1e002@ \x1f0Tp1\x1e\n";
+
+const OURS: &str = "\
+003@ \x1f01\x1e002@ \x1f0Tpz\x1e028A \x1faLovelace\x1fdAugusta Ada\x1e\
+050C \x1fabar\x1e\n\
+003@ \x1f03\x1e002@ \x1f0Tp1\x1e\n\
+003@ \x1f04\x1e002@ \x1f0Tp5\x1e\n\
+003@ \x1f05\x1e002@ \x1f0Ts1\x1e\n";
+
+const THEIRS: &str = "\
+003@ \x1f01\x1e002@ \x1f0Tp1\x1e028A \x1faLovelace\x1fdAda\x1e\
+050C \x1fabaz\x1e060R \x1fa1815\x1e\n\
+003@ \x1f02\x1e002@ \x1f0Tp1\x1e\n\
+003@ \x1f06\x1e002@ \x1f0Ts1\x1e\n";
+
+fn setup(temp_dir: &TempDir) -> anyhow::Result<()> {
+    temp_dir.child("base.dat").write_str(BASE)?;
+    temp_dir.child("ours.dat").write_str(OURS)?;
+    temp_dir.child("theirs.dat").write_str(THEIRS)?;
+    Ok(())
+}
+
+#[test]
+fn merge3_default() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("merge3")
+        .arg(temp_dir.child("base.dat").path())
+        .arg(temp_dir.child("ours.dat").path())
+        .arg(temp_dir.child("theirs.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f01\x1e002@ \x1f0Tpz\x1e\
+             028A \x1faLovelace\x1fdAugusta Ada\x1e\
+             050C \x1fabar\x1e060R \x1fa1815\x1e\n\
+             003@ \x1f04\x1e002@ \x1f0Tp5\x1e\n\
+             003@ \x1f05\x1e002@ \x1f0Ts1\x1e\n\
+             003@ \x1f06\x1e002@ \x1f0Ts1\x1e\n",
+        ))
+        .stderr(predicates::ord::eq(
+            "conflict: 1: 050C: base $afoo, ours $abar, theirs $abaz\n\
+             conflict: 4: ours modified, theirs deleted\n",
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn merge3_conflicts() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let conflicts = temp_dir.child("conflicts.csv");
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("merge3")
+        .arg("--conflicts")
+        .arg(conflicts.path())
+        .arg(temp_dir.child("base.dat").path())
+        .arg(temp_dir.child("ours.dat").path())
+        .arg(temp_dir.child("base.dat").path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(OURS))
+        .stderr(predicates::str::is_empty());
+
+    conflicts.assert("key,field,base,ours,theirs\n");
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("merge3")
+        .arg("--conflicts")
+        .arg(conflicts.path())
+        .arg(temp_dir.child("base.dat").path())
+        .arg(temp_dir.child("theirs.dat").path())
+        .arg(temp_dir.child("ours.dat").path())
+        .assert();
+
+    assert.success().code(0).stderr(predicates::str::is_empty());
+    conflicts.assert(
+        "key,field,base,ours,theirs\n\
+         1,050C,$afoo,$abaz,$abar\n\
+         4,,,deleted,modified\n",
+    );
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn merge3_duplicate_key() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["merge3", "--key", "002@.0"])
+        .arg(temp_dir.child("base.dat").path())
+        .arg(temp_dir.child("ours.dat").path())
+        .arg(temp_dir.child("theirs.dat").path())
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("duplicate key 'Tp1'"));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn merge3_add_add() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.child("base.dat");
+    base.write_str("003@ \x1f01\x1e002@ \x1f0Tp1\x1e\n")?;
+    let ours = temp_dir.child("ours.dat");
+    ours.write_str(
+        "003@ \x1f01\x1e002@ \x1f0Tp1\x1e050C \x1fabar\x1e\n",
+    )?;
+    let theirs = temp_dir.child("theirs.dat");
+    theirs.write_str(
+        "003@ \x1f01\x1e002@ \x1f0Tp1\x1e050C \x1fabaz\x1e\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("merge3")
+        .arg(base.path())
+        .arg(ours.path())
+        .arg(theirs.path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f01\x1e002@ \x1f0Tp1\x1e050C \x1fabar\x1e\n",
+        ))
+        .stderr(predicates::ord::eq(
+            "conflict: 1: 050C: base (none), ours $abar, theirs $abaz\n",
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn merge3_duplicate_key_ours() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    setup(&temp_dir)?;
+
+    let ours = temp_dir.child("ours.dat");
+    ours.write_str(&format!(
+        "{OURS}003@ \x1f01\x1e002@ \x1f0Tp1\x1e\n"
+    ))?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("merge3")
+        .arg(temp_dir.child("base.dat").path())
+        .arg(ours.path())
+        .arg(temp_dir.child("theirs.dat").path())
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stderr(predicates::str::contains("duplicate key '1'"));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
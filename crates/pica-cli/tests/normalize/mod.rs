@@ -0,0 +1,142 @@
+use std::fs::read_to_string;
+
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+#[test]
+fn normalize_stdin() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("normalize")
+        .write_stdin(
+            "012A \x1fa \x1e003@ \x1f0 123 \x1f1\x1e002@ \x1f0Tp1\x1e\n",
+        )
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "002@ \x1f0Tp1\x1e003@ \x1f0123\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn normalize_keep_order() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["normalize", "--keep-order"])
+        .write_stdin("003@ \x1f0123\x1e002@ \x1f0Tp1 \x1e\n")
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e002@ \x1f0Tp1\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn normalize_local_and_copy() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("normalize")
+        .write_stdin(
+            "003@ \x1f0123\x1e\
+             101@ \x1fa1\x1e\
+             209A/02 \x1faB\x1e\
+             203@/02 \x1f02\x1e\
+             203@/01 \x1f01\x1e\
+             002@ \x1f0Tp1\x1e\n",
+        )
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "002@ \x1f0Tp1\x1e\
+             003@ \x1f0123\x1e\
+             101@ \x1fa1\x1e\
+             203@/01 \x1f01\x1e\
+             203@/02 \x1f02\x1e\
+             209A/02 \x1faB\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn normalize_translit() -> TestResult {
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("normalize")
+        .write_stdin("003@ \x1f0123\x1e021A \x1faCafe\u{301}\x1e\n")
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e021A \x1faCaf\u{e9}\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["normalize", "--translit", "nfd"])
+        .write_stdin("003@ \x1f0123\x1e021A \x1faCaf\u{e9}\x1e\n")
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e021A \x1faCafe\u{301}\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn normalize_idempotent() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.child("first.dat");
+    let second = temp_dir.child("second.dat");
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["normalize", "-s"])
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .arg("-o")
+        .arg(first.path())
+        .assert();
+    assert.success().code(0).stderr(predicates::str::is_empty());
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("normalize")
+        .arg(first.path())
+        .arg("-o")
+        .arg(second.path())
+        .assert();
+    assert.success().code(0).stderr(predicates::str::is_empty());
+
+    assert_eq!(
+        read_to_string(first.path())?,
+        read_to_string(second.path())?
+    );
+    temp_dir.close().unwrap();
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+const RECORDS: &str = "\
+003@ \x1f0123\x1e028A \x1faLovelace\x1fdAda\x1e050C \x1fafoo\x1e\n\
+003@ \x1f0456\x1e\n";
+
+const CHANGES: &str = "\
+key,change,field,code,old,new
+123,modified,028A,d,Ada,Augusta Ada
+123,added,028A,c,,Lady
+123,deleted,050C,,$afoo,
+123,added,060R,,,$a1815
+789,added,,,,
+";
+
+#[test]
+fn patch_default() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let changes = temp_dir.child("changes.csv");
+    changes.write_str(CHANGES)?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("patch")
+        .arg(changes.path())
+        .write_stdin(RECORDS)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "003@ \x1f0123\x1e\
+             028A \x1faLovelace\x1fdAugusta Ada\x1fcLady\x1e\
+             060R \x1fa1815\x1e\n\
+             003@ \x1f0456\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn patch_diff() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let old = temp_dir.child("old.dat");
+    old.write_str(RECORDS)?;
+
+    let new = temp_dir.child("new.dat");
+    new.write_str(
+        "003@ \x1f0123\x1e002@ \x1f0Tp1\x1e\
+         028A \x1faLovelace\x1fdAugusta Ada\x1e\n",
+    )?;
+
+    let changes = temp_dir.child("changes.csv");
+    let mut cmd = pica_cmd();
+    cmd.args(["diff", "-f", "csv", "-o"])
+        .arg(changes.path())
+        .arg(old.path())
+        .arg(new.path())
+        .assert()
+        .success();
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("patch")
+        .arg(changes.path())
+        .arg(old.path())
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::ord::eq(
+            "002@ \x1f0Tp1\x1e003@ \x1f0123\x1e\
+             028A \x1faLovelace\x1fdAugusta Ada\x1e\n\
+             003@ \x1f0456\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn patch_tsv() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let changes = temp_dir.child("changes.tsv");
+    changes.write_str(
+        "key\tchange\tfield\tcode\told\tnew\n\
+         456\tadd\t002@\t\t\t$0Tp1\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("patch")
+        .arg(changes.path())
+        .write_stdin(RECORDS)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::ends_with(
+            "002@ \x1f0Tp1\x1e003@ \x1f0456\x1e\n",
+        ))
+        .stderr(predicates::str::is_empty());
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn patch_conflicts() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let changes = temp_dir.child("changes.csv");
+    changes.write_str(
+        "key,change,field,code,old,new\n\
+         123,replace,028A,d,Augusta Ada,Ada\n\
+         123,remove,044H/*,,,\n\
+         123,replace,028A,a,,Byron\n\
+         999,remove,050C,,,\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("patch")
+        .arg(changes.path())
+        .write_stdin(RECORDS)
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::starts_with(
+            "003@ \x1f0123\x1e028A \x1faByron\x1fdAda\x1e",
+        ))
+        .stderr(predicates::ord::eq(
+            "conflict: 123: replace 028A $d: old value doesn't match\n\
+             conflict: 123: remove 044H/*: field not found\n\
+             conflict: 999: remove 050C: record not found\n",
+        ));
+
+    let conflicts = temp_dir.child("conflicts.csv");
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("patch")
+        .arg("--conflicts")
+        .arg(conflicts.path())
+        .arg(changes.path())
+        .write_stdin(RECORDS)
+        .assert();
+
+    assert.success().code(0).stderr(predicates::str::is_empty());
+    conflicts.assert(
+        "key,change,field,code,old,new,conflict\n\
+         123,replace,028A,d,Augusta Ada,Ada,old value doesn't match\n\
+         123,remove,044H/*,,,,field not found\n\
+         999,remove,050C,,,,record not found\n",
+    );
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn patch_invalid_change() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let changes = temp_dir.child("changes.csv");
+    changes.write_str(
+        "key,change,field,code,old,new\n\
+         123,add,044H/*,,,$afoo\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("patch")
+        .arg(changes.path())
+        .write_stdin(RECORDS)
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::ord::eq(
+            "error: invalid change in line 2: invalid field '044H/*'\n",
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+
+use crate::prelude::*;
+
+#[test]
+fn route_first_match() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let routes = temp_dir.child("routes.toml");
+    routes.write_str(
+        "[[route]]\nfilter = \"002@.0 =^ 'Tp'\"\noutput = \"tp.dat.gz\"\n\n\
+         [[route]]\nfilter = \"002@.0 =^ 'Ts'\"\noutput = \"ts.dat\"\n\n\
+         [[route]]\noutput = \"rest.dat\"\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["route", "-s"])
+        .arg(routes.path())
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", temp_dir.to_str().unwrap()])
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    for (filename, expected) in
+        [("tp.dat.gz", "2\n"), ("ts.dat", "3\n"), ("rest.dat", "7\n")]
+    {
+        let mut cmd = pica_cmd();
+        let assert = cmd
+            .args(["count", "--records"])
+            .arg(temp_dir.join(filename))
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::ord::eq(expected))
+            .stderr(predicates::str::is_empty());
+    }
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn route_all() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let routes = temp_dir.child("routes.toml");
+    routes.write_str(
+        "[[route]]\noutput = \"all.dat\"\n\n\
+         [[route]]\nfilter = \"002@.0 =^ 'Tp'\"\noutput = \"tp.dat\"\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["route", "-s", "--all"])
+        .arg(routes.path())
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", temp_dir.to_str().unwrap()])
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    for (filename, expected) in [("all.dat", "12\n"), ("tp.dat", "2\n")]
+    {
+        let mut cmd = pica_cmd();
+        let assert = cmd
+            .args(["count", "--records"])
+            .arg(temp_dir.join(filename))
+            .assert();
+
+        assert
+            .success()
+            .code(0)
+            .stdout(predicates::ord::eq(expected))
+            .stderr(predicates::str::is_empty());
+    }
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn route_where() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let routes = temp_dir.child("routes.toml");
+    routes.write_str("[[route]]\noutput = \"goethe.dat\"\n")?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .args(["route", "-s", "--where", "003@.0 == '118540238'"])
+        .arg(routes.path())
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", temp_dir.to_str().unwrap()])
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    temp_dir
+        .child("goethe.dat")
+        .assert(predicates::path::eq_file(
+            data_dir().join("goethe.dat"),
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn route_duplicate_output() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let routes = temp_dir.child("routes.toml");
+    routes.write_str(
+        "[[route]]\nfilter = \"002@.0 =^ 'Tp'\"\noutput = \"out.dat\"\n\n\
+         [[route]]\noutput = \"out.dat\"\n",
+    )?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("route")
+        .arg(routes.path())
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", temp_dir.to_str().unwrap()])
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains(
+            "error: duplicate output 'out.dat' in routing table",
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn route_invalid_table() -> TestResult {
+    let temp_dir = TempDir::new().unwrap();
+    let routes = temp_dir.child("routes.toml");
+    routes.write_str("[[route]]\nfilter = \"002@.0 =^ 'Tp'\"\n")?;
+
+    let mut cmd = pica_cmd();
+    let assert = cmd
+        .arg("route")
+        .arg(routes.path())
+        .arg(data_dir().join("DUMP.dat.gz"))
+        .args(["-o", temp_dir.to_str().unwrap()])
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains(
+            "error: invalid routing table",
+        ));
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
@@ -1,7 +1,9 @@
-use std::fs::read_to_string;
+use std::fs::{File, read_dir, read_to_string};
+use std::io::Read;
 
 use assert_fs::TempDir;
 use assert_fs::prelude::*;
+use flate2::read::GzDecoder;
 
 use super::prelude::*;
 
@@ -53,6 +55,63 @@ fn select_csv_output() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn select_compressed_output() -> TestResult {
+    let mut cmd = pica_cmd();
+    let temp_dir = TempDir::new().unwrap();
+    let out = temp_dir.child("out.csv.gz");
+
+    let assert = cmd
+        .args(["select", "003@.0,002@.0"])
+        .args(["-o", out.to_str().unwrap()])
+        .arg(data_dir().join("algebra.dat"))
+        .arg(data_dir().join("ada.dat"))
+        .assert();
+
+    assert
+        .success()
+        .code(0)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::is_empty());
+
+    let mut data = String::new();
+    GzDecoder::new(File::open(out.path())?)
+        .read_to_string(&mut data)?;
+    assert_eq!(data, "040011569,Ts1\n119232022,Tp1\n");
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
+#[test]
+fn select_atomic_output() -> TestResult {
+    let mut cmd = pica_cmd();
+    let temp_dir = TempDir::new().unwrap();
+    let out = temp_dir.child("out.csv");
+    out.write_str("ppn,bbg\n")?;
+
+    let assert = cmd
+        .args(["select", "003@.0,002@.0"])
+        .args(["-o", out.to_str().unwrap()])
+        .arg(data_dir().join("ada.dat"))
+        .arg(data_dir().join("invalid.dat"))
+        .assert();
+
+    assert
+        .failure()
+        .code(2)
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("parse error"));
+
+    // The existing output is left untouched and no temporary file
+    // is left behind.
+    assert_eq!(read_to_string(out.path())?, "ppn,bbg\n");
+    assert_eq!(read_dir(temp_dir.path())?.count(), 1);
+
+    temp_dir.close().unwrap();
+    Ok(())
+}
+
 #[test]
 fn select_tsv_stdout() -> TestResult {
     let mut cmd = pica_cmd();
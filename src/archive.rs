@@ -0,0 +1,789 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+
+use globset::{Glob, GlobMatcher};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::reader::{
+    ReadPicaError, Reader, ReaderBuilder, RecordsIter, decompress, open,
+};
+use crate::{ByteRecord, StringRecord};
+
+/// The kind of an archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Tar,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Returns the kind of archive derived from the filename.
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tgz")
+            || [".tar", ".tar.gz", ".tar.zst", ".tar.xz", ".tar.bz2"]
+                .iter()
+                .any(|ext| name.ends_with(ext))
+        {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// A request to the thread, which reads an archive.
+enum Request {
+    /// Advance to the next regular file.
+    Next,
+    /// Read up to `len` bytes of the member with the given index.
+    Read { index: usize, len: usize },
+}
+
+/// A reply of the thread, which reads an archive.
+enum Reply {
+    Member(io::Result<Option<String>>),
+    Data(io::Result<Vec<u8>>),
+}
+
+/// An opened archive, whose members are read one after another.
+///
+/// The entries of a tar or zip archive borrow the archive (see
+/// [tar::Archive::entries] and [ZipArchive::by_index]), whereas the
+/// readers of the members are owned by the caller. Therefore, the
+/// archive is read by a thread of its own, which serves the requests
+/// of the [Sources] iterator and of the member readers.
+struct Archive {
+    requests: Sender<Request>,
+    replies: Receiver<Reply>,
+    members: Cell<usize>,
+}
+
+impl Archive {
+    /// Opens the archive at the given path.
+    ///
+    /// An error, which occurs when the archive is opened, is returned
+    /// by the first call of [Archive::next_member].
+    fn open(path: &Path, kind: ArchiveKind) -> Rc<Self> {
+        let (requests, rx) = channel();
+        let (tx, replies) = channel();
+        let server = Server {
+            requests: rx,
+            replies: tx,
+        };
+
+        let path = path.to_path_buf();
+        thread::spawn(move || match kind {
+            ArchiveKind::Tar => server.run_tar(&path),
+            ArchiveKind::Zip => server.run_zip(&path),
+        });
+
+        Rc::new(Self {
+            members: Cell::new(0),
+            requests,
+            replies,
+        })
+    }
+
+    /// Sends a request and waits for the reply.
+    fn request(&self, request: Request) -> io::Result<Reply> {
+        self.requests
+            .send(request)
+            .ok()
+            .and_then(|()| self.replies.recv().ok())
+            .ok_or_else(|| {
+                io::Error::other("archive reader terminated")
+            })
+    }
+
+    /// Returns the name and a reader of the next regular file or
+    /// `None`, if the end of the archive is reached.
+    ///
+    /// The data of a member is streamed; a reader of a member becomes
+    /// invalid as soon as the archive advances to the next member.
+    fn next_member(
+        self: &Rc<Self>,
+    ) -> io::Result<Option<(String, Box<dyn Read>)>> {
+        let Reply::Member(result) = self.request(Request::Next)? else {
+            return Err(io::Error::other("unexpected reply"));
+        };
+
+        let Some(name) = result? else {
+            return Ok(None);
+        };
+
+        let index = self.members.get() + 1;
+        self.members.set(index);
+
+        let member = Member {
+            archive: Rc::clone(self),
+            index,
+        };
+
+        Ok(Some((name, Box::new(member))))
+    }
+}
+
+/// A reader of an archive member.
+struct Member {
+    archive: Rc<Archive>,
+    index: usize,
+}
+
+impl Read for Member {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let request = Request::Read {
+            index: self.index,
+            len: buf.len(),
+        };
+
+        let Reply::Data(result) = self.archive.request(request)? else {
+            return Err(io::Error::other("unexpected reply"));
+        };
+
+        let data = result?;
+        buf[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+}
+
+/// The thread, which reads the entries of an archive.
+struct Server {
+    requests: Receiver<Request>,
+    replies: Sender<Reply>,
+}
+
+impl Server {
+    /// Reads the regular files of a tar archive.
+    fn run_tar(self, path: &Path) {
+        if !self.wait(None, 0) {
+            return;
+        }
+
+        let mut archive = match open(path, 1) {
+            Ok(reader) => tar::Archive::new(reader),
+            Err(e) => return self.fail(e),
+        };
+
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(e) => return self.fail(e),
+        };
+
+        let mut members = 0;
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return self.fail(e),
+            };
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let name = match entry.path() {
+                Ok(path) => {
+                    let path = path.strip_prefix("./").unwrap_or(&path);
+                    path.to_string_lossy().into_owned()
+                }
+                Err(e) => return self.fail(e),
+            };
+
+            // The data of a truncated entry ends prematurely without
+            // an error.
+            let mut entry = Exact {
+                remaining: entry.size(),
+                inner: entry,
+            };
+
+            members += 1;
+            if !self.reply(Reply::Member(Ok(Some(name))))
+                || !self.wait(Some(&mut entry), members)
+            {
+                return;
+            }
+        }
+
+        self.finish();
+    }
+
+    /// Reads the regular files of a zip archive.
+    fn run_zip(self, path: &Path) {
+        if !self.wait(None, 0) {
+            return;
+        }
+
+        let mut archive = match File::open(path).and_then(|file| {
+            ZipArchive::new(file).map_err(invalid_data)
+        }) {
+            Ok(archive) => archive,
+            Err(e) => return self.fail(e),
+        };
+
+        let mut members = 0;
+        for i in 0..archive.len() {
+            let mut file = match archive.by_index(i) {
+                Ok(file) => file,
+                Err(e) => return self.fail(invalid_data(e)),
+            };
+
+            if !file.is_file() {
+                continue;
+            }
+
+            members += 1;
+            let name = file.name().to_string();
+            if !self.reply(Reply::Member(Ok(Some(name))))
+                || !self.wait(Some(&mut file), members)
+            {
+                return;
+            }
+        }
+
+        self.finish();
+    }
+
+    /// Serves the read requests of the current member, until the next
+    /// member is requested. Returns `false`, if the archive was
+    /// dropped.
+    fn wait(
+        &self,
+        mut member: Option<&mut dyn Read>,
+        index: usize,
+    ) -> bool {
+        while let Ok(request) = self.requests.recv() {
+            let (i, len) = match request {
+                Request::Next => return true,
+                Request::Read { index: i, len } => (i, len),
+            };
+
+            let result = match member {
+                Some(ref mut reader) if i == index => {
+                    let mut buf = vec![0; len];
+                    reader.read(&mut buf).map(|n| {
+                        buf.truncate(n);
+                        buf
+                    })
+                }
+                _ => Err(io::Error::other(
+                    "archive member is no longer available",
+                )),
+            };
+
+            if !self.reply(Reply::Data(result)) {
+                return false;
+            }
+        }
+
+        false
+    }
+
+    /// Replies to all further requests, after the end of the archive
+    /// was reached.
+    fn finish(self) {
+        while self.reply(Reply::Member(Ok(None))) {
+            if !self.wait(None, 0) {
+                break;
+            }
+        }
+    }
+
+    /// Replies to the pending request with an error.
+    fn fail(self, err: io::Error) {
+        self.reply(Reply::Member(Err(err)));
+    }
+
+    #[inline]
+    fn reply(&self, reply: Reply) -> bool {
+        self.replies.send(reply).is_ok()
+    }
+}
+
+/// A reader, which fails if the data ends before the expected number
+/// of bytes was read.
+struct Exact<R: Read> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: Read> Read for Exact<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n == 0 && !buf.is_empty() && self.remaining > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of file",
+            ));
+        }
+
+        self.remaining = self.remaining.saturating_sub(n as u64);
+        Ok(n)
+    }
+}
+
+#[inline]
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// A path, which is visited by [Sources].
+struct Entry {
+    path: PathBuf,
+    /// The path relative to the directory, in which the file was
+    /// found, or `None`, if the path was given explicitly.
+    relative: Option<String>,
+}
+
+/// An iterator over the inputs of a path.
+///
+/// The path can be a single file (or `-` for stdin), a directory or a
+/// tar or zip archive. A directory is traversed recursively (in
+/// lexicographical order), and the members of an archive are read one
+/// after another. Archives, which are found within a directory, are
+/// expanded as well. The members of an archive are streamed, i.e. they
+/// are never read into memory as a whole (compressed members are
+/// decompressed while reading the records). A reader of an archive
+/// member must be consumed before the next input is requested.
+///
+/// If a glob pattern is set (see [ReaderBuilder::glob]), only files
+/// within a directory and members of an archive whose (relative) path
+/// matches the pattern are read. The source of each reader is the
+/// path of the file or, for an archive member, the path of the archive
+/// followed by `!/` and the name of the member.
+pub struct Sources {
+    builder: ReaderBuilder,
+    glob: Option<GlobMatcher>,
+    entries: VecDeque<Entry>,
+    archive: Option<(String, Rc<Archive>)>,
+}
+
+impl Sources {
+    /// Creates a new iterator over the inputs of the given path.
+    pub(crate) fn new(
+        builder: &ReaderBuilder,
+        path: &Path,
+    ) -> io::Result<Self> {
+        let glob = match builder.glob {
+            Some(ref pattern) => Some(
+                Glob::new(pattern)
+                    .map_err(|e| {
+                        io::Error::new(io::ErrorKind::InvalidInput, e)
+                    })?
+                    .compile_matcher(),
+            ),
+            None => None,
+        };
+
+        let mut entries = VecDeque::new();
+        if path.is_dir() {
+            for entry in WalkDir::new(path)
+                .follow_links(true)
+                .sort_by_file_name()
+            {
+                let entry = entry.map_err(io::Error::from)?;
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+
+                let relative = entry
+                    .path()
+                    .strip_prefix(path)
+                    .unwrap_or(entry.path())
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                entries.push_back(Entry {
+                    path: entry.into_path(),
+                    relative: Some(relative),
+                });
+            }
+        } else {
+            entries.push_back(Entry {
+                path: path.into(),
+                relative: None,
+            });
+        }
+
+        Ok(Self {
+            builder: builder.clone(),
+            archive: None,
+            entries,
+            glob,
+        })
+    }
+
+    /// Returns `true` if the name matches the glob pattern.
+    #[inline]
+    fn is_match(&self, name: &str) -> bool {
+        self.glob.as_ref().is_none_or(|glob| glob.is_match(name))
+    }
+}
+
+impl Iterator for Sources {
+    type Item = io::Result<Reader<Box<dyn Read>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((ref name, ref archive)) = self.archive {
+                match archive.next_member() {
+                    Err(e) => {
+                        let e = io::Error::new(
+                            e.kind(),
+                            format!("{e} ({name})"),
+                        );
+                        self.archive = None;
+                        return Some(Err(e));
+                    }
+                    Ok(None) => {
+                        self.archive = None;
+                        continue;
+                    }
+                    Ok(Some((member, reader))) => {
+                        if !self.is_match(&member) {
+                            continue;
+                        }
+
+                        let source = format!("{name}!/{member}");
+                        let reader =
+                            decompress(reader, Path::new(&member), 1);
+
+                        return Some(reader.map(|reader| {
+                            self.builder
+                                .from_reader(reader, Some(source))
+                        }));
+                    }
+                }
+            }
+
+            let entry = self.entries.pop_front()?;
+            if let Some(kind) = ArchiveKind::from_path(&entry.path) {
+                let name = entry.path.to_string_lossy().into();
+                self.archive =
+                    Some((name, Archive::open(&entry.path, kind)));
+                continue;
+            }
+
+            if let Some(ref relative) = entry.relative
+                && !self.is_match(relative)
+            {
+                continue;
+            }
+
+            return Some(self.builder.from_path(entry.path));
+        }
+    }
+}
+
+/// A reader, which reads the records of all inputs of a path.
+///
+/// This reader is created by [ReaderBuilder::from_sources] and reads
+/// the records of all files of a directory or all members of an
+/// archive one after another (see [Sources]).
+pub struct MultiReader {
+    sources: Sources,
+    current: Option<Reader<Box<dyn Read>>>,
+}
+
+impl MultiReader {
+    /// Creates a new [MultiReader].
+    pub(crate) fn new(sources: Sources) -> Self {
+        Self {
+            current: None,
+            sources,
+        }
+    }
+
+    /// Returns the source of the current input, if known.
+    #[inline]
+    pub fn source(&self) -> Option<&str> {
+        self.current.as_ref().and_then(Reader::source)
+    }
+
+    /// Advances to the next record of the current or the next input.
+    fn advance(&mut self) -> Option<Result<(), ReadPicaError>> {
+        loop {
+            let reader = match self.current {
+                Some(ref mut reader) => reader,
+                None => match self.sources.next()? {
+                    Ok(reader) => self.current.insert(reader),
+                    Err(e) => return Some(Err(e.into())),
+                },
+            };
+
+            match reader.read_next() {
+                None => self.current = None,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl RecordsIter for MultiReader {
+    type ByteItem<'a>
+        = Result<ByteRecord<'a>, ReadPicaError>
+    where
+        Self: 'a;
+
+    type StringItem<'a>
+        = Result<StringRecord<'a>, ReadPicaError>
+    where
+        Self: 'a;
+
+    /// Advance the iterator and return the next [ByteRecord].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_sources("tests/data/archive.zip")?;
+    ///
+    /// let mut ppns = vec![];
+    /// while let Some(result) = reader.next_byte_record() {
+    ///     if let Ok(record) = result {
+    ///         ppns.push(record.ppn().unwrap().to_string());
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(ppns, vec!["119232022", "118540238"]);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn next_byte_record(&mut self) -> Option<Self::ByteItem<'_>> {
+        if let Err(e) = self.advance()? {
+            return Some(Err(e));
+        }
+
+        self.current.as_ref().map(Reader::byte_record)
+    }
+
+    /// Advance the iterator and return the next [StringRecord].
+    fn next_string_record(&mut self) -> Option<Self::StringItem<'_>> {
+        if let Err(e) = self.advance()? {
+            return Some(Err(e));
+        }
+
+        self.current.as_ref().map(Reader::string_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::LazyLock;
+
+    use super::*;
+    use crate::path::PathExt;
+
+    static DATA_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data")
+    });
+
+    fn sources(builder: &ReaderBuilder, path: &Path) -> Vec<String> {
+        builder
+            .sources(path)
+            .unwrap()
+            .map(|reader| reader.unwrap().source().unwrap().to_string())
+            .collect()
+    }
+
+    fn read_members(
+        path: &Path,
+        kind: ArchiveKind,
+    ) -> io::Result<Vec<(String, Vec<u8>)>> {
+        let archive = Archive::open(path, kind);
+        let mut members = vec![];
+
+        while let Some((name, mut reader)) = archive.next_member()? {
+            let mut data = vec![];
+            reader.read_to_end(&mut data)?;
+            members.push((name, data));
+        }
+
+        Ok(members)
+    }
+
+    #[test]
+    fn test_archive() -> anyhow::Result<()> {
+        let ada = std::fs::read(DATA_DIR.join("ada.dat"))?;
+
+        for (filename, kind) in [
+            ("archive.tar", ArchiveKind::Tar),
+            ("archive.tar.gz", ArchiveKind::Tar),
+            ("archive.zip", ArchiveKind::Zip),
+        ] {
+            let members = read_members(&DATA_DIR.join(filename), kind)?;
+            let names = members
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                names,
+                ["ada.dat", "records/goethe.dat.gz", "README.txt"]
+            );
+
+            assert_eq!(members[0].1, ada);
+            assert!(members[2].1.starts_with(b"PICA"));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_invalid() -> anyhow::Result<()> {
+        let data = std::fs::read(DATA_DIR.join("archive.tar"))?;
+        let pos = data.windows(5).position(|w| w == b"003@ ").unwrap();
+
+        let mut file = tempfile::NamedTempFile::new()?;
+        file.write_all(&data[..pos + 10])?;
+
+        let archive = Archive::open(file.path(), ArchiveKind::Tar);
+        let (name, mut reader) = archive.next_member()?.unwrap();
+        assert_eq!(name, "ada.dat");
+        assert!(reader.read_to_end(&mut vec![]).is_err());
+
+        for kind in [ArchiveKind::Tar, ArchiveKind::Zip] {
+            let path = DATA_DIR.join("ada.dat");
+            assert!(read_members(&path, kind).is_err());
+
+            let path = DATA_DIR.join("missing.tar");
+            assert!(read_members(&path, kind).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_member_invalidated() -> anyhow::Result<()> {
+        for (filename, kind) in [
+            ("archive.tar", ArchiveKind::Tar),
+            ("archive.zip", ArchiveKind::Zip),
+        ] {
+            let archive = Archive::open(&DATA_DIR.join(filename), kind);
+            let (_, mut first) = archive.next_member()?.unwrap();
+            let (_, mut second) = archive.next_member()?.unwrap();
+            assert!(first.read(&mut [0; 8]).is_err());
+            assert!(second.read(&mut [0; 8])? > 0);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sources() {
+        let builder = ReaderBuilder::new();
+        let archive = DATA_DIR.join("archive.tar.gz");
+        let name = archive.to_string_lossy();
+
+        assert_eq!(
+            sources(&builder, &archive),
+            vec![
+                format!("{name}!/ada.dat"),
+                format!("{name}!/records/goethe.dat.gz"),
+                format!("{name}!/README.txt"),
+            ]
+        );
+
+        let builder = ReaderBuilder::new().glob("*.dat*");
+        let archive = DATA_DIR.join("archive.zip");
+        let name = archive.to_string_lossy();
+
+        assert_eq!(
+            sources(&builder, &archive),
+            vec![
+                format!("{name}!/ada.dat"),
+                format!("{name}!/records/goethe.dat.gz"),
+            ]
+        );
+
+        let file = DATA_DIR.join("ada.dat");
+        assert_eq!(
+            sources(&builder, &file),
+            vec![file.to_string_lossy().to_string()]
+        );
+
+        assert!(ReaderBuilder::new().glob("[").sources(&file).is_err());
+    }
+
+    #[test]
+    fn test_sources_dir() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("2024"))?;
+        std::fs::copy(
+            DATA_DIR.join("ada.dat"),
+            dir.path().join("2024/ada.dat"),
+        )?;
+        std::fs::copy(
+            DATA_DIR.join("archive.tar"),
+            dir.path().join("archive.tar"),
+        )?;
+        std::fs::write(dir.path().join("notes.txt"), b"notes")?;
+
+        let builder = ReaderBuilder::new().glob("*.dat");
+        let mut reader = builder.from_sources(dir.path())?;
+        let mut sources = vec![];
+
+        while let Some(result) = reader.next_byte_record() {
+            let ppn = result?.ppn().unwrap().to_string();
+            sources.push(format!("{} {ppn}", reader.source().unwrap()));
+        }
+
+        let prefix = dir.path().to_string_lossy();
+        assert_eq!(
+            sources,
+            vec![
+                format!("{prefix}/2024/ada.dat 119232022"),
+                format!("{prefix}/archive.tar!/ada.dat 119232022"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_provenance() -> anyhow::Result<()> {
+        let archive = DATA_DIR.join("archive.zip");
+        let name = archive.to_string_lossy();
+
+        let mut reader = ReaderBuilder::new()
+            .glob("*.dat*")
+            .from_sources(&archive)?;
+        let mut provenance = vec![];
+
+        while let Some(result) = reader.next_byte_record() {
+            let record = result?;
+            let p = record.provenance().unwrap();
+            provenance.push((
+                p.source().unwrap().to_string(),
+                p.index(),
+                p.offset(),
+            ));
+        }
+
+        assert_eq!(
+            provenance,
+            vec![
+                (format!("{name}!/ada.dat"), 1, Some(0)),
+                (format!("{name}!/records/goethe.dat.gz"), 1, Some(0)),
+            ]
+        );
+
+        Ok(())
+    }
+}
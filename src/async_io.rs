@@ -0,0 +1,564 @@
+use std::future::Future;
+use std::io;
+use std::path::Path;
+
+use async_compression::Level;
+use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::write::GzipEncoder;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncWrite,
+    AsyncWriteExt, BufReader, BufWriter,
+};
+
+use crate::encoding::transcode;
+use crate::reader::{InputFormat, ReadPicaError, ReaderBuilder};
+use crate::repair::{ParseMode, Policy, Repairs, is_blank, repair};
+use crate::writer::{Compression, WriterBuilder};
+use crate::{ByteRecord, StringRecord};
+
+/// An async counterpart of [RecordsIter](crate::io::RecordsIter).
+pub trait AsyncRecordsIter {
+    type ByteItem<'a>
+    where
+        Self: 'a;
+
+    type StringItem<'a>
+    where
+        Self: 'a;
+
+    fn next_byte_record(
+        &mut self,
+    ) -> impl Future<Output = Option<Self::ByteItem<'_>>> + Send;
+
+    fn next_string_record(
+        &mut self,
+    ) -> impl Future<Output = Option<Self::StringItem<'_>>> + Send;
+}
+
+/// An async counterpart of
+/// [ByteRecordWrite](crate::io::ByteRecordWrite).
+pub trait AsyncByteRecordWrite {
+    /// Writes a [ByteRecord] into this writer.
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Finish the underlying writer.
+    fn finish(&mut self)
+    -> impl Future<Output = io::Result<()>> + Send;
+}
+
+/// A boxed async reader, which is returned by the [ReaderBuilder].
+pub type BoxAsyncBufRead = Box<dyn AsyncBufRead + Unpin + Send>;
+
+/// An async PICA+ reader.
+pub struct AsyncReader<R: AsyncBufRead + Unpin> {
+    inner: R,
+    source: Option<String>,
+    line: usize,
+    policy: Policy,
+    repairs: Repairs,
+    scratch: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncReader<R> {
+    /// Creates a new [AsyncReader].
+    pub(crate) fn new(
+        inner: R,
+        source: Option<String>,
+        policy: Policy,
+    ) -> Self {
+        let source = source
+            .map(|s| if s == "-" { "<stdin>".to_string() } else { s });
+
+        Self {
+            repairs: Repairs::default(),
+            scratch: Vec::new(),
+            buf: Vec::new(),
+            policy,
+            line: 0,
+            inner,
+            source,
+        }
+    }
+
+    /// Returns the counters of the defects, which were repaired so
+    /// far (see [ReaderBuilder::mode]).
+    #[inline]
+    pub fn repairs(&self) -> &Repairs {
+        &self.repairs
+    }
+
+    /// Consumes the reader and returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncBufRead + Unpin + Send> AsyncRecordsIter
+    for AsyncReader<R>
+{
+    type ByteItem<'a>
+        = Result<ByteRecord<'a>, ReadPicaError>
+    where
+        Self: 'a;
+
+    type StringItem<'a>
+        = Result<StringRecord<'a>, ReadPicaError>
+    where
+        Self: 'a;
+
+    /// Advance the iterator and return the next [ByteRecord].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::AsyncRecordsIter;
+    /// use pica_record::prelude::*;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .build()?
+    /// #     .block_on(async {
+    /// let data = &b"003@ \x1f0123456789X\x1e\n"[..];
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_async_reader(data, None).await?;
+    ///
+    /// let mut count = 0;
+    /// while let Some(result) = reader.next_byte_record().await {
+    ///     assert_eq!(result?.ppn().unwrap(), "123456789X");
+    ///     count += 1;
+    /// }
+    ///
+    /// assert_eq!(count, 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// # })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    async fn next_byte_record(&mut self) -> Option<Self::ByteItem<'_>> {
+        loop {
+            self.buf.clear();
+            self.line += 1;
+
+            match self.inner.read_until(b'\n', &mut self.buf).await {
+                Err(e) => return Some(Err(ReadPicaError::from(e))),
+                Ok(0) => return None,
+                Ok(_) => {
+                    if self.policy.mode == ParseMode::Lenient
+                        && is_blank(&self.buf)
+                    {
+                        self.repairs.blank_lines += 1;
+                        continue;
+                    }
+
+                    break;
+                }
+            }
+        }
+
+        let result =
+            self.policy.check_size(self.buf.len()).and_then(|_| {
+                transcode(
+                    &mut self.buf,
+                    &mut self.scratch,
+                    self.policy.encoding,
+                )?;
+
+                if self.policy.rewrites() {
+                    repair(
+                        &mut self.buf,
+                        &mut self.scratch,
+                        &self.policy,
+                        &mut self.repairs,
+                    );
+                }
+
+                ByteRecord::from_bytes(&self.buf)
+            });
+
+        match result {
+            Ok(record) => Some(Ok(record)),
+            Err(err) => Some(Err(ReadPicaError::parse(
+                self.source.as_deref(),
+                self.line,
+                err,
+            ))),
+        }
+    }
+
+    /// Advance the iterator and return the next [StringRecord].
+    async fn next_string_record(
+        &mut self,
+    ) -> Option<Self::StringItem<'_>> {
+        let line = self.line + 1;
+        match self.next_byte_record().await? {
+            Ok(record) => match StringRecord::try_from(record) {
+                Ok(record) => Some(Ok(record)),
+                Err(err) => {
+                    let msg = format!("invalid record on line {line}.");
+                    Some(Err(ReadPicaError::Utf8 { msg, err }))
+                }
+            },
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+impl ReaderBuilder {
+    /// Creates a new async reader from an existing [AsyncRead].
+    ///
+    /// Gzip compressed data is decoded transparently. The async reader
+    /// supports normalized PICA+ only.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the beginning of the input can't be read
+    /// or if an input format other than PICA+ is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::AsyncRecordsIter;
+    /// use pica_record::prelude::*;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .build()?
+    /// #     .block_on(async {
+    /// let data = &b"003@ \x1f0123456789X\x1e\n"[..];
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_async_reader(data, None).await?;
+    /// assert!(reader.next_byte_record().await.is_some());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// # })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub async fn from_async_reader<R>(
+        &self,
+        reader: R,
+        source: Option<String>,
+    ) -> io::Result<AsyncReader<BoxAsyncBufRead>>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        if !matches!(self.format, None | Some(InputFormat::Plus)) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "async reader supports normalized PICA+ only",
+            ));
+        }
+
+        let mut reader = BufReader::new(reader);
+        let inner: BoxAsyncBufRead =
+            match Compression::from_magic(reader.fill_buf().await?) {
+                Compression::None => Box::new(reader),
+                Compression::Gzip | Compression::Bgzf => {
+                    let mut decoder = GzipDecoder::new(reader);
+                    decoder.multiple_members(true);
+                    Box::new(BufReader::new(decoder))
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "async reader supports gzip compression only",
+                    ));
+                }
+            };
+
+        Ok(AsyncReader::new(inner, source, self.policy))
+    }
+
+    /// Creates a new async reader from a path.
+    ///
+    /// A path equal to "-" means reading from stdin.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::AsyncRecordsIter;
+    /// use pica_record::prelude::*;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .build()?
+    /// #     .block_on(async {
+    /// let mut reader = ReaderBuilder::new()
+    ///     .from_async_path("tests/data/DUMP.dat.gz")
+    ///     .await?;
+    ///
+    /// let mut count = 0;
+    /// while let Some(result) = reader.next_byte_record().await {
+    ///     if result.is_ok() {
+    ///         count += 1;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(count, 12);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// # })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub async fn from_async_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<AsyncReader<BoxAsyncBufRead>> {
+        let path = path.as_ref();
+        let source = path.to_str().map(ToString::to_string);
+
+        if path.to_str() == Some("-") {
+            self.from_async_reader(tokio::io::stdin(), source).await
+        } else {
+            let file = File::open(path).await?;
+            self.from_async_reader(file, source).await
+        }
+    }
+}
+
+/// An async [ByteRecord] writer.
+pub struct AsyncWriter<'a> {
+    inner: Box<dyn AsyncWrite + Unpin + Send + 'a>,
+    buf: Vec<u8>,
+}
+
+impl<'a> AsyncWriter<'a> {
+    /// Creates a new [AsyncWriter] with the given compression format.
+    fn new<W>(
+        inner: W,
+        compression: Compression,
+        level: Option<u32>,
+    ) -> io::Result<Self>
+    where
+        W: AsyncWrite + Unpin + Send + 'a,
+    {
+        let inner = BufWriter::new(inner);
+        let inner: Box<dyn AsyncWrite + Unpin + Send + 'a> =
+            match (compression, level) {
+                (Compression::None, _) => Box::new(inner),
+                (Compression::Gzip, None) => {
+                    Box::new(GzipEncoder::new(inner))
+                }
+                (Compression::Gzip, Some(level)) => {
+                    Box::new(GzipEncoder::with_quality(
+                        inner,
+                        Level::Precise(level as i32),
+                    ))
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "async writer supports gzip compression only",
+                    ));
+                }
+            };
+
+        Ok(Self {
+            buf: Vec::new(),
+            inner,
+        })
+    }
+}
+
+impl AsyncByteRecordWrite for AsyncWriter<'_> {
+    /// Writes a [ByteRecord] to the [AsyncWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::AsyncByteRecordWrite;
+    /// use pica_record::prelude::*;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .build()?
+    /// #     .block_on(async {
+    /// let mut out = Vec::new();
+    /// let mut writer =
+    ///     WriterBuilder::new().from_async_writer(&mut out)?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record).await?;
+    /// writer.finish().await?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(out, b"003@ \x1f0123456789X\x1e\n");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// # })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    async fn write_byte_record(
+        &mut self,
+        record: &ByteRecord<'_>,
+    ) -> io::Result<()> {
+        self.buf.clear();
+        record.write_to(&mut self.buf)?;
+        self.inner.write_all(&self.buf).await
+    }
+
+    /// Finish the [AsyncWriter].
+    ///
+    /// All buffered data is flushed and, if the records are compressed,
+    /// the compression stream is finalized.
+    async fn finish(&mut self) -> io::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+impl WriterBuilder {
+    /// Builds an async [ByteRecord] writer from this configuration that
+    /// writes to the given [AsyncWrite].
+    ///
+    /// # Errors
+    ///
+    /// The async writer supports gzip compression only; this function
+    /// fails if any other compression format is set.
+    pub fn from_async_writer<'a, W>(
+        &self,
+        writer: W,
+    ) -> io::Result<AsyncWriter<'a>>
+    where
+        W: AsyncWrite + Unpin + Send + 'a,
+    {
+        AsyncWriter::new(
+            writer,
+            self.compression.unwrap_or_default(),
+            self.level,
+        )
+    }
+
+    /// Builds an async [ByteRecord] writer from this configuration that
+    /// writes to the given path.
+    ///
+    /// If no compression format is set explicitly, the format is
+    /// derived from the extension of the path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::{AsyncByteRecordWrite, AsyncRecordsIter};
+    /// use pica_record::prelude::*;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .build()?
+    /// #     .block_on(async {
+    /// let file = tempfile::NamedTempFile::with_suffix(".dat.gz")?;
+    /// let mut writer =
+    ///     WriterBuilder::new().from_async_path(file.path()).await?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record).await?;
+    /// writer.finish().await?;
+    ///
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_async_path(file.path()).await?;
+    /// assert_eq!(reader.next_byte_record().await.unwrap()?, record);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// # })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub async fn from_async_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<AsyncWriter<'static>> {
+        let path = path.as_ref();
+        let compression = self
+            .compression
+            .unwrap_or_else(|| Compression::from_path(path));
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(!self.append)
+            .append(self.append)
+            .open(path)
+            .await?;
+
+        AsyncWriter::new(file, compression, self.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::duplex;
+
+    use super::*;
+    use crate::path::PathExt;
+
+    #[tokio::test]
+    async fn duplex_round_trip() -> anyhow::Result<()> {
+        let (client, server) = duplex(64);
+
+        let producer = tokio::spawn(async move {
+            let mut writer =
+                WriterBuilder::new().from_async_writer(client)?;
+
+            for ppn in ["1", "2", "3"] {
+                let data = format!("003@ \x1f0{ppn}\x1e\n");
+                let record = ByteRecord::from_bytes(data.as_bytes())?;
+                writer.write_byte_record(&record).await?;
+            }
+
+            writer.finish().await?;
+            Ok::<(), anyhow::Error>(())
+        });
+
+        let mut reader = ReaderBuilder::new()
+            .from_async_reader(server, None)
+            .await?;
+
+        let mut ppns = vec![];
+        while let Some(result) = reader.next_byte_record().await {
+            ppns.push(result?.ppn().unwrap().to_string());
+        }
+
+        producer.await??;
+        assert_eq!(ppns, ["1", "2", "3"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn duplex_round_trip_gzip() -> anyhow::Result<()> {
+        let (client, server) = duplex(64);
+
+        let producer = tokio::spawn(async move {
+            let mut writer = WriterBuilder::new()
+                .compression(Compression::Gzip)
+                .from_async_writer(client)?;
+
+            let record =
+                ByteRecord::from_bytes(b"003@ \x1f0123\x1e\n")?;
+            writer.write_byte_record(&record).await?;
+            writer.finish().await?;
+            Ok::<(), anyhow::Error>(())
+        });
+
+        let mut reader = ReaderBuilder::new()
+            .from_async_reader(server, None)
+            .await?;
+
+        let record = reader.next_byte_record().await.unwrap()?;
+        assert_eq!(record.ppn().unwrap(), "123");
+        assert!(reader.next_byte_record().await.is_none());
+
+        producer.await??;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_record() -> anyhow::Result<()> {
+        let (mut client, server) = duplex(64);
+        client.write_all(b"003@ \x1f0123\x1e\n003@ 0456\n").await?;
+        drop(client);
+
+        let mut reader = ReaderBuilder::new()
+            .from_async_reader(server, Some("-".into()))
+            .await?;
+
+        assert!(reader.next_byte_record().await.unwrap().is_ok());
+        let err = reader.next_byte_record().await.unwrap().unwrap_err();
+        assert!(err.skip_parse_err(true));
+        assert_eq!(
+            err.to_string(),
+            "parse error: invalid record on line 2 (<stdin>): \
+            unexpected character '0' at byte offset 5."
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,912 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{
+    self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write,
+};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use flate2::Crc;
+use flate2::bufread::GzDecoder;
+use flate2::write::DeflateEncoder;
+
+use crate::ByteRecord;
+use crate::writer::ByteRecordWrite;
+
+/// The maximum size of a compressed block (including header and
+/// footer).
+const MAX_BLOCK_SIZE: usize = 0x10000;
+
+/// The maximum number of uncompressed bytes stored in a single block.
+const MAX_DATA_SIZE: usize = 0xff00;
+
+/// The number of blocks, which are decompressed by a single thread in
+/// one batch.
+const BLOCKS_PER_THREAD: usize = 16;
+
+/// The header of a block. The last two bytes (BSIZE) are replaced by
+/// the total block size minus one.
+const HEADER: [u8; 18] = [
+    0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06,
+    0x00, b'B', b'C', 0x02, 0x00, 0x00, 0x00,
+];
+
+/// The empty block, which marks the end of a BGZF file.
+const EOF_BLOCK: [u8; 28] = [
+    0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06,
+    0x00, b'B', b'C', 0x02, 0x00, 0x1b, 0x00, 0x03, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Returns the virtual offset of the given position.
+///
+/// A virtual offset combines the offset of a block within the
+/// compressed file (upper 48 bits) and the offset within the
+/// decompressed block (lower 16 bits).
+#[inline]
+fn virtual_offset(block: u64, within: u64) -> u64 {
+    (block << 16) | (within & 0xffff)
+}
+
+/// A [ByteRecord] writer that encodes records in the BGZF format.
+///
+/// BGZF (blocked gzip format) is a series of independent gzip members
+/// of at most 64 KiB each. The result is a valid gzip file, which can
+/// be decompressed by any gzip implementation, but the blocks can also
+/// be decompressed in parallel and a reader can seek to the start of
+/// each block. A record is written into a new block if it doesn't fit
+/// into the current block; only records larger than a block span
+/// multiple blocks.
+pub struct BgzfWriter<W: Write> {
+    inner: W,
+    level: flate2::Compression,
+    index: BlockIndex,
+    buf: Vec<u8>,
+    block: Vec<u8>,
+    scratch: Vec<u8>,
+    coffset: u64,
+    uoffset: u64,
+    finished: bool,
+}
+
+impl<W: Write> BgzfWriter<W> {
+    /// Creates a new [BgzfWriter] with the default compression level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::BgzfWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut file = tempfile::tempfile()?;
+    /// let mut writer = BgzfWriter::new(file);
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self::with_level(inner, 6)
+    }
+
+    /// Creates a new [BgzfWriter] with the given compression level
+    /// (0-9).
+    pub fn with_level(inner: W, level: u32) -> Self {
+        Self {
+            level: flate2::Compression::new(level.min(9)),
+            buf: Vec::with_capacity(MAX_DATA_SIZE),
+            block: Vec::with_capacity(MAX_BLOCK_SIZE),
+            index: BlockIndex::default(),
+            scratch: Vec::new(),
+            finished: false,
+            coffset: 0,
+            uoffset: 0,
+            inner,
+        }
+    }
+
+    /// Returns the virtual offset of the next byte to be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::BgzfWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut writer = BgzfWriter::new(vec![]);
+    /// assert_eq!(writer.virtual_offset(), 0);
+    ///
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// assert_eq!(writer.virtual_offset(), 19);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn virtual_offset(&self) -> u64 {
+        virtual_offset(self.coffset, self.buf.len() as u64)
+    }
+
+    /// Returns the index of all blocks written so far.
+    #[inline]
+    pub fn index(&self) -> &BlockIndex {
+        &self.index
+    }
+
+    /// Finishes the BGZF stream and returns the underlying writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.try_finish()?;
+        Ok(self.inner)
+    }
+
+    /// Writes the pending data as a block, writes the end-of-file
+    /// marker and flushes the underlying writer.
+    fn try_finish(&mut self) -> io::Result<()> {
+        if !self.finished {
+            self.write_block()?;
+            self.inner.write_all(&EOF_BLOCK)?;
+            self.finished = true;
+        }
+
+        self.inner.flush()
+    }
+
+    /// Compresses the pending data into a block.
+    fn write_block(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+
+        compress_block(&self.buf, self.level, &mut self.block)?;
+        self.inner.write_all(&self.block)?;
+
+        if self.uoffset > 0 {
+            self.index.entries.push((self.coffset, self.uoffset));
+        }
+
+        self.coffset += self.block.len() as u64;
+        self.uoffset += self.buf.len() as u64;
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for BgzfWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(MAX_DATA_SIZE - self.buf.len());
+        self.buf.extend_from_slice(&buf[..n]);
+
+        if self.buf.len() == MAX_DATA_SIZE {
+            self.write_block()?;
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_block()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> ByteRecordWrite for BgzfWriter<W> {
+    /// Writes a [ByteRecord] to the [BgzfWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Compression;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut file = tempfile::NamedTempFile::with_suffix(".dat.bgz")?;
+    /// let mut writer = WriterBuilder::new().from_path(file.path())?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    ///
+    /// let mut reader = ReaderBuilder::new().from_path(file.path())?;
+    /// assert_eq!(reader.next_byte_record().unwrap()?, record);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        let mut scratch = mem::take(&mut self.scratch);
+        scratch.clear();
+        record.write_to(&mut scratch)?;
+
+        if !self.buf.is_empty()
+            && self.buf.len() + scratch.len() > MAX_DATA_SIZE
+        {
+            self.write_block()?;
+        }
+
+        let result = self.write_all(&scratch);
+        self.scratch = scratch;
+        result
+    }
+
+    /// Finish the [BgzfWriter].
+    #[inline]
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()
+    }
+}
+
+/// Compresses the data into a single BGZF block.
+fn compress_block(
+    data: &[u8],
+    level: flate2::Compression,
+    out: &mut Vec<u8>,
+) -> io::Result<()> {
+    deflate(data, level, out)?;
+    if out.len() + 8 > MAX_BLOCK_SIZE {
+        // Incompressible data is stored without compression, which
+        // always fits into a block.
+        deflate(data, flate2::Compression::none(), out)?;
+    }
+
+    let mut crc = Crc::new();
+    crc.update(data);
+
+    out.extend_from_slice(&crc.sum().to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+
+    let bsize = (out.len() - 1) as u16;
+    out[16..18].copy_from_slice(&bsize.to_le_bytes());
+    Ok(())
+}
+
+/// Writes the block header followed by the deflate compressed data.
+fn deflate(
+    data: &[u8],
+    level: flate2::Compression,
+    out: &mut Vec<u8>,
+) -> io::Result<()> {
+    out.clear();
+    out.extend_from_slice(&HEADER);
+
+    let mut encoder = DeflateEncoder::new(out, level);
+    encoder.write_all(data)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// A reader, which decodes BGZF data.
+///
+/// The blocks are read in batches, which are decompressed on a number
+/// of threads (see [BgzfReader::threads]). If the underlying reader is
+/// seekable, the reader can seek to a virtual offset (see
+/// [BgzfReader::seek_virtual]).
+pub struct BgzfReader<R: Read> {
+    inner: R,
+    threads: usize,
+    queue: VecDeque<Block>,
+    block: Block,
+    pos: usize,
+    coffset: u64,
+    eof: bool,
+}
+
+/// A decompressed block.
+#[derive(Default)]
+struct Block {
+    /// The offset of the block within the compressed data.
+    offset: u64,
+    /// The decompressed data.
+    data: Vec<u8>,
+}
+
+impl<R: Read> BgzfReader<R> {
+    /// Creates a new [BgzfReader], which decompresses the blocks on
+    /// the current thread.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Read;
+    ///
+    /// use pica_record::io::{BgzfReader, BgzfWriter};
+    ///
+    /// let mut writer = BgzfWriter::new(vec![]);
+    /// std::io::Write::write_all(&mut writer, b"abc")?;
+    /// let data = writer.into_inner()?;
+    ///
+    /// let mut reader = BgzfReader::new(data.as_slice());
+    /// let mut buf = String::new();
+    /// reader.read_to_string(&mut buf)?;
+    /// assert_eq!(buf, "abc");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: R) -> Self {
+        Self {
+            block: Block::default(),
+            queue: VecDeque::new(),
+            eof: false,
+            threads: 1,
+            coffset: 0,
+            pos: 0,
+            inner,
+        }
+    }
+
+    /// Sets the number of threads used to decompress the blocks.
+    ///
+    /// # Panics
+    ///
+    /// It's an error to use zero threads.
+    pub fn threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0);
+        self.threads = threads;
+        self
+    }
+
+    /// Returns the virtual offset of the next byte to be read.
+    #[inline]
+    pub fn virtual_offset(&self) -> u64 {
+        virtual_offset(self.block.offset, self.pos as u64)
+    }
+
+    /// Reads the next (compressed) block and returns its offset and
+    /// the raw data, or `None` at the end of the input.
+    fn read_raw_block(&mut self) -> io::Result<Option<(u64, Vec<u8>)>> {
+        let mut raw = vec![0u8; 12];
+        let mut n = 0;
+        while n < raw.len() {
+            match self.inner.read(&mut raw[n..]) {
+                Ok(0) => break,
+                Ok(m) => n += m,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        if n == 0 {
+            return Ok(None);
+        }
+
+        if n < raw.len() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+
+        if raw[..3] != HEADER[..3] || raw[3] & 0x04 == 0 {
+            return Err(invalid_block("missing extra field"));
+        }
+
+        let xlen = u16::from_le_bytes([raw[10], raw[11]]) as usize;
+        raw.resize(12 + xlen, 0);
+        self.inner.read_exact(&mut raw[12..])?;
+
+        let size = block_size(&raw[12..])?;
+        if size < 12 + xlen + 8 {
+            return Err(invalid_block("invalid block size"));
+        }
+
+        raw.resize(size, 0);
+        self.inner.read_exact(&mut raw[12 + xlen..])?;
+
+        let offset = self.coffset;
+        self.coffset += size as u64;
+        Ok(Some((offset, raw)))
+    }
+
+    /// Reads and decompresses the next batch of blocks.
+    fn fill_queue(&mut self) -> io::Result<()> {
+        let mut raw = vec![];
+        let batch = if self.threads > 1 {
+            self.threads * BLOCKS_PER_THREAD
+        } else {
+            1
+        };
+
+        while raw.len() < batch {
+            match self.read_raw_block()? {
+                Some(block) => raw.push(block),
+                None => {
+                    self.eof = true;
+                    break;
+                }
+            }
+        }
+
+        let data = if self.threads > 1 && raw.len() > 1 {
+            let size = raw.len().div_ceil(self.threads);
+            thread::scope(|scope| {
+                let handles = raw
+                    .chunks(size)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|(_, raw)| decompress_block(raw))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            raw.iter().map(|(_, raw)| decompress_block(raw)).collect()
+        };
+
+        for ((offset, _), data) in raw.into_iter().zip(data) {
+            self.queue.push_back(Block {
+                offset,
+                data: data?,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> BgzfReader<R> {
+    /// Seeks to the given virtual offset.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{BufRead, Cursor, Write};
+    ///
+    /// use pica_record::io::{BgzfReader, BgzfWriter};
+    ///
+    /// let mut writer = BgzfWriter::new(vec![]);
+    /// writer.write_all(b"abc\n")?;
+    /// let offset = writer.virtual_offset();
+    /// writer.write_all(b"def\n")?;
+    /// let data = writer.into_inner()?;
+    ///
+    /// let mut reader = BgzfReader::new(Cursor::new(data));
+    /// reader.seek_virtual(offset)?;
+    ///
+    /// let mut line = String::new();
+    /// reader.read_line(&mut line)?;
+    /// assert_eq!(line, "def\n");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn seek_virtual(&mut self, offset: u64) -> io::Result<()> {
+        let (coffset, within) = (offset >> 16, offset & 0xffff);
+
+        self.inner.seek(SeekFrom::Start(coffset))?;
+        self.queue.clear();
+        self.coffset = coffset;
+        self.eof = false;
+
+        self.block = match self.read_raw_block()? {
+            Some((offset, raw)) => Block {
+                data: decompress_block(&raw)?,
+                offset,
+            },
+            None => Block {
+                offset: coffset,
+                data: vec![],
+            },
+        };
+
+        if within as usize > self.block.data.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid virtual offset {offset}"),
+            ));
+        }
+
+        self.pos = within as usize;
+        Ok(())
+    }
+}
+
+impl<R: Read> BufRead for BgzfReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos >= self.block.data.len() {
+            match self.queue.pop_front() {
+                Some(block) => {
+                    self.block = block;
+                    self.pos = 0;
+                }
+                None if self.eof => return Ok(&[]),
+                None => self.fill_queue()?,
+            }
+        }
+
+        Ok(&self.block.data[self.pos..])
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.block.data.len());
+    }
+}
+
+impl<R: Read> Read for BgzfReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.fill_buf()?;
+        let n = data.len().min(buf.len());
+        buf[..n].copy_from_slice(&data[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+/// Decompresses a single block.
+///
+/// The size of the decompressed data (ISIZE) is read from the footer
+/// of the block, which can't be trusted until the block is
+/// decompressed. Thus, a block is rejected if it decompresses to more
+/// than [MAX_BLOCK_SIZE] bytes or if its size differs from ISIZE.
+fn decompress_block(raw: &[u8]) -> io::Result<Vec<u8>> {
+    let isize = raw[raw.len() - 4..]
+        .try_into()
+        .map_or(0, u32::from_le_bytes) as usize;
+
+    let mut data = Vec::with_capacity(isize.min(MAX_BLOCK_SIZE));
+    GzDecoder::new(raw)
+        .take(MAX_BLOCK_SIZE as u64 + 1)
+        .read_to_end(&mut data)?;
+
+    if data.len() != isize {
+        return Err(invalid_block("size mismatch"));
+    }
+
+    Ok(data)
+}
+
+/// Returns the total size of a block from the extra subfields of its
+/// header.
+fn block_size(mut extra: &[u8]) -> io::Result<usize> {
+    while extra.len() >= 4 {
+        let len = u16::from_le_bytes([extra[2], extra[3]]) as usize;
+        if extra[..2] == *b"BC" && len == 2 && extra.len() >= 6 {
+            let bsize = u16::from_le_bytes([extra[4], extra[5]]);
+            return Ok(bsize as usize + 1);
+        }
+
+        extra = extra.get(4 + len..).unwrap_or_default();
+    }
+
+    Err(invalid_block("missing block size"))
+}
+
+#[inline]
+fn invalid_block(msg: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid BGZF block: {msg}"),
+    )
+}
+
+/// An index of the blocks of a BGZF file.
+///
+/// The index maps the offset of each block within the decompressed
+/// data to the offset of the block within the compressed file. Thus,
+/// a position in the decompressed data can be translated into a
+/// virtual offset (see [BlockIndex::virtual_offset]). The index can
+/// be stored in the `.gzi` format used by `bgzip`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockIndex {
+    /// The compressed and uncompressed offsets of the blocks.
+    entries: Vec<(u64, u64)>,
+}
+
+impl Default for BlockIndex {
+    fn default() -> Self {
+        Self {
+            entries: vec![(0, 0)],
+        }
+    }
+}
+
+impl BlockIndex {
+    /// Builds the index by scanning the blocks of a BGZF file. The
+    /// blocks aren't decompressed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    ///
+    /// use pica_record::io::{BgzfWriter, BlockIndex};
+    ///
+    /// let mut writer = BgzfWriter::new(vec![]);
+    /// writer.write_all(&vec![b'a'; 100_000])?;
+    /// writer.flush()?;
+    ///
+    /// let index = writer.index().clone();
+    /// let data = writer.into_inner()?;
+    ///
+    /// assert_eq!(index.len(), 2);
+    /// assert_eq!(BlockIndex::build(data.as_slice())?, index);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn build<R: Read>(reader: R) -> io::Result<Self> {
+        let mut reader = BgzfReader::new(reader);
+        let mut index = Self::default();
+        let mut uoffset = 0;
+
+        while let Some((offset, raw)) = reader.read_raw_block()? {
+            let isize = raw[raw.len() - 4..]
+                .try_into()
+                .map(u32::from_le_bytes)
+                .unwrap_or_default();
+
+            if isize > 0 {
+                if uoffset > 0 {
+                    index.entries.push((offset, uoffset));
+                }
+
+                uoffset += isize as u64;
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Returns the default location of the block index of the given
+    /// BGZF file, which is the filename with an additional `.gzi`
+    /// extension.
+    pub fn sidecar<P: AsRef<Path>>(path: P) -> PathBuf {
+        let mut path = path.as_ref().as_os_str().to_owned();
+        path.push(".gzi");
+        path.into()
+    }
+
+    /// Returns the number of blocks.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the index doesn't contain any blocks. An
+    /// index always contains the first block.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the virtual offset of the given position within the
+    /// decompressed data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Cursor, Read, Write};
+    ///
+    /// use pica_record::io::{BgzfReader, BgzfWriter};
+    ///
+    /// let mut writer = BgzfWriter::new(vec![]);
+    /// writer.write_all(&vec![b'a'; 100_000])?;
+    /// writer.write_all(b"b")?;
+    /// writer.flush()?;
+    ///
+    /// let index = writer.index().clone();
+    /// let data = writer.into_inner()?;
+    ///
+    /// let mut reader = BgzfReader::new(Cursor::new(data));
+    /// reader.seek_virtual(index.virtual_offset(100_000))?;
+    ///
+    /// let mut buf = String::new();
+    /// reader.read_to_string(&mut buf)?;
+    /// assert_eq!(buf, "b");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn virtual_offset(&self, offset: u64) -> u64 {
+        let i = self
+            .entries
+            .partition_point(|(_, uoffset)| *uoffset <= offset);
+        let (coffset, uoffset) = self.entries[i.saturating_sub(1)];
+        virtual_offset(coffset, offset - uoffset)
+    }
+
+    /// Reads a block index from the given path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a block index in the `.gzi` format from the given reader.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+
+        let len = u64::from_le_bytes(buf);
+        let mut index = Self::default();
+
+        for _ in 0..len {
+            reader.read_exact(&mut buf)?;
+            let coffset = u64::from_le_bytes(buf);
+            reader.read_exact(&mut buf)?;
+            let uoffset = u64::from_le_bytes(buf);
+
+            if index.entries.last().is_some_and(|e| e.1 >= uoffset) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid block index: unsorted offsets",
+                ));
+            }
+
+            index.entries.push((coffset, uoffset));
+        }
+
+        Ok(index)
+    }
+
+    /// Writes the block index to the given path.
+    pub fn to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Writes the block index in the `.gzi` format to the given
+    /// writer. The first block is implicit and isn't written.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let entries = &self.entries[1..];
+        writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+        for (coffset, uoffset) in entries {
+            writer.write_all(&coffset.to_le_bytes())?;
+            writer.write_all(&uoffset.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::read::MultiGzDecoder;
+
+    use super::*;
+
+    fn records(n: usize) -> Vec<u8> {
+        let mut data = vec![];
+        for i in 0..n {
+            data.extend_from_slice(
+                format!(
+                    "003@ \x1f0{i:09}\x1e012A \x1fa{}\x1e\n",
+                    "x".repeat(i % 97)
+                )
+                .as_bytes(),
+            );
+        }
+
+        data
+    }
+
+    fn write(data: &[u8]) -> (Vec<u8>, BlockIndex) {
+        let mut writer = BgzfWriter::new(vec![]);
+        for line in data.split_inclusive(|b| *b == b'\n') {
+            let record = ByteRecord::from_bytes(line).unwrap();
+            writer.write_byte_record(&record).unwrap();
+        }
+
+        writer.finish().unwrap();
+        let index = writer.index().clone();
+        (writer.into_inner().unwrap(), index)
+    }
+
+    #[test]
+    fn test_round_trip() -> io::Result<()> {
+        let data = records(10_000);
+        let (compressed, index) = write(&data);
+        assert!(index.len() > 1);
+        assert!(compressed.ends_with(&EOF_BLOCK));
+
+        for threads in [1, 4] {
+            let mut reader =
+                BgzfReader::new(compressed.as_slice()).threads(threads);
+            let mut buf = vec![];
+            reader.read_to_end(&mut buf)?;
+            assert_eq!(buf, data);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plain_gzip() -> io::Result<()> {
+        let data = records(10_000);
+        let (compressed, _) = write(&data);
+
+        let mut buf = vec![];
+        MultiGzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut buf)?;
+        assert_eq!(buf, data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_boundaries() -> io::Result<()> {
+        let data = records(10_000);
+        let (compressed, index) = write(&data);
+        assert_eq!(BlockIndex::build(compressed.as_slice())?, index);
+
+        // Each block (except the last) ends at a record boundary.
+        let mut reader = BgzfReader::new(compressed.as_slice());
+        while !reader.fill_buf()?.is_empty() {
+            assert_eq!(reader.block.data.last(), Some(&b'\n'));
+            reader.consume(reader.block.data.len());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_virtual() -> io::Result<()> {
+        let data = records(10_000);
+        let (compressed, index) = write(&data);
+        let mut reader = BgzfReader::new(io::Cursor::new(compressed));
+
+        let mut offset = 0;
+        for (i, line) in
+            data.split_inclusive(|b| *b == b'\n').enumerate()
+        {
+            if i % 997 == 0 {
+                reader.seek_virtual(index.virtual_offset(offset))?;
+                let mut buf = vec![];
+                reader.read_until(b'\n', &mut buf)?;
+                assert_eq!(buf, line);
+            }
+
+            offset += line.len() as u64;
+        }
+
+        assert!(reader.seek_virtual(0xffff).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_record() -> io::Result<()> {
+        let value = "x".repeat(3 * MAX_DATA_SIZE);
+        let data = format!("003@ \x1f0123\x1e012A \x1fa{value}\x1e\n");
+        let (compressed, index) = write(data.as_bytes());
+        assert_eq!(index.len(), 4);
+
+        let mut buf = vec![];
+        BgzfReader::new(compressed.as_slice()).read_to_end(&mut buf)?;
+        assert_eq!(buf, data.as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_index_gzi() -> io::Result<()> {
+        let (_, index) = write(&records(10_000));
+        let mut buf = vec![];
+        index.write_to(&mut buf)?;
+        assert_eq!(buf.len(), 8 + 16 * (index.len() - 1));
+        assert_eq!(BlockIndex::read_from(buf.as_slice())?, index);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_block() {
+        let mut reader =
+            BgzfReader::new(b"\x1f\x8b\x08\x00".as_slice());
+        assert!(reader.read_to_end(&mut vec![]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_isize() -> io::Result<()> {
+        let (compressed, _) = write(&records(10));
+        let size = block_size(&compressed[12..18])?;
+        let mut raw = compressed[..size].to_vec();
+        assert!(decompress_block(&raw).is_ok());
+
+        let len = raw.len();
+        raw[len - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decompress_block(&raw).is_err());
+
+        let mut reader = BgzfReader::new(raw.as_slice());
+        assert!(reader.read_to_end(&mut vec![]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::{self, BufRead, BufWriter, Write};
+
+use crate::ByteRecord;
+use crate::writer::ByteRecordWrite;
+
+/// A streaming decoder for binary PICA.
+///
+/// In binary PICA each field ends with the field separator (`\x1e`)
+/// and each record ends with the record separator (`\x1d`).
+pub(crate) struct BinaryDecoder<R: BufRead> {
+    inner: R,
+}
+
+impl<R: BufRead> BinaryDecoder<R> {
+    /// Creates a new decoder from a buffered reader.
+    pub(crate) fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes the decoder and returns the underlying reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decodes the next record and appends the normalized PICA+ data
+    /// to `out`. Returns `false` if there are no further records.
+    pub(crate) fn decode_record(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> io::Result<bool> {
+        // Skip line breaks between two records.
+        loop {
+            let data = self.inner.fill_buf()?;
+            let n = data
+                .iter()
+                .take_while(|b| matches!(b, b'\n' | b'\r'))
+                .count();
+
+            if n == 0 {
+                break;
+            }
+
+            self.inner.consume(n);
+        }
+
+        if self.inner.read_until(b'\x1d', out)? == 0 {
+            return Ok(false);
+        }
+
+        if out.last() == Some(&b'\x1d') {
+            out.pop();
+        }
+
+        out.push(b'\n');
+        Ok(true)
+    }
+}
+
+/// A [ByteRecord] writer that encodes records as binary PICA.
+pub struct BinaryWriter<W: Write>(BufWriter<W>);
+
+impl<W: Write> BinaryWriter<W> {
+    /// Creates a new [BinaryWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::BinaryWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut out = Vec::new();
+    /// let mut writer = BinaryWriter::new(&mut out);
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(out, b"003@ \x1f0123456789X\x1e\x1d");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self(BufWriter::new(inner))
+    }
+}
+
+impl<W: Write> ByteRecordWrite for BinaryWriter<W> {
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        for field in record.fields() {
+            field.tag().write_to(&mut self.0)?;
+            if let Some(occurrence) = field.occurrence() {
+                occurrence.write_to(&mut self.0)?;
+            }
+
+            self.0.write_all(b" ")?;
+            for subfield in field.subfields() {
+                subfield.write_to(&mut self.0)?;
+            }
+
+            self.0.write_all(b"\x1e")?;
+        }
+
+        self.0.write_all(b"\x1d")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
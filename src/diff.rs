@@ -0,0 +1,385 @@
+//! Field- and subfield-level comparison of two records.
+//!
+//! The fields of both records are grouped by tag and occurrence. Within
+//! a group, identical fields are matched first, regardless of their
+//! position. The remaining fields are paired in the order of the
+//! records and reported as modified; unpaired fields are reported as
+//! added or removed. The subfields of a modified field are compared in
+//! the same way, grouped by subfield code. A field, whose subfields
+//! differ in order only, is reported as reordered.
+
+use crate::primitives::{FieldRef, RecordRef, SubfieldRef};
+
+/// A change of a field between two records.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange<'a> {
+    /// The field exists only in the new record.
+    Added(&'a FieldRef<'a>),
+    /// The field exists only in the old record.
+    Removed(&'a FieldRef<'a>),
+    /// The field exists in both records, but the subfields differ.
+    Modified {
+        old: &'a FieldRef<'a>,
+        new: &'a FieldRef<'a>,
+        changes: Vec<SubfieldChange<'a>>,
+    },
+    /// The field exists in both records with the same subfields, but
+    /// the order of the subfields differs.
+    Reordered {
+        old: &'a FieldRef<'a>,
+        new: &'a FieldRef<'a>,
+    },
+}
+
+impl<'a> FieldChange<'a> {
+    /// Returns the field of the old record, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::diff::FieldChange;
+    /// use pica_record::primitives::FieldRef;
+    ///
+    /// let field = FieldRef::new("003@", None, vec![('0', "123")])?;
+    /// assert_eq!(FieldChange::Removed(&field).old_field(), Some(&field));
+    /// assert_eq!(FieldChange::Added(&field).old_field(), None);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn old_field(&self) -> Option<&'a FieldRef<'a>> {
+        match self {
+            Self::Added(_) => None,
+            Self::Removed(old)
+            | Self::Modified { old, .. }
+            | Self::Reordered { old, .. } => Some(old),
+        }
+    }
+
+    /// Returns the field of the new record, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::diff::FieldChange;
+    /// use pica_record::primitives::FieldRef;
+    ///
+    /// let field = FieldRef::new("003@", None, vec![('0', "123")])?;
+    /// assert_eq!(FieldChange::Added(&field).new_field(), Some(&field));
+    /// assert_eq!(FieldChange::Removed(&field).new_field(), None);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_field(&self) -> Option<&'a FieldRef<'a>> {
+        match self {
+            Self::Removed(_) => None,
+            Self::Added(new)
+            | Self::Modified { new, .. }
+            | Self::Reordered { new, .. } => Some(new),
+        }
+    }
+}
+
+/// A change of a subfield within a modified field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubfieldChange<'a> {
+    /// The subfield exists only in the new field.
+    Added(&'a SubfieldRef<'a>),
+    /// The subfield exists only in the old field.
+    Removed(&'a SubfieldRef<'a>),
+    /// The value of the subfield has changed.
+    Modified {
+        old: &'a SubfieldRef<'a>,
+        new: &'a SubfieldRef<'a>,
+    },
+}
+
+impl RecordRef<'_> {
+    /// Compares the record with a newer version of the record and
+    /// returns the list of field changes. If both records contain the
+    /// same fields (regardless of their order), the list is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::diff::{FieldChange, SubfieldChange};
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let old = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "Ada")]),
+    ///     ("050C", None, vec![('a', "foo")]),
+    /// ])?;
+    ///
+    /// let new = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "Augusta Ada")]),
+    ///     ("060R", None, vec![('a', "1815")]),
+    /// ])?;
+    ///
+    /// let changes = old.diff(&new);
+    /// assert_eq!(changes.len(), 3);
+    ///
+    /// let FieldChange::Modified { changes: ref subfields, .. } = changes[0]
+    /// else {
+    ///     panic!("expected modified field");
+    /// };
+    ///
+    /// assert!(matches!(
+    ///     subfields[..],
+    ///     [SubfieldChange::Modified { .. }]
+    /// ));
+    ///
+    /// assert!(matches!(changes[1], FieldChange::Removed(_)));
+    /// assert!(matches!(changes[2], FieldChange::Added(_)));
+    /// assert!(old.diff(&old).is_empty());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn diff<'b>(
+        &'b self,
+        other: &'b RecordRef,
+    ) -> Vec<FieldChange<'b>> {
+        let mut keys: Vec<&FieldRef> = vec![];
+        for field in self.fields().iter().chain(other.fields()) {
+            if !keys.iter().any(|key| same_key(key, field)) {
+                keys.push(field);
+            }
+        }
+
+        let mut changes = vec![];
+        for key in keys {
+            let old = self.fields().iter().filter(|f| same_key(f, key));
+            let new =
+                other.fields().iter().filter(|f| same_key(f, key));
+
+            for (old, new) in pair(old.collect(), new.collect()) {
+                changes.push(match (old, new) {
+                    (Some(old), Some(new)) => {
+                        let changes = diff_subfields(old, new);
+                        if changes.is_empty() {
+                            FieldChange::Reordered { old, new }
+                        } else {
+                            FieldChange::Modified { old, new, changes }
+                        }
+                    }
+                    (Some(old), None) => FieldChange::Removed(old),
+                    (None, Some(new)) => FieldChange::Added(new),
+                    (None, None) => unreachable!(),
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// Compares the subfields of two fields, grouped by subfield code.
+fn diff_subfields<'a>(
+    old: &'a FieldRef<'a>,
+    new: &'a FieldRef<'a>,
+) -> Vec<SubfieldChange<'a>> {
+    let mut codes: Vec<_> = vec![];
+    for subfield in old.subfields().iter().chain(new.subfields()) {
+        if !codes.contains(&subfield.code()) {
+            codes.push(subfield.code());
+        }
+    }
+
+    let mut changes = vec![];
+    for code in codes {
+        let old = old.subfields().iter().filter(|s| s.code() == code);
+        let new = new.subfields().iter().filter(|s| s.code() == code);
+
+        for (old, new) in pair(old.collect(), new.collect()) {
+            changes.push(match (old, new) {
+                (Some(old), Some(new)) => {
+                    SubfieldChange::Modified { old, new }
+                }
+                (Some(old), None) => SubfieldChange::Removed(old),
+                (None, Some(new)) => SubfieldChange::Added(new),
+                (None, None) => unreachable!(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Returns `true` if both fields have the same tag and occurrence.
+pub(crate) fn same_key(a: &FieldRef, b: &FieldRef) -> bool {
+    a.tag() == b.tag() && a.occurrence() == b.occurrence()
+}
+
+/// Aligns the items of two lists and returns the pairs of indices.
+///
+/// Items which exist in both lists are matched first, regardless of
+/// their position. The remaining items are paired in order; if one
+/// list is longer than the other, the surplus items are unpaired.
+pub(crate) fn align<T: PartialEq>(
+    old: &[&T],
+    new: &[&T],
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let mut used = vec![false; new.len()];
+    let mut pairs = vec![];
+    let mut rest = vec![];
+
+    for (i, lhs) in old.iter().enumerate() {
+        match (0..new.len()).find(|&j| !used[j] && new[j] == *lhs) {
+            Some(j) => {
+                used[j] = true;
+                pairs.push((Some(i), Some(j)));
+            }
+            None => rest.push(i),
+        }
+    }
+
+    let unused: Vec<usize> =
+        (0..new.len()).filter(|&j| !used[j]).collect();
+    let len = rest.len().max(unused.len());
+    pairs.extend(
+        (0..len)
+            .map(|k| (rest.get(k).copied(), unused.get(k).copied())),
+    );
+
+    pairs
+}
+
+/// Removes all items, which exist in both lists, and pairs the
+/// remaining items in order.
+fn pair<'a, T: PartialEq>(
+    old: Vec<&'a T>,
+    new: Vec<&'a T>,
+) -> Vec<(Option<&'a T>, Option<&'a T>)> {
+    align(&old, &new)
+        .into_iter()
+        .map(|(i, j)| (i.map(|i| old[i]), j.map(|j| new[j])))
+        .filter(|pair| !matches!(pair, (Some(lhs), Some(rhs)) if lhs == rhs))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestResult = anyhow::Result<()>;
+
+    #[test]
+    fn test_diff_field_order() -> TestResult {
+        let old = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+        ])?;
+
+        let new = RecordRef::new(vec![
+            ("002@", None, vec![('0', "Tp1")]),
+            ("003@", None, vec![('0', "123")]),
+        ])?;
+
+        assert!(old.diff(&new).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_repeated_fields() -> TestResult {
+        let old = RecordRef::new(vec![
+            ("044H", None, vec![('a', "a")]),
+            ("044H", None, vec![('a', "b")]),
+            ("044H", Some("01"), vec![('a', "c")]),
+        ])?;
+
+        let new = RecordRef::new(vec![
+            ("044H", None, vec![('a', "b")]),
+            ("044H", None, vec![('a', "d")]),
+            ("044H", None, vec![('a', "e")]),
+        ])?;
+
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 3);
+
+        let FieldChange::Modified {
+            old: lhs,
+            new: rhs,
+            changes: ref subfields,
+        } = changes[0]
+        else {
+            panic!("expected modified field");
+        };
+
+        assert_eq!(lhs, &old.fields()[0]);
+        assert_eq!(rhs, &new.fields()[1]);
+        assert_eq!(
+            subfields,
+            &[SubfieldChange::Modified {
+                old: &old.fields()[0].subfields()[0],
+                new: &new.fields()[1].subfields()[0],
+            }]
+        );
+
+        assert_eq!(changes[1], FieldChange::Added(&new.fields()[2]));
+        assert_eq!(changes[2], FieldChange::Removed(&old.fields()[2]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_subfields() -> TestResult {
+        let old = RecordRef::new(vec![(
+            "041A",
+            None,
+            vec![('a', "x"), ('9', "1"), ('9', "2"), ('b', "y")],
+        )])?;
+
+        let new = RecordRef::new(vec![(
+            "041A",
+            None,
+            vec![('a', "x"), ('9', "2"), ('c', "z")],
+        )])?;
+
+        let changes = old.diff(&new);
+        let [FieldChange::Modified { changes, .. }] = &changes[..]
+        else {
+            panic!("expected modified field");
+        };
+
+        let (old, new) =
+            (old.fields()[0].subfields(), new.fields()[0].subfields());
+        assert_eq!(
+            changes,
+            &[
+                SubfieldChange::Removed(&old[1]),
+                SubfieldChange::Removed(&old[3]),
+                SubfieldChange::Added(&new[2]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_subfield_order() -> TestResult {
+        let old = RecordRef::new(vec![
+            ("028A", None, vec![('a', "Lovelace"), ('d', "Ada")]),
+            ("041A", None, vec![('9', "1"), ('9', "2")]),
+        ])?;
+
+        let new = RecordRef::new(vec![
+            ("041A", None, vec![('9', "2"), ('9', "1")]),
+            ("028A", None, vec![('d', "Ada"), ('a', "Lovelace")]),
+        ])?;
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                FieldChange::Reordered {
+                    old: &old.fields()[0],
+                    new: &new.fields()[1],
+                },
+                FieldChange::Reordered {
+                    old: &old.fields()[1],
+                    new: &new.fields()[0],
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
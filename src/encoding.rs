@@ -0,0 +1,306 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::str::{self, FromStr};
+
+use crate::primitives::{ParsePicaError, ParsePicaErrorKind};
+
+/// The character encoding of the input data.
+///
+/// Records of a legacy encoding are transcoded to UTF-8 before they
+/// are parsed. A record that can't be decoded unambiguously results in
+/// an error of kind [ParsePicaErrorKind::InvalidEncoding]:
+///
+/// * a byte that isn't defined in the encoding (in Windows-1252 the
+///   bytes `0x81`, `0x8d`, `0x8f`, `0x90` and `0x9d`),
+/// * a C1 control character (`0x80`–`0x9f`) in ISO-8859-1, which
+///   indicates Windows-1252 encoded data, and
+/// * a record with non-ASCII characters, which is already valid UTF-8.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8 (no transcoding).
+    #[default]
+    Utf8,
+    /// ISO-8859-1 (Latin-1).
+    Latin1,
+    /// Windows-1252 (CP1252).
+    Windows1252,
+}
+
+/// The characters of the bytes `0x80`–`0x9f` in Windows-1252; the
+/// undefined bytes are mapped to `None`.
+const WINDOWS_1252: [Option<char>; 32] = [
+    Some('\u{20ac}'),
+    None,
+    Some('\u{201a}'),
+    Some('\u{0192}'),
+    Some('\u{201e}'),
+    Some('\u{2026}'),
+    Some('\u{2020}'),
+    Some('\u{2021}'),
+    Some('\u{02c6}'),
+    Some('\u{2030}'),
+    Some('\u{0160}'),
+    Some('\u{2039}'),
+    Some('\u{0152}'),
+    None,
+    Some('\u{017d}'),
+    None,
+    None,
+    Some('\u{2018}'),
+    Some('\u{2019}'),
+    Some('\u{201c}'),
+    Some('\u{201d}'),
+    Some('\u{2022}'),
+    Some('\u{2013}'),
+    Some('\u{2014}'),
+    Some('\u{02dc}'),
+    Some('\u{2122}'),
+    Some('\u{0161}'),
+    Some('\u{203a}'),
+    Some('\u{0153}'),
+    None,
+    Some('\u{017e}'),
+    Some('\u{0178}'),
+];
+
+impl Encoding {
+    /// Returns the canonical name of the encoding.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Latin1 => "iso-8859-1",
+            Self::Windows1252 => "windows-1252",
+        }
+    }
+
+    /// Decodes the given data and returns the UTF-8 encoded data.
+    ///
+    /// Pure ASCII data (and all data in case of [Encoding::Utf8]) is
+    /// returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the data can't be decoded unambiguously
+    /// (see [Encoding]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Encoding;
+    ///
+    /// let data = Encoding::Latin1.decode(b"021A \x1faG\xf6the\x1e\n")?;
+    /// assert_eq!(data.as_ref(), "021A \x1faGöthe\x1e\n".as_bytes());
+    ///
+    /// let data = Encoding::Windows1252.decode(b"\x80 5")?;
+    /// assert_eq!(data.as_ref(), "€ 5".as_bytes());
+    ///
+    /// assert!(Encoding::Latin1.decode(b"\x80 5").is_err());
+    /// assert!(Encoding::Latin1.decode("Göthe".as_bytes()).is_err());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode<'a>(
+        &self,
+        data: &'a [u8],
+    ) -> Result<Cow<'a, [u8]>, ParsePicaError> {
+        if *self == Self::Utf8 || data.is_ascii() {
+            return Ok(Cow::Borrowed(data));
+        }
+
+        let mut out = Vec::with_capacity(data.len() + data.len() / 8);
+        self.decode_into(data, &mut out)?;
+        Ok(Cow::Owned(out))
+    }
+
+    /// Decodes the given data and appends the UTF-8 encoded data to
+    /// `out`.
+    fn decode_into(
+        &self,
+        data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), ParsePicaError> {
+        if str::from_utf8(data).is_ok() {
+            return Err(ParsePicaError::with_kind(
+                ParsePicaErrorKind::InvalidEncoding,
+                format!(
+                    "ambiguous encoding (expected {}, found valid UTF-8)",
+                    self.as_str()
+                ),
+            ));
+        }
+
+        let mut tmp = [0; 4];
+        for (offset, byte) in data.iter().copied().enumerate() {
+            if byte.is_ascii() {
+                out.push(byte);
+                continue;
+            }
+
+            let c = match (self, byte) {
+                (Self::Latin1, 0x80..=0x9f) => {
+                    return Err(error(
+                        data,
+                        offset,
+                        format!(
+                            "ambiguous byte 0x{byte:02x} at byte offset \
+                             {offset} (C1 control character)"
+                        ),
+                    ));
+                }
+                (Self::Windows1252, 0x80..=0x9f) => WINDOWS_1252
+                    [(byte - 0x80) as usize]
+                    .ok_or_else(|| {
+                        error(
+                            data,
+                            offset,
+                            format!(
+                                "undefined byte 0x{byte:02x} at \
+                                     byte offset {offset}"
+                            ),
+                        )
+                    })?,
+                _ => char::from(byte),
+            };
+
+            out.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates an error for the byte at the given offset. The tag of the
+/// field, which contains the byte, is added to the error if known.
+fn error(data: &[u8], offset: usize, msg: String) -> ParsePicaError {
+    let err = ParsePicaError::with_kind(
+        ParsePicaErrorKind::InvalidEncoding,
+        msg,
+    )
+    .with_offset(offset);
+
+    let start = data[..offset]
+        .iter()
+        .rposition(|b| *b == b'\x1e')
+        .map_or(0, |pos| pos + 1);
+
+    match data.get(start..start + 4).map(str::from_utf8) {
+        Some(Ok(tag)) if tag.is_ascii() => err.with_tag(tag),
+        _ => err,
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    /// Parses an encoding from its name (case-insensitive).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Encoding;
+    ///
+    /// assert_eq!("latin1".parse(), Ok(Encoding::Latin1));
+    /// assert_eq!("CP1252".parse(), Ok(Encoding::Windows1252));
+    /// assert!("ebcdic".parse::<Encoding>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "iso-8859-1" | "latin1" | "latin-1" => Ok(Self::Latin1),
+            "windows-1252" | "cp1252" => Ok(Self::Windows1252),
+            _ => Err(format!("unknown encoding '{s}'")),
+        }
+    }
+}
+
+/// Transcodes the record in `buf` into UTF-8.
+///
+/// The `scratch` buffer is used to avoid an allocation per record.
+pub(crate) fn transcode(
+    buf: &mut Vec<u8>,
+    scratch: &mut Vec<u8>,
+    encoding: Encoding,
+) -> Result<(), ParsePicaError> {
+    if encoding == Encoding::Utf8 || buf.is_ascii() {
+        return Ok(());
+    }
+
+    scratch.clear();
+    encoding.decode_into(buf, scratch)?;
+    std::mem::swap(buf, scratch);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_latin1() {
+        let data = (0xa0..=0xffu8).collect::<Vec<_>>();
+        let expected =
+            (0xa0..=0xffu8).map(char::from).collect::<String>();
+        let result = Encoding::Latin1.decode(&data).unwrap();
+        assert_eq!(result.as_ref(), expected.as_bytes());
+
+        let err = Encoding::Latin1.decode(b"abc\x96").unwrap_err();
+        assert_eq!(err.kind(), ParsePicaErrorKind::InvalidEncoding);
+        assert_eq!(err.byte_offset(), Some(3));
+        assert_eq!(err.field_tag(), None);
+
+        let err = Encoding::Latin1
+            .decode(b"003@ \x1f0123\x1e041A \x1fa1815\x961852\x1e\n")
+            .unwrap_err();
+        assert_eq!(err.byte_offset(), Some(22));
+        assert_eq!(err.field_tag(), Some("041A"));
+    }
+
+    #[test]
+    fn test_decode_windows_1252() {
+        let result = Encoding::Windows1252
+            .decode(b"\x84Ma\xdfe\x93 \x96 \x80")
+            .unwrap();
+        assert_eq!(result.as_ref(), "„Maße“ – €".as_bytes());
+
+        for byte in [0x81, 0x8d, 0x8f, 0x90, 0x9d] {
+            let err =
+                Encoding::Windows1252.decode(&[byte]).unwrap_err();
+            assert_eq!(err.kind(), ParsePicaErrorKind::InvalidEncoding);
+            assert_eq!(err.byte_offset(), Some(0));
+        }
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let data = "Gö".as_bytes();
+        assert!(matches!(
+            Encoding::Utf8.decode(data).unwrap(),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            Encoding::Latin1.decode(b"abc").unwrap(),
+            Cow::Borrowed(_)
+        ));
+
+        let err = Encoding::Windows1252.decode(data).unwrap_err();
+        assert_eq!(err.kind(), ParsePicaErrorKind::InvalidEncoding);
+        assert_eq!(err.byte_offset(), None);
+    }
+
+    #[test]
+    fn test_transcode() {
+        let mut buf =
+            b"003@ \x1f0123\x1e021A \x1faM\xfcller\x1e\n".to_vec();
+        transcode(&mut buf, &mut vec![], Encoding::Latin1).unwrap();
+        assert_eq!(
+            buf,
+            "003@ \x1f0123\x1e021A \x1faMüller\x1e\n".as_bytes()
+        );
+    }
+}
@@ -15,4 +15,6 @@ pub enum Error {
     ParseFormat(crate::fmt::ParseFormatError),
     #[error(transparent)]
     ParseQuery(crate::query::ParseQueryError),
+    #[error(transparent)]
+    ParsePatch(crate::patch::ParsePatchError),
 }
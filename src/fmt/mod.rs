@@ -9,7 +9,8 @@ use winnow::prelude::*;
 use crate::StringRecord;
 use crate::matcher::subfield::SubfieldMatcher;
 use crate::matcher::{MatcherOptions, OccurrenceMatcher, TagMatcher};
-use crate::primitives::{FieldRef, RecordRef, SubfieldCode};
+use crate::path::FieldSelector;
+use crate::primitives::{FieldRef, Record, RecordRef, SubfieldCode};
 
 mod parser;
 
@@ -404,6 +405,23 @@ pub trait FormatExt {
     ) -> impl Iterator<Item = Self::Value>;
 }
 
+impl FieldSelector for Format {
+    #[inline]
+    fn tag_matcher(&self) -> &TagMatcher {
+        &self.tag_matcher
+    }
+
+    #[inline]
+    fn occurrence_matcher(&self) -> &OccurrenceMatcher {
+        &self.occurrence_matcher
+    }
+
+    #[inline]
+    fn subfield_matcher(&self) -> Option<&SubfieldMatcher> {
+        self.subfield_matcher.as_ref()
+    }
+}
+
 impl FormatExt for RecordRef<'_> {
     type Value = BString;
 
@@ -412,28 +430,55 @@ impl FormatExt for RecordRef<'_> {
         format: &Format,
         options: &FormatOptions,
     ) -> impl Iterator<Item = Self::Value> {
+        let matcher_options = options.into();
+
         self.fields()
             .iter()
-            .filter(|field| {
-                let retval = format.tag_matcher.is_match(field.tag())
-                    && format
-                        .occurrence_matcher
-                        .is_match(field.occurrence());
-
-                if let Some(ref matcher) = format.subfield_matcher {
-                    retval
-                        && matcher.is_match(
-                            field.subfields(),
-                            &options.into(),
-                        )
-                } else {
-                    retval
-                }
+            .filter(move |field| {
+                format.selects(field, &matcher_options)
             })
             .filter_map(|field| format.fmt_field(field, options))
     }
 }
 
+impl FormatExt for Record {
+    type Value = BString;
+
+    /// Returns the formatted fields of an owned record.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    /// use pica_record::primitives::Record;
+    ///
+    /// let record =
+    ///     Record::new(vec![("021A", None, vec![('a', "foo"), ('d', "bar")])])?;
+    /// let format = Format::new("021A{ a <$> ' ' d }")?;
+    /// let values: Vec<_> =
+    ///     record.format(&format, &Default::default()).collect();
+    /// assert_eq!(values, vec!["foo bar"]);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn format(
+        &self,
+        format: &Format,
+        options: &FormatOptions,
+    ) -> impl Iterator<Item = Self::Value> {
+        let matcher_options = options.into();
+
+        self.fields()
+            .iter()
+            .filter(move |field| {
+                format.selects_owned(field, &matcher_options)
+            })
+            .filter_map(|field| {
+                format.fmt_field(&FieldRef::from(field), options)
+            })
+    }
+}
+
 impl FormatExt for StringRecord<'_> {
     type Value = String;
 
@@ -0,0 +1,147 @@
+use bstr::ByteSlice;
+
+use crate::NormalizationForm;
+use crate::matcher::{OccurrenceMatcher, TagMatcher};
+use crate::primitives::{
+    Field, OccurrenceRef, Record, SubfieldValue, TagRef,
+};
+
+/// A list of field predicates, each consisting of a tag and an
+/// occurrence matcher (e.g. `001B` or `044H/*`).
+type Predicates = Vec<(TagMatcher, OccurrenceMatcher)>;
+
+/// Options and flags which can be used to configure the hash of a
+/// record.
+///
+/// By default, the hash covers all fields in the order of the record,
+/// which is the same as [ByteRecord::sha256](crate::ByteRecord::sha256).
+/// Volatile fields (e.g. the date of the last change `001B`) can be
+/// excluded from the hash and the record can be brought into a
+/// canonical form, so that records with the same content, but a
+/// different field order, whitespace or Unicode normalization form,
+/// result in the same hash.
+///
+/// # Example
+///
+/// ```rust
+/// use pica_record::NormalizationForm;
+/// use pica_record::prelude::*;
+///
+/// let a = ByteRecord::from_bytes(
+///     b"001B \x1f01999:01-01-24\x1e003@ \x1f0123\x1e002@ \x1f0Tp1\x1e\
+///       021A \x1faAm\xc3\xa9lie\x1e\n",
+/// )?;
+/// let b = ByteRecord::from_bytes(
+///     b"001B \x1f01999:02-01-24\x1e002@ \x1f0Tp1 \x1e003@ \x1f0123\x1e\
+///       021A \x1faAme\xcc\x81lie\x1e\n",
+/// )?;
+///
+/// assert_ne!(a.sha256(), b.sha256());
+///
+/// let options = HashOptions::new()
+///     .discard(vec![(TagMatcher::new("001B")?, OccurrenceMatcher::None)])
+///     .canonical(true)
+///     .normalize(Some(NormalizationForm::Nfc));
+///
+/// assert_eq!(a.sha256_with(&options), b.sha256_with(&options));
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct HashOptions {
+    keep: Predicates,
+    discard: Predicates,
+    canonical: bool,
+    normalize: Option<NormalizationForm>,
+}
+
+impl HashOptions {
+    /// Create new hash options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hash only those fields, which match at least one of the given
+    /// predicates. An empty list keeps all fields.
+    pub fn keep(mut self, predicates: Predicates) -> Self {
+        self.keep = predicates;
+        self
+    }
+
+    /// Exclude all fields from the hash, which match at least one of
+    /// the given predicates.
+    pub fn discard(mut self, predicates: Predicates) -> Self {
+        self.discard = predicates;
+        self
+    }
+
+    /// Whether to sort the fields into canonical order (see
+    /// [Record::sort]) before hashing or not.
+    pub fn canonical(mut self, yes: bool) -> Self {
+        self.canonical = yes;
+        self
+    }
+
+    /// Whether to normalize the subfield values before hashing or
+    /// not.
+    ///
+    /// If a normalization form is given, the subfield values are
+    /// converted into this Unicode normalization form (values, which
+    /// aren't valid UTF-8, are left as they are). Afterwards, the
+    /// values are trimmed and empty subfields and fields are removed
+    /// (see [Record::trim]).
+    pub fn normalize(mut self, nf: Option<NormalizationForm>) -> Self {
+        self.normalize = nf;
+        self
+    }
+
+    /// Returns `true` if the options don't change the record.
+    pub(crate) fn is_identity(&self) -> bool {
+        self.keep.is_empty()
+            && self.discard.is_empty()
+            && !self.canonical
+            && self.normalize.is_none()
+    }
+
+    /// Applies the options to the record.
+    pub(crate) fn apply(&self, record: &mut Record) {
+        let is_match = |predicates: &Predicates, field: &Field| {
+            let tag = TagRef::from(field.tag());
+            let occ = field.occurrence().map(OccurrenceRef::from);
+            predicates.iter().any(|(t, o)| {
+                t.is_match(&tag) && o.is_match(occ.as_ref())
+            })
+        };
+
+        if !self.keep.is_empty() {
+            record.retain(|field| is_match(&self.keep, field));
+        }
+
+        if !self.discard.is_empty() {
+            record.retain(|field| !is_match(&self.discard, field));
+        }
+
+        if let Some(nf) = self.normalize {
+            for field in record.fields_mut() {
+                for subfield in field.subfields_mut() {
+                    let Ok(value) = subfield.value().to_str() else {
+                        continue;
+                    };
+
+                    let normalized = nf.normalize(value);
+                    if normalized != value {
+                        subfield.set_value(
+                            SubfieldValue::from_unchecked(&normalized),
+                        );
+                    }
+                }
+            }
+
+            record.trim();
+        }
+
+        if self.canonical {
+            record.sort();
+        }
+    }
+}
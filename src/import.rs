@@ -0,0 +1,165 @@
+use std::io::{self, BufRead, BufWriter, Write};
+
+use crate::ByteRecord;
+use crate::plain::trim_newline;
+use crate::primitives::ParsePicaError;
+use crate::reader::DecodeError;
+use crate::writer::ByteRecordWrite;
+
+/// A streaming decoder for the PICA import format.
+///
+/// Each record starts with a line ending with the record separator
+/// (`\x1d`), followed by one line per field. Each field line starts
+/// with the field separator (`\x1e`).
+pub(crate) struct ImportDecoder<R: BufRead> {
+    inner: R,
+    line: usize,
+    position: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> ImportDecoder<R> {
+    /// Creates a new decoder from a buffered reader.
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            buf: Vec::new(),
+            position: 0,
+            line: 0,
+            inner,
+        }
+    }
+
+    /// Consumes the decoder and returns the underlying reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the line on which the last decoded record starts.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Decodes the next record and appends the normalized PICA+ data
+    /// to `out`. Returns `false` if there are no further records.
+    pub(crate) fn decode_record(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, DecodeError> {
+        let mut result = Ok(());
+        self.position = 0;
+
+        loop {
+            // A line, which doesn't start with a field separator,
+            // terminates the current record.
+            let next = self.inner.fill_buf()?.first().copied();
+            if self.position > 0 && !matches!(next, Some(b'\x1e')) {
+                break;
+            }
+
+            self.buf.clear();
+            if self.inner.read_until(b'\n', &mut self.buf)? == 0 {
+                break;
+            }
+
+            self.line += 1;
+
+            let line = trim_newline(&self.buf);
+            if self.position == 0 {
+                self.position = self.line;
+            }
+
+            match line {
+                [b'\x1e', field @ ..] => {
+                    if field.contains(&b'\x1e') {
+                        result = result.and(Err(ParsePicaError::new(
+                            format!(
+                                "invalid field on line {}",
+                                self.line
+                            ),
+                        )));
+                    }
+
+                    out.extend_from_slice(field);
+                    out.push(b'\x1e');
+                }
+                [] => {
+                    self.position = 0;
+                }
+                [.., b'\x1d'] => {}
+                _ => {
+                    result = result.and(Err(ParsePicaError::new(
+                        format!("unexpected line {}", self.line),
+                    )));
+                }
+            }
+        }
+
+        if self.position == 0 {
+            return Ok(false);
+        }
+
+        result?;
+        out.push(b'\n');
+        Ok(true)
+    }
+}
+
+/// A [ByteRecord] writer that encodes records in the PICA import
+/// format.
+pub struct ImportWriter<W: Write>(BufWriter<W>);
+
+impl<W: Write> ImportWriter<W> {
+    /// Creates a new [ImportWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ImportWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut out = Vec::new();
+    /// let mut writer = ImportWriter::new(&mut out);
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(out, b"'\x1d\n\x1e003@ \x1f0123456789X\n");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self(BufWriter::new(inner))
+    }
+}
+
+impl<W: Write> ByteRecordWrite for ImportWriter<W> {
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        self.0.write_all(b"'\x1d\x0a")?;
+
+        for field in record.fields() {
+            self.0.write_all(b"\x1e")?;
+
+            field.tag().write_to(&mut self.0)?;
+            if let Some(occurrence) = field.occurrence() {
+                occurrence.write_to(&mut self.0)?;
+            }
+
+            self.0.write_all(b" ")?;
+            for subfield in field.subfields() {
+                subfield.write_to(&mut self.0)?;
+            }
+
+            self.0.write_all(b"\x0a")?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
@@ -0,0 +1,639 @@
+use std::fs::File;
+use std::io::{
+    self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write,
+};
+use std::path::{Path as FsPath, PathBuf};
+
+use flate2::bufread::GzDecoder;
+use hashbrown::HashMap;
+
+use crate::ByteRecord;
+use crate::bgzf::{BgzfReader, BlockIndex};
+use crate::matcher::MatcherOptions;
+use crate::path::{Path, PathExt};
+use crate::reader::{
+    CAPACITY, InputFormat, ReadPicaError, Reader, ReaderBuilder,
+    RecordsIter,
+};
+use crate::writer::Compression;
+
+/// The magic number (and version) of an index file.
+const MAGIC: &[u8; 8] = b"PICAIDX\x01";
+
+/// The location of a record within a PICA+ file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// The byte offset of the gzip member (or BGZF block), which
+    /// contains the start of the record, or `None` if the file isn't
+    /// compressed.
+    pub member: Option<u64>,
+    /// The byte offset of the record within the file or, if the file
+    /// is compressed, within the decompressed member.
+    pub offset: u64,
+    /// The length of the record in bytes (including the newline).
+    pub length: u64,
+}
+
+/// A persistent index, which maps the values of a path expression
+/// (e.g. the PPN) to the location of the records within a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    key: String,
+    compression: Compression,
+    entries: HashMap<Vec<u8>, Vec<IndexEntry>>,
+}
+
+impl Index {
+    /// Returns the path expression, whose values are the keys of the
+    /// index.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::IndexBuilder;
+    /// use pica_record::prelude::*;
+    ///
+    /// let index = IndexBuilder::new(Path::new("003@.0")?)
+    ///     .from_path("tests/data/ada.dat")?;
+    /// assert_eq!(index.key(), "003@.0");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the compression format of the indexed file.
+    #[inline]
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Returns the number of distinct keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the index doesn't contain any keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the locations of all records with the given key. The
+    /// locations are in the same order as the records in the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::IndexBuilder;
+    /// use pica_record::prelude::*;
+    ///
+    /// let index = IndexBuilder::new(Path::new("003@.0")?)
+    ///     .from_path("tests/data/ada.dat")?;
+    ///
+    /// assert_eq!(index.get("119232022").len(), 1);
+    /// assert!(index.get("118540238").is_empty());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> &[IndexEntry] {
+        self.entries
+            .get(key.as_ref())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the default location of the index file of the given
+    /// PICA+ file, which is the filename with an additional `.idx`
+    /// extension.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::PathBuf;
+    ///
+    /// use pica_record::io::Index;
+    ///
+    /// assert_eq!(
+    ///     Index::sidecar("DUMP.dat.gz"),
+    ///     PathBuf::from("DUMP.dat.gz.idx")
+    /// );
+    /// ```
+    pub fn sidecar<P: AsRef<FsPath>>(path: P) -> PathBuf {
+        let mut path = path.as_ref().as_os_str().to_owned();
+        path.push(".idx");
+        path.into()
+    }
+
+    /// Reads an index from the given path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::{Index, IndexBuilder};
+    /// use pica_record::prelude::*;
+    ///
+    /// let index = IndexBuilder::new(Path::new("003@.0")?)
+    ///     .skip_invalid(true)
+    ///     .from_path("tests/data/DUMP.dat.gz")?;
+    ///
+    /// let file = tempfile::NamedTempFile::new()?;
+    /// index.to_path(file.path())?;
+    ///
+    /// assert_eq!(Index::from_path(file.path())?, index);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_path<P: AsRef<FsPath>>(path: P) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads an index from the given reader.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the data isn't a valid index.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_index("unknown file format"));
+        }
+
+        let key = String::from_utf8(read_bytes(&mut reader)?)
+            .map_err(|_| invalid_index("invalid path expression"))?;
+
+        let compression = match read_u8(&mut reader)? {
+            0 => Compression::None,
+            1 => Compression::Gzip,
+            2 => Compression::Bgzf,
+            _ => {
+                return Err(invalid_index(
+                    "unknown compression format",
+                ));
+            }
+        };
+
+        let len = read_u64(&mut reader)?;
+        let mut entries = HashMap::new();
+
+        for _ in 0..len {
+            let key = read_bytes(&mut reader)?;
+            let count = read_u64(&mut reader)?;
+            let mut values = vec![];
+
+            for _ in 0..count {
+                let member = read_u64(&mut reader)?;
+                values.push(IndexEntry {
+                    member: member.checked_sub(1),
+                    offset: read_u64(&mut reader)?,
+                    length: read_u64(&mut reader)?,
+                });
+            }
+
+            entries.insert(key, values);
+        }
+
+        Ok(Self {
+            key,
+            compression,
+            entries,
+        })
+    }
+
+    /// Writes the index to the given path.
+    pub fn to_path<P: AsRef<FsPath>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Writes the index to the given writer.
+    ///
+    /// The keys are written in sorted order, so that the same file
+    /// always results in the same index.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_bytes(&mut writer, self.key.as_bytes())?;
+        writer.write_all(&[match self.compression {
+            Compression::Gzip => 1,
+            Compression::Bgzf => 2,
+            _ => 0,
+        }])?;
+
+        let mut keys = self.entries.keys().collect::<Vec<_>>();
+        keys.sort_unstable();
+
+        writer.write_all(&(keys.len() as u64).to_le_bytes())?;
+        for key in keys {
+            let values = &self.entries[key];
+            write_bytes(&mut writer, key)?;
+            writer.write_all(&(values.len() as u64).to_le_bytes())?;
+
+            for entry in values {
+                let member =
+                    entry.member.map_or(0, |member| member + 1);
+                writer.write_all(&member.to_le_bytes())?;
+                writer.write_all(&entry.offset.to_le_bytes())?;
+                writer.write_all(&entry.length.to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Opens the indexed file for random access.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file can't be opened or if the
+    /// compression format of the file doesn't match the index.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::IndexBuilder;
+    /// use pica_record::prelude::*;
+    ///
+    /// let index = IndexBuilder::new(Path::new("003@.0")?)
+    ///     .skip_invalid(true)
+    ///     .from_path("tests/data/DUMP.dat.gz")?;
+    ///
+    /// let mut reader = index.reader("tests/data/DUMP.dat.gz")?;
+    /// let entry = index.get("040993396")[0];
+    /// let record = reader.read_entry(&entry)?;
+    /// assert_eq!(record.ppn().unwrap(), "040993396");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reader<P: AsRef<FsPath>>(
+        &self,
+        path: P,
+    ) -> io::Result<IndexedReader> {
+        let path = path.as_ref();
+        let source = path.to_str().map(ToString::to_string);
+        let mut file =
+            BufReader::with_capacity(CAPACITY, File::open(path)?);
+
+        // A BGZF file is a valid gzip file, so that an index of the
+        // gzip members can be used to access the blocks as well.
+        let compression = Compression::from_magic(file.fill_buf()?);
+        if compression != self.compression
+            && !(self.compression == Compression::Gzip
+                && compression == Compression::Bgzf)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "index doesn't match the file '{}'",
+                    path.display()
+                ),
+            ));
+        }
+
+        let inner = match self.compression {
+            Compression::Gzip => Inner::Gzip(file, Vec::new()),
+            Compression::Bgzf => Inner::Bgzf(
+                BgzfReader::new(file.into_inner()),
+                Vec::new(),
+            ),
+            _ => Inner::Plus(Box::new(
+                ReaderBuilder::new()
+                    .format(InputFormat::Plus)
+                    .from_reader(file.into_inner(), source.clone()),
+            )),
+        };
+
+        Ok(IndexedReader { inner, source })
+    }
+}
+
+/// Configures and builds an [Index].
+#[derive(Debug)]
+pub struct IndexBuilder {
+    key: Path,
+    options: MatcherOptions,
+    skip_invalid: bool,
+}
+
+impl IndexBuilder {
+    /// Creates a new [IndexBuilder], which uses the values of the given
+    /// path expression as keys.
+    pub fn new(key: Path) -> Self {
+        Self {
+            options: MatcherOptions::default(),
+            skip_invalid: false,
+            key,
+        }
+    }
+
+    /// Whether to skip invalid records or not.
+    ///
+    /// If this flag isn't set, building the index fails on the first
+    /// invalid record. This flag is disabled by default.
+    pub fn skip_invalid(mut self, yes: bool) -> Self {
+        self.skip_invalid = yes;
+        self
+    }
+
+    /// Sets the options, which are used to evaluate the path
+    /// expression.
+    pub fn options(mut self, options: MatcherOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Builds the index of an uncompressed, gzip or BGZF compressed
+    /// PICA+ file.
+    ///
+    /// A record that doesn't have a value for the path expression
+    /// isn't indexed. If a record has more than one value, the record
+    /// can be looked up by each of these values.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file can't be read, the file is
+    /// compressed in any other format than gzip or BGZF or if the file
+    /// contains an invalid record (and invalid records aren't
+    /// skipped). A gzip compressed file can be indexed only if no
+    /// record spans multiple gzip members; in a BGZF compressed file,
+    /// a record may span multiple blocks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::IndexBuilder;
+    /// use pica_record::prelude::*;
+    ///
+    /// let index = IndexBuilder::new(Path::new("003@.0")?)
+    ///     .skip_invalid(true)
+    ///     .from_path("tests/data/DUMP.dat.gz")?;
+    /// assert_eq!(index.len(), 12);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_path<P: AsRef<FsPath>>(
+        &self,
+        path: P,
+    ) -> Result<Index, ReadPicaError> {
+        let path = path.as_ref();
+        let source = path.to_str().map(ToString::to_string);
+        let builder = ReaderBuilder::new().format(InputFormat::Plus);
+
+        if path.to_str() == Some("-") {
+            return Err(ReadPicaError::from(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "unable to index stdin",
+            )));
+        }
+
+        let mut file =
+            BufReader::with_capacity(CAPACITY, File::open(path)?);
+        // The extension doesn't distinguish between gzip and BGZF
+        // (e.g. a BGZF file named `.gz`), so the magic bytes of the
+        // file are inspected as well.
+        let compression = match Compression::from_path(path) {
+            Compression::None
+            | Compression::Gzip
+            | Compression::Bgzf => {
+                Compression::from_magic(file.fill_buf()?)
+            }
+            compression => compression,
+        };
+
+        let mut index = Index {
+            key: self.key.to_string(),
+            entries: HashMap::new(),
+            compression,
+        };
+
+        match compression {
+            Compression::None => {
+                let mut reader = builder.from_reader(file, source);
+                self.index_records(&mut reader, None, &mut index)?;
+            }
+            Compression::Gzip => {
+                // The members are decoded one by one, so that the
+                // offset of each member within the file is known.
+                while !file.fill_buf()?.is_empty() {
+                    let member = file.stream_position()?;
+                    let decoder = GzDecoder::new(&mut file);
+                    let mut reader =
+                        builder.from_reader(decoder, source.clone());
+                    self.index_records(
+                        &mut reader,
+                        Some(member),
+                        &mut index,
+                    )?;
+                }
+            }
+            Compression::Bgzf => {
+                // The records are indexed by their offset within the
+                // decompressed data, which is translated into a
+                // virtual offset (block offset and offset within the
+                // block) afterwards.
+                let blocks = BlockIndex::build(&mut file)?;
+                file.seek(SeekFrom::Start(0))?;
+
+                let mut reader = builder
+                    .from_reader(BgzfReader::new(&mut file), source);
+                self.index_records(&mut reader, None, &mut index)?;
+
+                for entry in index.entries.values_mut().flatten() {
+                    let offset = blocks.virtual_offset(entry.offset);
+                    entry.member = Some(offset >> 16);
+                    entry.offset = offset & 0xffff;
+                }
+            }
+            _ => {
+                return Err(ReadPicaError::from(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!(
+                        "unable to index '{}' (only uncompressed, gzip \
+                        or BGZF compressed files are supported)",
+                        path.display()
+                    ),
+                )));
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Adds all records of the reader to the index.
+    fn index_records<R: Read>(
+        &self,
+        reader: &mut Reader<R>,
+        member: Option<u64>,
+        index: &mut Index,
+    ) -> Result<(), ReadPicaError> {
+        loop {
+            let offset = reader.position();
+            let mut keys = match reader.next_byte_record() {
+                None => return Ok(()),
+                Some(Err(e)) if e.skip_parse_err(self.skip_invalid) => {
+                    continue;
+                }
+                Some(Err(e)) => return Err(e),
+                Some(Ok(record)) => record
+                    .path(&self.key, &self.options)
+                    .map(|value| value.to_vec())
+                    .collect::<Vec<_>>(),
+            };
+
+            keys.sort_unstable();
+            keys.dedup();
+
+            let entry = IndexEntry {
+                length: reader.position() - offset,
+                member,
+                offset,
+            };
+
+            for key in keys {
+                index.entries.entry(key).or_default().push(entry);
+            }
+        }
+    }
+}
+
+/// A reader, which reads single records at the locations of an
+/// [Index].
+///
+/// This reader is created by [Index::reader].
+pub struct IndexedReader {
+    inner: Inner,
+    source: Option<String>,
+}
+
+enum Inner {
+    Plus(Box<Reader<File>>),
+    Gzip(BufReader<File>, Vec<u8>),
+    Bgzf(BgzfReader<File>, Vec<u8>),
+}
+
+impl IndexedReader {
+    /// Reads the record at the location of the given entry.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the record can't be read or if the
+    /// record is invalid.
+    pub fn read_entry(
+        &mut self,
+        entry: &IndexEntry,
+    ) -> Result<ByteRecord<'_>, ReadPicaError> {
+        let source = self.source.as_deref();
+        let result = match self.inner {
+            Inner::Plus(ref mut reader) => {
+                reader.seek(entry.offset)?;
+                reader.next_byte_record().unwrap_or_else(|| {
+                    Err(ReadPicaError::from(io::Error::from(
+                        io::ErrorKind::UnexpectedEof,
+                    )))
+                })
+            }
+            Inner::Gzip(ref mut file, ref mut buf) => {
+                let Some(member) = entry.member else {
+                    return Err(ReadPicaError::from(invalid_index(
+                        "missing gzip member",
+                    )));
+                };
+
+                file.seek(SeekFrom::Start(member))?;
+                let mut decoder = GzDecoder::new(file);
+                io::copy(
+                    &mut (&mut decoder).take(entry.offset),
+                    &mut io::sink(),
+                )?;
+
+                buf.clear();
+                decoder.take(entry.length).read_to_end(buf)?;
+                ByteRecord::from_bytes(buf)
+                    .map_err(|err| ReadPicaError::parse(None, 1, err))
+            }
+            Inner::Bgzf(ref mut reader, ref mut buf) => {
+                let Some(member) = entry.member else {
+                    return Err(ReadPicaError::from(invalid_index(
+                        "missing BGZF block",
+                    )));
+                };
+
+                reader.seek_virtual((member << 16) | entry.offset)?;
+
+                buf.clear();
+                reader.take(entry.length).read_to_end(buf)?;
+                ByteRecord::from_bytes(buf)
+                    .map_err(|err| ReadPicaError::parse(None, 1, err))
+            }
+        };
+
+        result.map_err(|e| match e {
+            ReadPicaError::Parse { err, .. } => {
+                let mut msg = format!(
+                    "invalid record at offset {}",
+                    entry.offset
+                );
+                if let Some(member) = entry.member {
+                    msg.push_str(&format!(" of gzip member {member}"));
+                }
+
+                if let Some(src) = source {
+                    msg.push_str(&format!(" ({src})"));
+                }
+
+                msg.push_str(&format!(": {err}."));
+                ReadPicaError::Parse {
+                    input: source.map(ToString::to_string),
+                    record: None,
+                    line: None,
+                    msg,
+                    err,
+                }
+            }
+            e => e,
+        })
+    }
+}
+
+#[inline]
+fn invalid_index(msg: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid index: {msg}"),
+    )
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_u64(reader)?;
+    let mut buf = vec![];
+    reader.take(len).read_to_end(&mut buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+
+    Ok(buf)
+}
+
+fn write_bytes<W: Write>(
+    writer: &mut W,
+    value: &[u8],
+) -> io::Result<()> {
+    writer.write_all(&(value.len() as u64).to_le_bytes())?;
+    writer.write_all(value)
+}
@@ -0,0 +1,518 @@
+use std::io::{self, BufRead, BufWriter, Write};
+
+use serde_json::Value;
+
+use crate::ByteRecord;
+use crate::primitives::ParsePicaError;
+use crate::reader::DecodeError;
+use crate::writer::ByteRecordWrite;
+
+/// A streaming decoder for PICA-JSON.
+///
+/// A record is encoded as an array of fields, where each field is an
+/// array of the form `[tag, occurrence, code, value, ...]`. The
+/// decoder accepts both a JSON array of records and line-delimited
+/// records (JSON Lines); the layout is detected from the input.
+pub(crate) struct JsonDecoder<R: BufRead> {
+    inner: R,
+    state: State,
+    buf: Vec<u8>,
+    line: usize,
+    column: usize,
+    position: (usize, usize),
+}
+
+/// The position of the decoder within a PICA-JSON document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Array,
+    Lines,
+    Trailing,
+    Done,
+}
+
+impl<R: BufRead> JsonDecoder<R> {
+    /// Creates a new decoder from a buffered reader.
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            state: State::Start,
+            buf: Vec::new(),
+            line: 1,
+            column: 1,
+            position: (1, 1),
+            inner,
+        }
+    }
+
+    /// Consumes the decoder and returns the underlying reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the line and column of the last decoded record or, if
+    /// the record is malformed, the position of the error.
+    pub(crate) fn position(&self) -> (usize, usize) {
+        self.position
+    }
+
+    /// Decodes the next record and appends the normalized PICA+ data
+    /// to `out`. Returns `false` if there are no further records.
+    pub(crate) fn decode_record(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, DecodeError> {
+        loop {
+            self.skip_whitespace()?;
+
+            match (self.state, self.peek()?) {
+                (State::Start, None) => {
+                    self.state = State::Done;
+                }
+                (State::Start, Some(b'[')) => {
+                    return self.decode_first(out);
+                }
+                (State::Array, Some(b',')) => {
+                    self.bump(false)?;
+                    self.skip_whitespace()?;
+                    if self.peek()? != Some(b'[') {
+                        return Err(self
+                            .syntax_error("expected '['")
+                            .into());
+                    }
+
+                    self.begin();
+                    self.read_value(0)?;
+                    return self.decode_value(out);
+                }
+                (State::Array, Some(b']')) => {
+                    self.bump(false)?;
+                    self.state = State::Trailing;
+                }
+                (State::Lines, Some(b'[')) => {
+                    self.begin();
+                    self.read_value(0)?;
+                    return self.decode_value(out);
+                }
+                (State::Lines | State::Trailing, None)
+                | (State::Done, _) => {
+                    self.state = State::Done;
+                    return Ok(false);
+                }
+                (_, None) => {
+                    return Err(self
+                        .syntax_error("unexpected end of file")
+                        .into());
+                }
+                (_, Some(b)) => {
+                    return Err(self
+                        .syntax_error(format!(
+                            "unexpected character '{}'",
+                            b.escape_ascii()
+                        ))
+                        .into());
+                }
+            }
+        }
+    }
+
+    /// Decodes the first record and detects the layout of the input:
+    /// `[[[` starts an array of records, whereas `[[` starts a
+    /// line-delimited record. The layout is decided once and kept for
+    /// the rest of the input.
+    fn decode_first(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, DecodeError> {
+        self.begin();
+        self.bump(true)?;
+        self.skip_whitespace_into()?;
+
+        match self.peek()? {
+            Some(b']') => {
+                // Either an empty array of records or an empty record
+                // on the first line. Since only JSON Lines can contain
+                // another value, the next token decides the layout.
+                self.bump(true)?;
+                self.skip_whitespace()?;
+
+                if self.peek()? == Some(b'[') {
+                    self.state = State::Lines;
+                    self.decode_value(out)
+                } else {
+                    self.state = State::Trailing;
+                    self.decode_record(out)
+                }
+            }
+            Some(b'[') => {
+                let (line, column) = (self.line, self.column);
+                let offset = self.buf.len();
+
+                self.bump(true)?;
+                self.skip_whitespace_into()?;
+
+                if self.peek()? == Some(b'[') {
+                    self.buf.drain(..offset);
+                    self.position = (line, column);
+                    self.state = State::Array;
+                    self.read_value(1)?;
+                } else {
+                    self.state = State::Lines;
+                    self.read_value(2)?;
+                }
+
+                self.decode_value(out)
+            }
+            _ => {
+                self.state = State::Lines;
+                self.read_value(1)?;
+                self.decode_value(out)
+            }
+        }
+    }
+
+    /// Marks the beginning of a new record at the current position.
+    fn begin(&mut self) {
+        self.buf.clear();
+        self.position = (self.line, self.column);
+    }
+
+    /// Reads the remaining bytes of a JSON value, whose opening
+    /// brackets up to the given `depth` are already consumed.
+    fn read_value(&mut self, mut depth: usize) -> io::Result<()> {
+        let mut in_string = false;
+        let mut escape = false;
+
+        loop {
+            let Some(b) = self.peek()? else {
+                return Err(self.syntax_error("unexpected end of file"));
+            };
+
+            self.bump(true)?;
+
+            if in_string {
+                if escape {
+                    escape = false;
+                } else if b == b'\\' {
+                    escape = true;
+                } else if b == b'"' {
+                    in_string = false;
+                }
+
+                continue;
+            }
+
+            match b {
+                b'"' => in_string = true,
+                b'[' | b'{' => depth += 1,
+                b']' | b'}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Decodes the buffered JSON value into normalized PICA+.
+    fn decode_value(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, DecodeError> {
+        let fields: Vec<Vec<Option<String>>> =
+            serde_json::from_slice(&self.buf).map_err(|e| {
+                let (line, column) = self.position;
+                self.position = if e.line() <= 1 {
+                    (line, column + e.column().saturating_sub(1))
+                } else {
+                    (line + e.line() - 1, e.column())
+                };
+
+                let msg = e.to_string();
+                let msg = msg
+                    .rsplit_once(" at line ")
+                    .map_or(msg.as_str(), |(msg, _)| msg);
+
+                ParsePicaError::new(msg.to_string())
+            })?;
+
+        for field in fields {
+            let (header, subfields) = match field.split_at_checked(2) {
+                Some((header, subfields))
+                    if subfields.len() % 2 == 0 =>
+                {
+                    (header, subfields)
+                }
+                _ => {
+                    return Err(invalid_field(
+                        "expected [tag, occurrence, code, value, ...]",
+                    ));
+                }
+            };
+
+            let Some(ref tag) = header[0] else {
+                return Err(invalid_field("missing tag"));
+            };
+
+            out.extend_from_slice(tag.as_bytes());
+            if let Some(ref occurrence) = header[1] {
+                let occurrence =
+                    occurrence.strip_prefix('/').unwrap_or(occurrence);
+                if !occurrence.is_empty() {
+                    out.push(b'/');
+                    out.extend_from_slice(occurrence.as_bytes());
+                }
+            }
+
+            out.push(b' ');
+
+            for pair in subfields.chunks_exact(2) {
+                let (Some(code), Some(value)) = (&pair[0], &pair[1])
+                else {
+                    return Err(invalid_field("missing code or value"));
+                };
+
+                if code.chars().count() != 1 {
+                    return Err(invalid_field(format!(
+                        "invalid subfield code '{code}'"
+                    )));
+                }
+
+                if value.contains(['\n', '\x1e', '\x1f']) {
+                    return Err(invalid_field(format!(
+                        "invalid subfield value '{}'",
+                        value.escape_debug()
+                    )));
+                }
+
+                out.push(b'\x1f');
+                out.extend_from_slice(code.as_bytes());
+                out.extend_from_slice(value.as_bytes());
+            }
+
+            out.push(b'\x1e');
+        }
+
+        out.push(b'\n');
+        Ok(true)
+    }
+
+    /// Returns the next byte without consuming it.
+    #[inline]
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.inner.fill_buf()?.first().copied())
+    }
+
+    /// Consumes the next byte and updates the position. If `keep` is
+    /// true, the byte is appended to the record buffer.
+    #[inline]
+    fn bump(&mut self, keep: bool) -> io::Result<()> {
+        if let Some(b) = self.peek()? {
+            if keep {
+                self.buf.push(b);
+            }
+
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+
+            self.inner.consume(1);
+        }
+
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<()> {
+        while matches!(self.peek()?, Some(b) if b.is_ascii_whitespace())
+        {
+            self.bump(false)?;
+        }
+
+        Ok(())
+    }
+
+    fn skip_whitespace_into(&mut self) -> io::Result<()> {
+        while matches!(self.peek()?, Some(b) if b.is_ascii_whitespace())
+        {
+            self.bump(true)?;
+        }
+
+        Ok(())
+    }
+
+    fn syntax_error<E: ToString>(&self, err: E) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "invalid PICA-JSON at line {}, column {}: {}",
+                self.line,
+                self.column,
+                err.to_string()
+            ),
+        )
+    }
+}
+
+#[inline]
+fn invalid_field<E: ToString>(err: E) -> DecodeError {
+    DecodeError::Parse(ParsePicaError::new(format!(
+        "invalid field: {}",
+        err.to_string()
+    )))
+}
+
+/// A [ByteRecord] writer that encodes records as PICA-JSON.
+///
+/// The records are written as a JSON array of records, where each
+/// field is an array of the form `[tag, occurrence, code, value, ...]`.
+pub struct JsonWriter<W: Write> {
+    inner: BufWriter<W>,
+    count: usize,
+}
+
+impl<W: Write> JsonWriter<W> {
+    /// Creates a new [JsonWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::JsonWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut out = Vec::new();
+    /// let mut writer = JsonWriter::new(&mut out)?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(out)?,
+    ///     r#"[[["003@",null,"0","123456789X"]]]"#
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> io::Result<Self> {
+        let mut inner = BufWriter::new(inner);
+        inner.write_all(b"[")?;
+
+        Ok(Self { inner, count: 0 })
+    }
+}
+
+impl<W: Write> ByteRecordWrite for JsonWriter<W> {
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        let mut fields: Vec<Value> = Vec::new();
+
+        for field in record.fields() {
+            let mut data: Vec<Value> = Vec::new();
+            data.push(Value::String(field.tag().to_string()));
+
+            if let Some(occurrence) = field.occurrence() {
+                data.push(Value::String(occurrence.to_string()));
+            } else {
+                data.push(Value::Null);
+            }
+
+            for subfield in field.subfields() {
+                data.push(Value::String(subfield.code().to_string()));
+                data.push(Value::String(subfield.value().to_string()));
+            }
+
+            fields.push(Value::Array(data));
+        }
+
+        let data = Value::Array(fields);
+        if self.count > 0 {
+            write!(self.inner, ",{data}")?;
+        } else {
+            write!(self.inner, "{data}")?;
+        }
+
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.write_all(b"]")?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(data: &[u8]) -> Vec<Result<Vec<u8>, DecodeError>> {
+        let mut decoder = JsonDecoder::new(data);
+        let mut result = vec![];
+
+        loop {
+            let mut out = vec![];
+            match decoder.decode_record(&mut out) {
+                Ok(false) => break,
+                Ok(true) => result.push(Ok(out)),
+                Err(e @ DecodeError::Parse(_)) => result.push(Err(e)),
+                Err(e @ DecodeError::IO(_)) => {
+                    result.push(Err(e));
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_decode_array() {
+        let result =
+            decode(b"[[[\"003@\",null,\"0\",\"123\"]],[[\"012A\",\"01\",\"a\",\"x\"]]]");
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].as_ref().unwrap(), b"003@ \x1f0123\x1e\n");
+        assert_eq!(
+            result[1].as_ref().unwrap(),
+            b"012A/01 \x1fax\x1e\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_lines() {
+        let result = decode(
+            b"[[\"003@\",null,\"0\",\"123\"]]\n[[\"003@\",null,\"0\",\"456\"]]\n",
+        );
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].as_ref().unwrap(), b"003@ \x1f0456\x1e\n");
+    }
+
+    #[test]
+    fn test_decode_invalid_value() {
+        for value in ["12\\n34", "12\\u001e34", "12\\u001f34"] {
+            let data = format!(
+                "[[\"003@\",null,\"0\",\"{value}\"]]\n\
+                 [[\"003@\",null,\"0\",\"123\"]]\n"
+            );
+
+            let result = decode(data.as_bytes());
+            assert_eq!(result.len(), 2);
+
+            let Err(DecodeError::Parse(ref err)) = result[0] else {
+                panic!("expected parse error");
+            };
+
+            assert!(err.message().starts_with("invalid field: "));
+            assert_eq!(
+                result[1].as_ref().unwrap(),
+                b"003@ \x1f0123\x1e\n"
+            );
+        }
+    }
+}
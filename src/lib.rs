@@ -3,26 +3,69 @@
 //! [OCLC](https://www.oclc.org) cataloging system.
 
 pub use error::Error;
-pub use record::{ByteRecord, StringRecord};
+pub use hash::HashOptions;
+pub use normalization::NormalizationForm;
+pub use record::{ByteRecord, Provenance, StringRecord};
 
+pub mod diff;
 mod error;
 mod fmt;
+mod hash;
 pub mod matcher;
+pub mod merge;
+mod normalization;
 mod parser;
+pub mod patch;
 pub mod path;
 pub mod prelude;
 pub mod primitives;
 pub mod query;
 
 pub mod io {
+    pub use super::archive::{MultiReader, Sources};
+    #[cfg(feature = "async")]
+    pub use super::async_io::{
+        AsyncByteRecordWrite, AsyncReader, AsyncRecordsIter,
+        AsyncWriter, BoxAsyncBufRead,
+    };
+    pub use super::bgzf::{BgzfReader, BgzfWriter, BlockIndex};
+    pub use super::binary::BinaryWriter;
+    pub use super::encoding::Encoding;
+    pub use super::import::ImportWriter;
+    pub use super::index::{
+        Index, IndexBuilder, IndexEntry, IndexedReader,
+    };
+    pub use super::json::JsonWriter;
+    pub use super::mmap::{MmapReader, MmapRecords};
+    pub use super::multi::MultiWriter;
+    pub use super::parallel::{ParReader, ParReaderBuilder};
+    pub use super::plain::PlainTextWriter;
     pub use super::reader::{
-        ReadPicaError, ReaderBuilder, RecordsIter,
+        InputFormat, ReadPicaError, ReaderBuilder, RecordsIter,
     };
+    pub use super::repair::{ParseMode, Repairs};
     pub use super::writer::{
-        ByteRecordWrite, GzipWriter, PlainWriter, WriterBuilder,
+        ByteRecordWrite, Bzip2Writer, Compression, Encoder, GzipWriter,
+        PlainWriter, WriterBuilder, XzWriter, ZstdWriter,
     };
+    pub use super::xml::XmlWriter;
 }
 
+mod archive;
+#[cfg(feature = "async")]
+mod async_io;
+mod bgzf;
+mod binary;
+mod encoding;
+mod import;
+mod index;
+mod json;
+mod mmap;
+mod multi;
+mod parallel;
+mod plain;
 mod reader;
 mod record;
+mod repair;
 mod writer;
+mod xml;
@@ -8,7 +8,7 @@ use winnow::Parser;
 use super::field::FieldMatcher;
 use super::field::parser::parse_field_matcher;
 use super::{MatcherOptions, ParseMatcherError};
-use crate::primitives::RecordRef;
+use crate::primitives::AsRecordRef;
 
 /// A matcher that matches against a [RecordRef].
 #[derive(Debug, Clone, PartialEq)]
@@ -50,11 +50,14 @@ impl RecordMatcher {
     /// Returns `true` if the given field(s) matches against the field
     /// matcher.
     ///
+    /// The record can be any borrowed or owned record (see
+    /// [AsRecordRef]).
+    ///
     /// # Example
     ///
     /// ```rust
     /// use pica_record::matcher::{MatcherOptions, RecordMatcher};
-    /// use pica_record::primitives::RecordRef;
+    /// use pica_record::primitives::{Record, RecordRef};
     ///
     /// let record = RecordRef::new(vec![
     ///     ("003@", None, vec![('0', "123456789X")]),
@@ -65,15 +68,18 @@ impl RecordMatcher {
     /// let matcher = RecordMatcher::new("002@.0 == 'Tp1'")?;
     /// assert!(matcher.is_match(&record, &options));
     ///
+    /// let record = Record::from(record);
+    /// assert!(matcher.is_match(&record, &options));
+    ///
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     #[inline(always)]
-    pub fn is_match(
+    pub fn is_match<R: AsRecordRef + ?Sized>(
         &self,
-        record: &RecordRef,
+        record: &R,
         options: &MatcherOptions,
     ) -> bool {
-        self.0.is_match(record.fields(), options)
+        self.0.is_match(record.as_record_ref().fields(), options)
     }
 }
 
@@ -329,6 +335,7 @@ mod tests {
     use serde_test::{Token, assert_tokens};
 
     use super::*;
+    use crate::primitives::{Record, RecordRef};
 
     type TestResult = anyhow::Result<()>;
 
@@ -392,6 +399,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_record_matcher_owned_record() -> TestResult {
+        let record =
+            Record::from(RecordRef::from_bytes(ada_lovelace())?);
+
+        let matcher = RecordMatcher::new(
+            "#028[A@]{d =^ 'Ada' && a == 'Lovelace'} == 5",
+        )?;
+        assert!(matcher.is_match(&record, &Default::default()));
+
+        let matcher = RecordMatcher::new("002@.0 == 'Tp1' && 004B?")?;
+        assert!(matcher.is_match(&record, &Default::default()));
+
+        let matcher = RecordMatcher::new("002@.0 == 'Tpz'")?;
+        assert!(!matcher.is_match(&record, &Default::default()));
+
+        Ok(())
+    }
+
     #[test]
     fn test_record_matcher_cardinality() -> TestResult {
         let record = RecordRef::from_bytes(ada_lovelace())?;
@@ -0,0 +1,405 @@
+//! Three-way merge of records.
+//!
+//! Given a common base version of a record and two independently
+//! changed versions (ours and theirs), the changes of both versions
+//! are combined on field level. The fields are grouped by tag and
+//! occurrence and aligned in the same way as by the
+//! [diff](crate::diff) of two records. A field, which was changed in
+//! only one version, is taken from this version; fields added by any
+//! version are kept. If both versions changed (or removed) the same
+//! field in different ways, or both versions added different fields
+//! to the same group, a [MergeConflict] is reported and our version
+//! is kept.
+
+use crate::diff::{align, same_key};
+use crate::primitives::{Field, FieldRef, Record, RecordRef};
+
+/// A field, which was changed differently in both versions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict<'a> {
+    base: Option<&'a FieldRef<'a>>,
+    ours: Option<&'a FieldRef<'a>>,
+    theirs: Option<&'a FieldRef<'a>>,
+}
+
+impl<'a> MergeConflict<'a> {
+    /// Returns the field of the base version or `None`, if both
+    /// versions added a different field.
+    #[inline]
+    pub fn base(&self) -> Option<&'a FieldRef<'a>> {
+        self.base
+    }
+
+    /// Returns our version of the field or `None`, if we removed the
+    /// field.
+    #[inline]
+    pub fn ours(&self) -> Option<&'a FieldRef<'a>> {
+        self.ours
+    }
+
+    /// Returns their version of the field or `None`, if they removed
+    /// the field.
+    #[inline]
+    pub fn theirs(&self) -> Option<&'a FieldRef<'a>> {
+        self.theirs
+    }
+}
+
+/// The result of a three-way merge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge<'a> {
+    record: Record,
+    conflicts: Vec<MergeConflict<'a>>,
+}
+
+impl<'a> Merge<'a> {
+    /// Returns the merged record.
+    #[inline]
+    pub fn record(&self) -> &Record {
+        &self.record
+    }
+
+    /// Returns the conflicts, which couldn't be merged automatically.
+    #[inline]
+    pub fn conflicts(&self) -> &[MergeConflict<'a>] {
+        &self.conflicts
+    }
+
+    /// Returns `true` if the merge has no conflicts.
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Consumes the merge and returns the merged record.
+    #[inline]
+    pub fn into_record(self) -> Record {
+        self.record
+    }
+}
+
+impl RecordRef<'_> {
+    /// Merges two changed versions of the record, whereby `self` is
+    /// the common base version.
+    ///
+    /// The merged record keeps the field order of our version; fields
+    /// added by their version are inserted at their canonical position
+    /// (see [Record::insert]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, RecordRef};
+    ///
+    /// let base = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "Ada")]),
+    ///     ("050C", None, vec![('a', "foo")]),
+    /// ])?;
+    ///
+    /// let ours = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "Augusta Ada")]),
+    ///     ("050C", None, vec![('a', "bar")]),
+    /// ])?;
+    ///
+    /// let theirs = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "Ada")]),
+    ///     ("050C", None, vec![('a', "baz")]),
+    ///     ("060R", None, vec![('a', "1815")]),
+    /// ])?;
+    ///
+    /// let merge = base.merge3(&ours, &theirs);
+    /// let record = merge.record();
+    /// assert_eq!(record.fields().len(), 4);
+    /// assert_eq!(
+    ///     record.fields()[3],
+    ///     Field::new("060R", None, vec![('a', "1815")])?
+    /// );
+    ///
+    /// let conflicts = merge.conflicts();
+    /// assert_eq!(conflicts.len(), 1);
+    /// assert_eq!(conflicts[0].base().unwrap().tag(), "050C");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn merge3<'b>(
+        &'b self,
+        ours: &'b RecordRef,
+        theirs: &'b RecordRef,
+    ) -> Merge<'b> {
+        let mut keys: Vec<&FieldRef> = vec![];
+        for field in ours
+            .fields()
+            .iter()
+            .chain(theirs.fields())
+            .chain(self.fields())
+        {
+            if !keys.iter().any(|key| same_key(key, field)) {
+                keys.push(field);
+            }
+        }
+
+        let mut result: Vec<Option<&FieldRef>> =
+            ours.fields().iter().map(Some).collect();
+        let mut added: Vec<&FieldRef> = vec![];
+        let mut conflicts = vec![];
+
+        for key in keys {
+            let group = |record: &'b RecordRef| -> Vec<usize> {
+                (0..record.fields().len())
+                    .filter(|&i| same_key(&record.fields()[i], key))
+                    .collect()
+            };
+
+            let (b, o, t) = (group(self), group(ours), group(theirs));
+            let base: Vec<_> =
+                b.iter().map(|&i| &self.fields()[i]).collect();
+            let (ours_of, ours_added) = states(&base, ours, &o);
+            let (theirs_of, theirs_added) = states(&base, theirs, &t);
+
+            for (i, base) in base.iter().copied().enumerate() {
+                let lhs = ours_of[i].map(|j| (j, &ours.fields()[j]));
+                let rhs = theirs_of[i].map(|k| &theirs.fields()[k]);
+
+                match (lhs, rhs) {
+                    (Some((j, lhs)), rhs) if lhs == base => {
+                        result[j] = rhs;
+                    }
+                    (Some((_, lhs)), Some(rhs))
+                        if lhs == rhs || rhs == base => {}
+                    (None, Some(rhs)) if rhs == base => {}
+                    (None, None) => {}
+                    (lhs, rhs) => conflicts.push(MergeConflict {
+                        ours: lhs.map(|(_, field)| field),
+                        theirs: rhs,
+                        base: Some(base),
+                    }),
+                }
+            }
+
+            let mut ours_added: Vec<&FieldRef> =
+                ours_added.iter().map(|&j| &ours.fields()[j]).collect();
+
+            let mut theirs_added: Vec<&FieldRef> = theirs_added
+                .iter()
+                .map(|&k| &theirs.fields()[k])
+                .filter(|rhs| {
+                    match ours_added.iter().position(|lhs| lhs == rhs) {
+                        Some(pos) => {
+                            ours_added.remove(pos);
+                            false
+                        }
+                        None => true,
+                    }
+                })
+                .collect();
+
+            // The remaining fields, which were added by both versions,
+            // are paired by position. Our version is kept.
+            let n = ours_added.len().min(theirs_added.len());
+            for (lhs, rhs) in
+                ours_added.iter().zip(theirs_added.drain(..n))
+            {
+                conflicts.push(MergeConflict {
+                    base: None,
+                    ours: Some(lhs),
+                    theirs: Some(rhs),
+                });
+            }
+
+            added.extend(theirs_added);
+        }
+
+        let mut record = Record::default();
+        for field in result.into_iter().flatten() {
+            record.push(Field::from(field.clone()));
+        }
+
+        for field in added {
+            record.insert(Field::from(field.clone()));
+        }
+
+        Merge { record, conflicts }
+    }
+}
+
+/// Aligns the base fields of a group with the fields of a changed
+/// version. Returns for each base field the index of the corresponding
+/// field (if any) and the indices of the added fields.
+fn states(
+    base: &[&FieldRef],
+    other: &RecordRef,
+    indices: &[usize],
+) -> (Vec<Option<usize>>, Vec<usize>) {
+    let fields: Vec<&FieldRef> =
+        indices.iter().map(|&i| &other.fields()[i]).collect();
+
+    let mut matched = vec![None; base.len()];
+    let mut added = vec![];
+
+    for pair in align(base, &fields) {
+        match pair {
+            (Some(i), j) => matched[i] = j.map(|j| indices[j]),
+            (None, Some(j)) => added.push(indices[j]),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    (matched, added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestResult = anyhow::Result<()>;
+
+    #[test]
+    fn test_merge3_clean() -> TestResult {
+        let base = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+            ("044H", None, vec![('a', "x")]),
+            ("050C", None, vec![('a', "foo")]),
+        ])?;
+
+        let ours = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tpz")]),
+            ("044H", None, vec![('a', "x")]),
+            ("044H", None, vec![('a', "y")]),
+            ("050C", None, vec![('a', "foo")]),
+        ])?;
+
+        let theirs = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+            ("008A", None, vec![('a', "s")]),
+            ("044H", None, vec![('a', "x")]),
+            ("044H", None, vec![('a', "y")]),
+        ])?;
+
+        let merge = base.merge3(&ours, &theirs);
+        assert!(merge.is_clean());
+        assert_eq!(
+            merge.into_record(),
+            Record::new(vec![
+                ("003@", None, vec![('0', "123")]),
+                ("002@", None, vec![('0', "Tpz")]),
+                ("008A", None, vec![('a', "s")]),
+                ("044H", None, vec![('a', "x")]),
+                ("044H", None, vec![('a', "y")]),
+            ])?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge3_conflicts() -> TestResult {
+        let base = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+            ("050C", None, vec![('a', "foo")]),
+        ])?;
+
+        let ours = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tpz")]),
+        ])?;
+
+        let theirs = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp5")]),
+            ("050C", None, vec![('a', "bar")]),
+        ])?;
+
+        let merge = base.merge3(&ours, &theirs);
+        assert_eq!(
+            merge.conflicts(),
+            &[
+                MergeConflict {
+                    base: Some(&base.fields()[1]),
+                    ours: Some(&ours.fields()[1]),
+                    theirs: Some(&theirs.fields()[1]),
+                },
+                MergeConflict {
+                    base: Some(&base.fields()[2]),
+                    ours: None,
+                    theirs: Some(&theirs.fields()[2]),
+                },
+            ]
+        );
+
+        assert_eq!(merge.record(), &ours);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge3_add_add() -> TestResult {
+        let base = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+        ])?;
+
+        let ours = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+            ("044H", None, vec![('a', "x")]),
+            ("050C", None, vec![('a', "foo")]),
+        ])?;
+
+        let theirs = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+            ("044H", None, vec![('a', "x")]),
+            ("050C", None, vec![('a', "bar")]),
+            ("050C", None, vec![('a', "baz")]),
+        ])?;
+
+        let merge = base.merge3(&ours, &theirs);
+        assert_eq!(
+            merge.conflicts(),
+            &[MergeConflict {
+                base: None,
+                ours: Some(&ours.fields()[3]),
+                theirs: Some(&theirs.fields()[3]),
+            }]
+        );
+
+        assert_eq!(
+            merge.into_record(),
+            Record::new(vec![
+                ("003@", None, vec![('0', "123")]),
+                ("002@", None, vec![('0', "Tp1")]),
+                ("044H", None, vec![('a', "x")]),
+                ("050C", None, vec![('a', "foo")]),
+                ("050C", None, vec![('a', "baz")]),
+            ])?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge3_same_change() -> TestResult {
+        let base = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tp1")]),
+            ("050C", None, vec![('a', "foo")]),
+        ])?;
+
+        let ours = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("002@", None, vec![('0', "Tpz")]),
+            ("060R", None, vec![('a', "1815")]),
+        ])?;
+
+        let merge = base.merge3(&ours, &ours);
+        assert!(merge.is_clean());
+        assert_eq!(merge.record(), &ours);
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::reader::{ReadPicaError, RecordsIter};
+use crate::writer::Compression;
+use crate::{ByteRecord, Provenance, StringRecord};
+
+/// A memory-mapped PICA+ reader.
+///
+/// The records of a memory-mapped reader borrow directly from the
+/// mapping, so no data is copied while reading records.
+pub struct MmapReader {
+    mmap: Mmap,
+    source: Option<String>,
+    pos: usize,
+    line: usize,
+}
+
+impl MmapReader {
+    /// Creates a new [MmapReader] from a path.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file can't be opened or mapped into
+    /// memory. Compressed files and stdin can't be memory-mapped.
+    pub(crate) fn from_path(path: &Path) -> io::Result<Self> {
+        if path.to_str() == Some("-")
+            || Compression::from_path(path) != Compression::None
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unable to memory-map '{}'", path.display()),
+            ));
+        }
+
+        let file = File::open(path)?;
+
+        // SAFETY: The mapping is read-only. It's undefined behavior if
+        // the underlying file is modified while the mapping exists;
+        // it's up to the caller to ensure that this doesn't happen.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        Ok(Self {
+            source: path.to_str().map(ToString::to_string),
+            mmap,
+            pos: 0,
+            line: 0,
+        })
+    }
+
+    /// Returns the mapped data as a byte slice.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let reader =
+    ///     ReaderBuilder::new().from_path_mmap("tests/data/ada.dat")?;
+    /// assert!(reader.as_bytes().starts_with(b"001A "));
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Returns an iterator over all records of the mapping.
+    ///
+    /// In contrast to [RecordsIter::next_byte_record], the records
+    /// yielded by this iterator borrow from the mapping only and can
+    /// outlive the next call of the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let reader =
+    ///     ReaderBuilder::new().from_path_mmap("tests/data/ada.dat")?;
+    ///
+    /// let records =
+    ///     reader.records().collect::<Result<Vec<ByteRecord>, _>>()?;
+    ///
+    /// assert_eq!(records.len(), 1);
+    /// assert_eq!(records[0].ppn().unwrap(), "119232022");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn records(&self) -> MmapRecords<'_> {
+        MmapRecords::new(&self.mmap, self.source.as_deref())
+    }
+}
+
+impl RecordsIter for MmapReader {
+    type ByteItem<'a>
+        = Result<ByteRecord<'a>, ReadPicaError>
+    where
+        Self: 'a;
+
+    type StringItem<'a>
+        = Result<StringRecord<'a>, ReadPicaError>
+    where
+        Self: 'a;
+
+    /// Advance the iterator and return the next [ByteRecord].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_path_mmap("tests/data/ada.dat")?;
+    ///
+    /// let mut count = 0;
+    /// while let Some(result) = reader.next_byte_record() {
+    ///     if result.is_ok() {
+    ///         count += 1
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(count, 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn next_byte_record(&mut self) -> Option<Self::ByteItem<'_>> {
+        let mut records = MmapRecords {
+            data: &self.mmap[self.pos..],
+            source: self.source.as_deref(),
+            line: self.line,
+            pos: self.pos,
+        };
+
+        let result = records.next();
+        self.line = records.line;
+        self.pos = records.pos;
+        result
+    }
+
+    /// Advance the iterator and return the next [StringRecord].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_path_mmap("tests/data/ada.dat")?;
+    ///
+    /// let record = reader.next_string_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "119232022");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn next_string_record(&mut self) -> Option<Self::StringItem<'_>> {
+        let line = self.line + 1;
+        match self.next_byte_record()? {
+            Ok(record) => match StringRecord::try_from(record) {
+                Ok(record) => Some(Ok(record)),
+                Err(err) => {
+                    let msg = format!("invalid record on line {line}.");
+                    Some(Err(ReadPicaError::Utf8 { msg, err }))
+                }
+            },
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// An iterator over the records of a byte slice.
+///
+/// This iterator is created by [MmapReader::records].
+pub struct MmapRecords<'a> {
+    data: &'a [u8],
+    source: Option<&'a str>,
+    line: usize,
+    pos: usize,
+}
+
+impl<'a> MmapRecords<'a> {
+    fn new(data: &'a [u8], source: Option<&'a str>) -> Self {
+        Self {
+            data,
+            source,
+            line: 0,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for MmapRecords<'a> {
+    type Item = Result<ByteRecord<'a>, ReadPicaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let (data, rest) = self.data.split_at(next_boundary(self.data));
+        let provenance = Provenance::new(
+            self.source,
+            self.line + 1,
+            Some(self.pos as u64),
+        );
+
+        self.data = rest;
+        self.pos += data.len();
+        self.line += 1;
+
+        Some(
+            ByteRecord::from_bytes(data)
+                .map(|record| record.with_provenance(provenance))
+                .map_err(|err| {
+                    ReadPicaError::parse(self.source, self.line, err)
+                }),
+        )
+    }
+}
+
+/// Returns the position after the next record boundary (newline) or
+/// the length of the data, if there is no further newline.
+#[inline]
+fn next_boundary(data: &[u8]) -> usize {
+    data.iter()
+        .position(|b| *b == b'\n')
+        .map_or(data.len(), |pos| pos + 1)
+}
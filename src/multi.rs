@@ -0,0 +1,179 @@
+use std::io;
+
+use crate::ByteRecord;
+use crate::matcher::{MatcherOptions, RecordMatcher};
+use crate::writer::ByteRecordWrite;
+
+/// A sink of a [MultiWriter].
+struct Sink<'a> {
+    matcher: Option<RecordMatcher>,
+    writer: Box<dyn ByteRecordWrite + 'a>,
+}
+
+/// A [ByteRecord] writer that fans out records to multiple sinks.
+///
+/// Each sink consists of an optional [RecordMatcher] and an underlying
+/// writer. A record is written to a sink, if the sink has no matcher
+/// or if the matcher matches the record. By default, a record is
+/// written to _every_ matching sink. In first-match mode, a record is
+/// only written to the first matching sink, so that a sink without a
+/// matcher, which is added last, receives all remaining records.
+pub struct MultiWriter<'a> {
+    sinks: Vec<Sink<'a>>,
+    options: MatcherOptions,
+    first_match: bool,
+}
+
+impl Default for MultiWriter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MultiWriter<'a> {
+    /// Creates a new [MultiWriter] without any sinks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::MultiWriter;
+    ///
+    /// let writer = MultiWriter::new();
+    /// assert!(writer.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            options: MatcherOptions::default(),
+            first_match: false,
+            sinks: vec![],
+        }
+    }
+
+    /// Sets the options, which are used to evaluate the matchers.
+    pub fn options(mut self, options: MatcherOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Whether to write a record only to the first matching sink or
+    /// not.
+    ///
+    /// This flag is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::{MultiWriter, PlainWriter};
+    /// use pica_record::matcher::RecordMatcher;
+    /// use pica_record::prelude::*;
+    ///
+    /// let (mut persons, mut rest) = (Vec::new(), Vec::new());
+    /// let mut writer = MultiWriter::new().first_match(true);
+    /// writer.add(
+    ///     Some(RecordMatcher::new("002@.0 =^ 'Tp'")?),
+    ///     PlainWriter::new(&mut persons),
+    /// );
+    /// writer.add(None, PlainWriter::new(&mut rest));
+    ///
+    /// let record = ByteRecord::from_bytes(b"002@ \x1f0Tp1\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// let record = ByteRecord::from_bytes(b"002@ \x1f0Ts1\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(persons, b"002@ \x1f0Tp1\x1e\n");
+    /// assert_eq!(rest, b"002@ \x1f0Ts1\x1e\n");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn first_match(mut self, yes: bool) -> Self {
+        self.first_match = yes;
+        self
+    }
+
+    /// Adds a new sink to the writer.
+    ///
+    /// If no matcher is given, the sink receives every record (resp.
+    /// every remaining record in first-match mode).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::{MultiWriter, PlainWriter};
+    /// use pica_record::matcher::RecordMatcher;
+    /// use pica_record::prelude::*;
+    ///
+    /// let (mut all, mut persons) = (Vec::new(), Vec::new());
+    /// let mut writer = MultiWriter::new();
+    /// writer.add(None, PlainWriter::new(&mut all));
+    /// writer.add(
+    ///     Some(RecordMatcher::new("002@.0 =^ 'Tp'")?),
+    ///     PlainWriter::new(&mut persons),
+    /// );
+    ///
+    /// let record = ByteRecord::from_bytes(b"002@ \x1f0Tp1\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(all, b"002@ \x1f0Tp1\x1e\n");
+    /// assert_eq!(persons, b"002@ \x1f0Tp1\x1e\n");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add<W>(&mut self, matcher: Option<RecordMatcher>, writer: W)
+    where
+        W: ByteRecordWrite + 'a,
+    {
+        self.sinks.push(Sink {
+            writer: Box::new(writer),
+            matcher,
+        });
+    }
+
+    /// Returns the number of sinks.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Returns `true` if the writer has no sinks.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+}
+
+impl ByteRecordWrite for MultiWriter<'_> {
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        for sink in self.sinks.iter_mut() {
+            let is_match = match sink.matcher {
+                Some(ref matcher) => {
+                    matcher.is_match(record, &self.options)
+                }
+                None => true,
+            };
+
+            if is_match {
+                sink.writer.write_byte_record(record)?;
+                if self.first_match {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        for sink in self.sinks.iter_mut() {
+            sink.writer.finish()?;
+        }
+
+        Ok(())
+    }
+}
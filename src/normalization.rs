@@ -0,0 +1,120 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+/// A Unicode normalization form.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum NormalizationForm {
+    /// Canonical decomposition, followed by canonical composition.
+    #[default]
+    Nfc,
+    /// Compatibility decomposition, followed by canonical
+    /// composition.
+    Nfkc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Converts the string into this normalization form.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::NormalizationForm;
+    ///
+    /// let nf = NormalizationForm::Nfc;
+    /// assert_eq!(nf.normalize("Ame\u{301}lie"), "Am\u{0e9}lie");
+    ///
+    /// let nf = NormalizationForm::Nfd;
+    /// assert_eq!(nf.normalize("Am\u{0e9}lie"), "Ame\u{301}lie");
+    /// ```
+    pub fn normalize(&self, s: &str) -> String {
+        match self {
+            Self::Nfc => s.nfc().collect(),
+            Self::Nfkc => s.nfkc().collect(),
+            Self::Nfd => s.nfd().collect(),
+            Self::Nfkd => s.nfkd().collect(),
+        }
+    }
+
+    /// Returns the name of the normalization form.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::NormalizationForm;
+    ///
+    /// assert_eq!(NormalizationForm::Nfkd.as_str(), "nfkd");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Nfc => "nfc",
+            Self::Nfkc => "nfkc",
+            Self::Nfd => "nfd",
+            Self::Nfkd => "nfkd",
+        }
+    }
+}
+
+impl Display for NormalizationForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NormalizationForm {
+    type Err = String;
+
+    /// Parses a normalization form from its (lowercase) name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::NormalizationForm;
+    ///
+    /// assert_eq!("nfd".parse(), Ok(NormalizationForm::Nfd));
+    /// assert!("NFD".parse::<NormalizationForm>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nfc" => Ok(Self::Nfc),
+            "nfkc" => Ok(Self::Nfkc),
+            "nfd" => Ok(Self::Nfd),
+            "nfkd" => Ok(Self::Nfkd),
+            _ => Err(format!("invalid normalization form '{s}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string() {
+        assert_eq!(NormalizationForm::Nfc.to_string(), "nfc");
+        assert_eq!(NormalizationForm::Nfkc.to_string(), "nfkc");
+        assert_eq!(NormalizationForm::Nfd.to_string(), "nfd");
+        assert_eq!(NormalizationForm::Nfkd.to_string(), "nfkd");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(NormalizationForm::Nfc, "nfc".parse().unwrap());
+        assert!("NFC".parse::<NormalizationForm>().is_err());
+        assert_eq!(NormalizationForm::Nfkc, "nfkc".parse().unwrap());
+        assert!("NFKC".parse::<NormalizationForm>().is_err());
+        assert_eq!(NormalizationForm::Nfd, "nfd".parse().unwrap());
+        assert!("NFD".parse::<NormalizationForm>().is_err());
+        assert_eq!(NormalizationForm::Nfkd, "nfkd".parse().unwrap());
+        assert!("NFKD".parse::<NormalizationForm>().is_err());
+    }
+}
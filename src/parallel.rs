@@ -0,0 +1,846 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::mpsc::{Receiver, sync_channel};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::encoding::Encoding;
+use crate::reader::{ReadPicaError, open};
+use crate::repair::{ParseMode, Policy, Repairs, is_blank};
+use crate::{ByteRecord, Provenance};
+
+/// The default size (in bytes) of a chunk.
+const DEFAULT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Configures and builds a parallel PICA+ reader.
+///
+/// A parallel reader splits the input at record boundaries into chunks
+/// of (approximately) `chunk_size` bytes. The chunks are parsed on a
+/// pool of worker threads.
+#[derive(Debug, Clone)]
+pub struct ParReaderBuilder {
+    threads: usize,
+    chunk_size: usize,
+    ordered: bool,
+    policy: Policy,
+}
+
+impl Default for ParReaderBuilder {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism()
+                .map_or(1, NonZeroUsize::get),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            ordered: true,
+            policy: Policy::default(),
+        }
+    }
+}
+
+impl ParReaderBuilder {
+    /// Creates a new [ParReaderBuilder].
+    ///
+    /// By default, the number of worker threads is equal to the
+    /// available parallelism and the results are delivered in input
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ParReaderBuilder;
+    ///
+    /// let _reader = ParReaderBuilder::new()
+    ///     .threads(2)
+    ///     .from_path("tests/data/DUMP.dat.gz")?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of worker threads.
+    ///
+    /// # Panics
+    ///
+    /// It's an error to use zero worker threads.
+    pub fn threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0);
+        self.threads = threads;
+        self
+    }
+
+    /// Sets the (minimum) size of a chunk in bytes.
+    ///
+    /// A chunk is always extended up to the next record boundary, so
+    /// a chunk contains at least one record.
+    ///
+    /// # Panics
+    ///
+    /// It's an error to use a chunk size of zero.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        assert!(size > 0);
+        self.chunk_size = size;
+        self
+    }
+
+    /// Whether results are delivered in input order or not.
+    ///
+    /// When this flag is set, the results of [ParReader::map] and the
+    /// partial results of [ParReader::reduce] are passed on in the
+    /// order of the input. Otherwise, the results are passed on as
+    /// soon as a chunk is processed. This flag is enabled by default.
+    pub fn ordered(mut self, yes: bool) -> Self {
+        self.ordered = yes;
+        self
+    }
+
+    /// Sets the parsing mode (see
+    /// [ReaderBuilder::mode](crate::io::ReaderBuilder::mode)).
+    ///
+    /// Unlike a sequential reader, a parallel reader doesn't count the
+    /// repairs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::io::{ParReaderBuilder, ParseMode};
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ \x1f0123\x1e\r\n\n003@ \x1f0456");
+    /// let (valid, invalid) = ParReaderBuilder::new()
+    ///     .mode(ParseMode::Lenient)
+    ///     .from_reader(data, None)
+    ///     .reduce(
+    ///         || (0, 0),
+    ///         |(valid, invalid), result| match result {
+    ///             Ok(_) => (valid + 1, invalid),
+    ///             Err(_) => (valid, invalid + 1),
+    ///         },
+    ///         |lhs, rhs| (lhs.0 + rhs.0, lhs.1 + rhs.1),
+    ///     )?;
+    ///
+    /// assert_eq!((valid, invalid), (2, 0));
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.policy.mode = mode;
+        self
+    }
+
+    /// Sets the maximum size of a record in bytes (see
+    /// [ReaderBuilder::max_record_size](crate::io::ReaderBuilder::max_record_size)).
+    pub fn max_record_size(mut self, size: usize) -> Self {
+        self.policy.max_record_size = Some(size);
+        self
+    }
+
+    /// Whether to remove subfields with an empty value or not (see
+    /// [ReaderBuilder::drop_empty_subfields](crate::io::ReaderBuilder::drop_empty_subfields)).
+    pub fn drop_empty_subfields(mut self, yes: bool) -> Self {
+        self.policy.drop_empty_subfields = yes;
+        self
+    }
+
+    /// Sets the character encoding of the input data (see
+    /// [ReaderBuilder::encoding](crate::io::ReaderBuilder::encoding)).
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.policy.encoding = encoding;
+        self
+    }
+
+    /// Creates a new [ParReader] from an existing reader.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::io::ParReaderBuilder;
+    ///
+    /// let data = Cursor::new(b"002@ \x1f0Abvz\x1e\n");
+    /// let _reader = ParReaderBuilder::new().from_reader(data, None);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_reader<R: Read>(
+        &self,
+        reader: R,
+        source: Option<String>,
+    ) -> ParReader<R> {
+        let source = source
+            .map(|s| if s == "-" { "<stdin>".to_string() } else { s });
+
+        ParReader {
+            inner: BufReader::new(reader),
+            builder: self.clone(),
+            source,
+        }
+    }
+
+    /// Creates a new [ParReader] from a path.
+    ///
+    /// # Note
+    ///
+    /// A path equal to "-" means reading from stdin.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ParReaderBuilder;
+    ///
+    /// let _reader =
+    ///     ParReaderBuilder::new().from_path("tests/data/DUMP.dat.gz")?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<ParReader<Box<dyn Read>>> {
+        let path = path.as_ref();
+        let source = path.to_str().map(ToString::to_string);
+        Ok(self.from_reader(open(path, self.threads)?, source))
+    }
+}
+
+/// A chunk of consecutive records.
+struct Chunk {
+    /// The sequence number of the chunk.
+    index: usize,
+    /// The number of lines before the first record of the chunk.
+    line: usize,
+    /// The number of records before the first record of the chunk.
+    record: usize,
+    /// The byte offset of the chunk within the input.
+    offset: u64,
+    /// The raw data of the chunk.
+    data: Vec<u8>,
+}
+
+/// A reader, which parses records on a pool of worker threads.
+pub struct ParReader<R: Read> {
+    inner: BufReader<R>,
+    builder: ParReaderBuilder,
+    source: Option<String>,
+}
+
+impl<R: Read> ParReader<R> {
+    /// Calls a closure on each record in parallel.
+    ///
+    /// The closure is called concurrently from multiple threads and
+    /// the records are passed in no particular order.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the underlying reader fails. Invalid
+    /// records are passed on to the closure.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// use pica_record::io::ParReaderBuilder;
+    /// use pica_record::prelude::*;
+    ///
+    /// let matcher = RecordMatcher::new("002@.0 == 'Tu1'")?;
+    /// let options = MatcherOptions::default();
+    /// let count = AtomicUsize::new(0);
+    ///
+    /// ParReaderBuilder::new()
+    ///     .chunk_size(1024)
+    ///     .from_path("tests/data/DUMP.dat.gz")?
+    ///     .for_each(|result| {
+    ///         if let Ok(record) = result {
+    ///             if matcher.is_match(&record, &options) {
+    ///                 count.fetch_add(1, Ordering::Relaxed);
+    ///             }
+    ///         }
+    ///     })?;
+    ///
+    /// assert_eq!(count.load(Ordering::Relaxed), 6);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn for_each<F>(self, f: F) -> Result<(), ReadPicaError>
+    where
+        F: Fn(Result<ByteRecord, ReadPicaError>) + Sync,
+    {
+        self.run(
+            |chunk, source, policy| {
+                for_each_record(chunk, source, policy, &f);
+            },
+            |_| Ok::<(), ReadPicaError>(()),
+        )
+    }
+
+    /// Maps each record in parallel and passes the results to a sink.
+    ///
+    /// The closure `f` is called concurrently from multiple threads,
+    /// whereas the `sink` is called from the current thread only. If
+    /// the reader is ordered (default), the results are passed to the
+    /// sink in input order.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if either the underlying reader or the sink
+    /// fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ParReaderBuilder;
+    /// use pica_record::prelude::*;
+    ///
+    /// let path = Path::new("003@.0")?;
+    /// let options = MatcherOptions::default();
+    /// let mut ppns = vec![];
+    ///
+    /// ParReaderBuilder::new()
+    ///     .chunk_size(1024)
+    ///     .from_path("tests/data/DUMP.dat.gz")?
+    ///     .map(
+    ///         |result| {
+    ///             result.ok().and_then(|record| {
+    ///                 record
+    ///                     .path(&path, &options)
+    ///                     .next()
+    ///                     .map(ToString::to_string)
+    ///             })
+    ///         },
+    ///         |ppn| {
+    ///             ppns.extend(ppn);
+    ///             Ok::<(), ReadPicaError>(())
+    ///         },
+    ///     )?;
+    ///
+    /// assert_eq!(ppns.len(), 12);
+    /// assert_eq!(ppns[0], "118540238");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn map<T, E, F, S>(self, f: F, mut sink: S) -> Result<(), E>
+    where
+        T: Send,
+        E: From<ReadPicaError>,
+        F: Fn(Result<ByteRecord, ReadPicaError>) -> T + Sync,
+        S: FnMut(T) -> Result<(), E>,
+    {
+        self.run(
+            |chunk, source, policy| {
+                let mut items = vec![];
+                for_each_record(chunk, source, policy, |result| {
+                    items.push(f(result))
+                });
+                items
+            },
+            |items| {
+                for item in items {
+                    sink(item)?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Folds the records in parallel and merges the partial results.
+    ///
+    /// Each chunk is folded with `fold`, starting with the value
+    /// returned by `identity`. The partial results of the chunks are
+    /// combined by `merge`. If the reader is ordered (default), the
+    /// partial results are merged in input order.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the underlying reader fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ParReaderBuilder;
+    ///
+    /// let (valid, invalid) = ParReaderBuilder::new()
+    ///     .chunk_size(1024)
+    ///     .from_path("tests/data/DUMP.dat.gz")?
+    ///     .reduce(
+    ///         || (0, 0),
+    ///         |(valid, invalid), result| match result {
+    ///             Ok(_) => (valid + 1, invalid),
+    ///             Err(_) => (valid, invalid + 1),
+    ///         },
+    ///         |lhs, rhs| (lhs.0 + rhs.0, lhs.1 + rhs.1),
+    ///     )?;
+    ///
+    /// assert_eq!(valid, 12);
+    /// assert_eq!(invalid, 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reduce<T, I, F, M>(
+        self,
+        identity: I,
+        fold: F,
+        merge: M,
+    ) -> Result<T, ReadPicaError>
+    where
+        T: Send,
+        I: Fn() -> T + Sync,
+        F: Fn(T, Result<ByteRecord, ReadPicaError>) -> T + Sync,
+        M: Fn(T, T) -> T,
+    {
+        let mut acc = None;
+
+        self.run(
+            |chunk, source, policy| {
+                let mut value = Some(identity());
+                for_each_record(chunk, source, policy, |result| {
+                    value =
+                        value.take().map(|value| fold(value, result));
+                });
+
+                value.unwrap()
+            },
+            |value| {
+                acc = Some(match acc.take() {
+                    Some(acc) => merge(acc, value),
+                    None => value,
+                });
+
+                Ok::<(), ReadPicaError>(())
+            },
+        )?;
+
+        Ok(acc.unwrap_or_else(identity))
+    }
+
+    /// Reads the next chunk from the underlying reader.
+    fn next_chunk(
+        &mut self,
+        index: usize,
+        line: usize,
+        record: usize,
+        offset: u64,
+    ) -> io::Result<Option<Chunk>> {
+        let mut data = Vec::with_capacity(self.builder.chunk_size);
+
+        let n = (&mut self.inner)
+            .take(self.builder.chunk_size as u64)
+            .read_to_end(&mut data)?;
+
+        if n == 0 {
+            return Ok(None);
+        }
+
+        // Extend the chunk up to the next record terminator. Cutting
+        // at a bare newline could split an invalid record, which would
+        // shift the line numbers of all subsequent records.
+        while !data.ends_with(b"\x1e\n") && !data.ends_with(b"\x1e\r\n")
+        {
+            if self.inner.read_until(b'\n', &mut data)? == 0 {
+                break;
+            }
+        }
+
+        Ok(Some(Chunk {
+            index,
+            line,
+            record,
+            offset,
+            data,
+        }))
+    }
+
+    /// Distributes the chunks of the input among the worker threads.
+    ///
+    /// Every chunk is processed by `process` on a worker thread. The
+    /// results are passed to `consume` on the current thread. At most
+    /// `2 * threads` chunks are in flight (read, but not yet consumed),
+    /// which bounds the memory usage even if a single chunk is slow to
+    /// process and the results must be delivered in input order.
+    fn run<T, E, P, C>(
+        mut self,
+        process: P,
+        mut consume: C,
+    ) -> Result<(), E>
+    where
+        T: Send,
+        E: From<ReadPicaError>,
+        P: Fn(&Chunk, Option<&str>, &Policy) -> T + Sync,
+        C: FnMut(T) -> Result<(), E>,
+    {
+        let threads = self.builder.threads;
+        let ordered = self.builder.ordered;
+        let window = 2 * threads;
+        let policy = self.builder.policy;
+        let source = self.source.take();
+
+        thread::scope(|scope| {
+            let (chunk_tx, chunk_rx) = sync_channel::<Chunk>(window);
+            let (result_tx, result_rx) =
+                sync_channel::<(usize, T)>(window);
+            let chunk_rx = Arc::new(Mutex::new(chunk_rx));
+
+            for _ in 0..threads {
+                let chunk_rx = Arc::clone(&chunk_rx);
+                let result_tx = result_tx.clone();
+                let process = &process;
+                let policy = &policy;
+                let source = source.as_deref();
+
+                scope.spawn(move || {
+                    while let Some(chunk) = recv(&chunk_rx) {
+                        let value = process(&chunk, source, policy);
+                        if result_tx.send((chunk.index, value)).is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+            }
+
+            drop(result_tx);
+
+            let mut pending = BTreeMap::new();
+            let mut consumed = 0;
+
+            let mut deliver = |index: usize, value: T| {
+                if !ordered {
+                    consumed += 1;
+                    return consume(value).map(|_| consumed);
+                }
+
+                pending.insert(index, value);
+                while let Some(value) = pending.remove(&consumed) {
+                    consume(value)?;
+                    consumed += 1;
+                }
+
+                Ok(consumed)
+            };
+
+            let mut index = 0;
+            let mut line = 0;
+            let mut record = 0;
+            let mut offset = 0;
+            let mut done = 0;
+
+            loop {
+                while index - done >= window {
+                    match result_rx.recv() {
+                        Ok((index, value)) => {
+                            done = deliver(index, value)?
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                let chunk = match self
+                    .next_chunk(index, line, record, offset)
+                {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(e) => {
+                        return Err(ReadPicaError::from(e).into());
+                    }
+                };
+
+                let lines =
+                    chunk.data.iter().filter(|b| **b == b'\n').count();
+                let blanks = if policy.mode == ParseMode::Lenient {
+                    chunk
+                        .data
+                        .split_inclusive(|b| *b == b'\n')
+                        .filter(|line| is_blank(line))
+                        .count()
+                } else {
+                    0
+                };
+
+                line += lines;
+                record += lines - blanks;
+                offset += chunk.data.len() as u64;
+                index += 1;
+
+                if chunk_tx.send(chunk).is_err() {
+                    break;
+                }
+
+                while let Ok((index, value)) = result_rx.try_recv() {
+                    done = deliver(index, value)?;
+                }
+            }
+
+            drop(chunk_tx);
+
+            for (index, value) in result_rx {
+                deliver(index, value)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Receives the next chunk from a shared receiver.
+#[inline]
+fn recv(rx: &Mutex<Receiver<Chunk>>) -> Option<Chunk> {
+    rx.lock().ok()?.recv().ok()
+}
+
+/// Parses each record of the chunk and calls the closure on it.
+///
+/// If the parsing policy isn't the default policy, each record is
+/// copied into a buffer and the policy is applied before parsing.
+fn for_each_record<F>(
+    chunk: &Chunk,
+    source: Option<&str>,
+    policy: &Policy,
+    mut f: F,
+) where
+    F: FnMut(Result<ByteRecord, ReadPicaError>),
+{
+    let strict = *policy == Policy::default();
+    let lenient = policy.mode == ParseMode::Lenient;
+    let mut repairs = Repairs::default();
+    let mut scratch = vec![];
+    let mut buf = vec![];
+
+    let mut offset = chunk.offset;
+    let mut record = chunk.record;
+
+    for (i, data) in
+        chunk.data.split_inclusive(|b| *b == b'\n').enumerate()
+    {
+        let line = chunk.line + i + 1;
+        let start = offset;
+        offset += data.len() as u64;
+
+        if lenient && is_blank(data) {
+            continue;
+        }
+
+        record += 1;
+
+        let data = if strict {
+            data
+        } else {
+            buf.clear();
+            buf.extend_from_slice(data);
+
+            if let Err(err) =
+                policy.apply(&mut buf, &mut scratch, &mut repairs)
+            {
+                f(Err(ReadPicaError::parse(source, line, err)));
+                continue;
+            }
+
+            &buf
+        };
+
+        let provenance = Provenance::new(source, record, Some(start));
+        match ByteRecord::from_bytes(data) {
+            Ok(record) => f(Ok(record.with_provenance(provenance))),
+            Err(err) => f(Err(ReadPicaError::parse(source, line, err))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Returns the line numbers of the valid and invalid records.
+    fn lines(
+        builder: &ParReaderBuilder,
+        data: &'static [u8],
+    ) -> Vec<Result<usize, usize>> {
+        let mut lines = vec![];
+
+        builder
+            .from_reader(Cursor::new(data), None)
+            .map(
+                |result| match result {
+                    Ok(record) => {
+                        Ok(record.provenance().unwrap().index())
+                    }
+                    Err(ReadPicaError::Parse { line, .. }) => {
+                        Err(line.unwrap())
+                    }
+                    Err(e) => panic!("unexpected error: {e}"),
+                },
+                |line| {
+                    lines.push(line);
+                    Ok::<(), ReadPicaError>(())
+                },
+            )
+            .unwrap();
+
+        lines
+    }
+
+    fn records(n: usize) -> &'static [u8] {
+        let data: String =
+            (1..=n).map(|i| format!("003@ \x1f0{i}\x1e\n")).collect();
+
+        data.leak().as_bytes()
+    }
+
+    #[test]
+    fn test_ordered() {
+        let data = records(500);
+        let builder = ParReaderBuilder::new().threads(4).chunk_size(16);
+
+        assert_eq!(
+            lines(&builder, data),
+            (1..=500).map(Ok).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_unordered() {
+        let data = records(500);
+        let builder = ParReaderBuilder::new()
+            .threads(4)
+            .chunk_size(16)
+            .ordered(false);
+
+        let mut lines = lines(&builder, data);
+        lines.sort();
+
+        assert_eq!(lines, (1..=500).map(Ok).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_bounded() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let data = records(500);
+        let started = AtomicUsize::new(0);
+        let mut first = None;
+
+        ParReaderBuilder::new()
+            .threads(2)
+            .chunk_size(1)
+            .from_reader(Cursor::new(data), None)
+            .map(
+                |result| {
+                    let line =
+                        result.unwrap().provenance().unwrap().index();
+                    if line == 1 {
+                        thread::sleep(Duration::from_millis(100));
+                    }
+
+                    started.fetch_add(1, Ordering::SeqCst)
+                },
+                |_| {
+                    first.get_or_insert(started.load(Ordering::SeqCst));
+                    Ok::<(), ReadPicaError>(())
+                },
+            )
+            .unwrap();
+
+        // The first record is consumed before more than four chunks
+        // (two per thread) are read.
+        assert!(first.unwrap() <= 4);
+    }
+
+    #[test]
+    fn test_chunk_boundaries() {
+        let data =
+            b"003@ \x1f01\x1e\n003@ \x1f0\n2\x1e\n003@ \x1f03\x1e\n";
+
+        for chunk_size in 1..=data.len() {
+            let builder = ParReaderBuilder::new()
+                .threads(2)
+                .chunk_size(chunk_size);
+            assert_eq!(
+                lines(&builder, data),
+                vec![Ok(1), Err(2), Err(3), Ok(4)],
+                "chunk size {chunk_size}"
+            );
+        }
+
+        let mut offsets = vec![];
+        ParReaderBuilder::new()
+            .chunk_size(1)
+            .from_reader(Cursor::new(data), None)
+            .map(
+                |result| {
+                    result.ok().and_then(|r| r.provenance()?.offset())
+                },
+                |offset| {
+                    offsets.extend(offset);
+                    Ok::<(), ReadPicaError>(())
+                },
+            )
+            .unwrap();
+
+        assert_eq!(offsets, vec![0, 21]);
+    }
+
+    #[test]
+    fn test_policy() {
+        let data = b"003@ \x1f01\x1e\r\n\n003@ \x1f02\x1fa\x1e\n\
+            003@ \x1f0123456789X12\x1e\n028A \x1fdJ\xfcrgen\x1e\n003@ \x1f05";
+
+        for chunk_size in [1, 16, data.len()] {
+            let builder = ParReaderBuilder::new()
+                .threads(2)
+                .chunk_size(chunk_size)
+                .mode(ParseMode::Lenient)
+                .max_record_size(20)
+                .drop_empty_subfields(true)
+                .encoding(Encoding::Latin1);
+
+            assert_eq!(
+                lines(&builder, data),
+                vec![Ok(1), Ok(2), Err(4), Ok(4), Ok(5)],
+                "chunk size {chunk_size}"
+            );
+        }
+
+        let builder = ParReaderBuilder::new().threads(2).chunk_size(1);
+        assert_eq!(
+            lines(&builder, data),
+            vec![Err(1), Err(2), Ok(3), Ok(4), Ok(5), Err(6)]
+        );
+    }
+
+    #[test]
+    fn test_error_lines() {
+        let data = b"003@ \x1f01\x1e\n003@ \x1f02\n003@ \x1f03\x1e\n\
+            003@ \x1f04\x1e\n003@ \x1f\x1e\n";
+        let mut errors = vec![];
+
+        ParReaderBuilder::new()
+            .threads(3)
+            .chunk_size(1)
+            .from_reader(Cursor::new(data), Some("DUMP.dat".into()))
+            .map(
+                |result| result.err().map(|e| e.to_string()),
+                |err| {
+                    errors.extend(err);
+                    Ok::<(), ReadPicaError>(())
+                },
+            )
+            .unwrap();
+
+        assert_eq!(errors.len(), 2);
+        assert!(
+            errors[0].contains("invalid record on line 2 (DUMP.dat)")
+        );
+        assert!(
+            errors[1].contains("invalid record on line 5 (DUMP.dat)")
+        );
+    }
+}
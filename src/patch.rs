@@ -0,0 +1,719 @@
+//! Field- and subfield-level changes, which can be applied to records.
+//!
+//! A [Change] adds, removes or replaces a field or a subfield. The
+//! affected fields are selected by a tag and an occurrence matcher
+//! (e.g. `044H/*`). Changes that remove or replace data can be guarded
+//! by the expected old value: if the record no longer contains the
+//! expected value, the change isn't applied and a [Conflict] is
+//! reported instead.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::matcher::{OccurrenceMatcher, TagMatcher};
+use crate::primitives::{
+    Field, OccurrenceRef, Record, Subfield, SubfieldCode,
+    SubfieldValue, TagRef,
+};
+
+/// An error that can occur when parsing a change.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct ParsePatchError(pub(crate) String);
+
+/// The kind of a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Remove,
+    Replace,
+}
+
+impl FromStr for Operation {
+    type Err = ParsePatchError;
+
+    /// Parses an operation from a string slice.
+    ///
+    /// Besides `add`, `remove` and `replace`, the names used by the
+    /// diff of two records (`added`, `deleted` and `modified`) are
+    /// accepted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::patch::Operation;
+    ///
+    /// assert_eq!("add".parse::<Operation>()?, Operation::Add);
+    /// assert_eq!("deleted".parse::<Operation>()?, Operation::Remove);
+    /// assert!("foo".parse::<Operation>().is_err());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add" | "added" => Ok(Self::Add),
+            "remove" | "deleted" => Ok(Self::Remove),
+            "replace" | "modified" => Ok(Self::Replace),
+            _ => {
+                Err(ParsePatchError(format!("invalid operation '{s}'")))
+            }
+        }
+    }
+}
+
+/// The reason why a change couldn't be applied to a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum Conflict {
+    /// The record doesn't contain a matching field.
+    #[error("field not found")]
+    FieldNotFound,
+    /// None of the matching fields contains the subfield.
+    #[error("subfield not found")]
+    SubfieldNotFound,
+    /// The matching fields or subfields don't have the expected old
+    /// value.
+    #[error("old value doesn't match")]
+    GuardMismatch,
+    /// The field to be added already exists.
+    #[error("field already exists")]
+    FieldExists,
+    /// The subfield to be added already exists.
+    #[error("subfield already exists")]
+    SubfieldExists,
+    /// The change can't be applied unambiguously, because more than one
+    /// field matches.
+    #[error("ambiguous change ({0} fields match)")]
+    Ambiguous(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    AddField(Field),
+    RemoveField {
+        guard: Option<Vec<Subfield>>,
+    },
+    ReplaceField {
+        guard: Option<Vec<Subfield>>,
+        subfields: Vec<Subfield>,
+    },
+    AddSubfield(Subfield),
+    RemoveSubfield {
+        code: SubfieldCode,
+        guard: Option<SubfieldValue>,
+    },
+    ReplaceSubfield {
+        code: SubfieldCode,
+        guard: Option<SubfieldValue>,
+        value: SubfieldValue,
+    },
+}
+
+/// A single change of a field or subfield.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    tag: TagMatcher,
+    occurrence: OccurrenceMatcher,
+    kind: Kind,
+}
+
+impl Change {
+    /// Creates a new [Change].
+    ///
+    /// The `field` consists of a tag and an optional occurrence
+    /// matcher (e.g. `028A` or `044H/*`). If no subfield `code` is
+    /// given, the change affects whole fields and the `old` and `new`
+    /// values are lists of subfields in PICA-Plain (e.g. `$aX$bY`,
+    /// whereby a `$` within a value is escaped as `$$`). Otherwise,
+    /// the change affects the subfields with the given code and the
+    /// values are plain subfield values.
+    ///
+    /// An `add` requires a `new` value; added fields need a tag and
+    /// occurrence without wildcards. A `remove` must not have a `new`
+    /// value and a `replace` requires a `new` value. The `old` value of
+    /// a `remove` or `replace` is optional and acts as a guard.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the field expression, the subfield code
+    /// or any of the values is invalid, or if a required value is
+    /// missing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::patch::{Change, Operation};
+    ///
+    /// let _change =
+    ///     Change::new(Operation::Add, "060R", None, None, Some("$a1815"))?;
+    /// let _change = Change::new(
+    ///     Operation::Replace,
+    ///     "028A",
+    ///     Some('d'),
+    ///     Some("Ada"),
+    ///     Some("Augusta Ada"),
+    /// )?;
+    ///
+    /// assert!(Change::new(Operation::Add, "044H/*", None, None, None)
+    ///     .is_err());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(
+        op: Operation,
+        field: &str,
+        code: Option<char>,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) -> Result<Self, ParsePatchError> {
+        let invalid_field =
+            || ParsePatchError(format!("invalid field '{field}'"));
+
+        let (name, occ) = match field.find('/') {
+            Some(pos) => (&field[..pos], Some(&field[pos..])),
+            None => (field, None),
+        };
+
+        let tag = TagMatcher::new(name).map_err(|_| invalid_field())?;
+        let occurrence = match occ {
+            Some(occ) => OccurrenceMatcher::new(occ)
+                .map_err(|_| invalid_field())?,
+            None => OccurrenceMatcher::None,
+        };
+
+        let missing =
+            || ParsePatchError("missing new value".to_string());
+
+        if op == Operation::Add && old.is_some() {
+            return Err(ParsePatchError(
+                "unexpected old value".to_string(),
+            ));
+        }
+
+        if op == Operation::Remove && new.is_some() {
+            return Err(ParsePatchError(
+                "unexpected new value".to_string(),
+            ));
+        }
+
+        let kind = if let Some(c) = code {
+            let code = SubfieldCode::new(c)
+                .map_err(|e| ParsePatchError(e.to_string()))?;
+            let guard = old.map(parse_value).transpose()?;
+            let value = new.map(parse_value).transpose()?;
+
+            match op {
+                Operation::Add => Kind::AddSubfield(
+                    Subfield::new(c, new.ok_or_else(missing)?)
+                        .map_err(|e| ParsePatchError(e.to_string()))?,
+                ),
+                Operation::Remove => {
+                    Kind::RemoveSubfield { code, guard }
+                }
+                Operation::Replace => Kind::ReplaceSubfield {
+                    value: value.ok_or_else(missing)?,
+                    guard,
+                    code,
+                },
+            }
+        } else {
+            let guard = old.map(parse_subfields).transpose()?;
+            let subfields = new.map(parse_subfields).transpose()?;
+
+            match op {
+                Operation::Add => {
+                    let mut field = Field::new(
+                        name,
+                        occ.map(|occ| &occ[1..]),
+                        Vec::<(char, &str)>::new(),
+                    )
+                    .map_err(|_| invalid_field())?;
+
+                    for subfield in subfields.ok_or_else(missing)? {
+                        field.push(subfield);
+                    }
+
+                    Kind::AddField(field)
+                }
+                Operation::Remove => Kind::RemoveField { guard },
+                Operation::Replace => Kind::ReplaceField {
+                    subfields: subfields.ok_or_else(missing)?,
+                    guard,
+                },
+            }
+        };
+
+        Ok(Self {
+            tag,
+            occurrence,
+            kind,
+        })
+    }
+
+    /// Applies the change to the given record.
+    ///
+    /// If the change can't be applied, the record is left unchanged
+    /// and the reason is returned as a [Conflict]. A change, which
+    /// modifies a field (replacing the field or adding, replacing or
+    /// removing a subfield), must address exactly one field; otherwise
+    /// the change is [ambiguous](Conflict::Ambiguous). Only the removal
+    /// of whole fields affects all matching fields. Fields without any
+    /// remaining subfields are removed as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::patch::{Change, Conflict, Operation};
+    /// use pica_record::primitives::Record;
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "Ada")]),
+    /// ])?;
+    ///
+    /// let change = Change::new(
+    ///     Operation::Replace,
+    ///     "028A",
+    ///     Some('d'),
+    ///     Some("Ada"),
+    ///     Some("Augusta Ada"),
+    /// )?;
+    ///
+    /// assert!(change.apply(&mut record).is_ok());
+    /// assert_eq!(record.fields()[1].subfields()[1].value(), "Augusta Ada");
+    ///
+    /// // The guard no longer matches.
+    /// assert_eq!(change.apply(&mut record), Err(Conflict::GuardMismatch));
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn apply(&self, record: &mut Record) -> Result<(), Conflict> {
+        let candidates: Vec<usize> = record
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| self.is_match(field))
+            .map(|(i, _)| i)
+            .collect();
+
+        match self.kind {
+            Kind::AddField(ref field) => {
+                if candidates
+                    .iter()
+                    .any(|&i| &record.fields()[i] == field)
+                {
+                    return Err(Conflict::FieldExists);
+                }
+
+                record.insert(field.clone());
+            }
+            Kind::RemoveField { ref guard } => {
+                let matches = guarded(record, candidates, guard)?;
+                for i in matches.into_iter().rev() {
+                    record.remove(i);
+                }
+            }
+            Kind::ReplaceField {
+                ref guard,
+                ref subfields,
+            } => {
+                let matches = guarded(record, candidates, guard)?;
+                let [i] = matches[..] else {
+                    return Err(Conflict::Ambiguous(matches.len()));
+                };
+
+                let field = &mut record.fields_mut()[i];
+                field.retain(|_| false);
+                for subfield in subfields {
+                    field.push(subfield.clone());
+                }
+            }
+            Kind::AddSubfield(ref subfield) => {
+                let i = match candidates[..] {
+                    [] => return Err(Conflict::FieldNotFound),
+                    [i] => i,
+                    _ => {
+                        return Err(Conflict::Ambiguous(
+                            candidates.len(),
+                        ));
+                    }
+                };
+
+                let field = &mut record.fields_mut()[i];
+                if field.subfields().contains(subfield) {
+                    return Err(Conflict::SubfieldExists);
+                }
+
+                field.push(subfield.clone());
+            }
+            Kind::RemoveSubfield {
+                ref code,
+                ref guard,
+            } => {
+                let is_target = targets(code, guard.as_ref());
+                let i = target_field(
+                    record,
+                    &candidates,
+                    code,
+                    &is_target,
+                )?;
+
+                let field = &mut record.fields_mut()[i];
+                field.retain(|subfield| !is_target(subfield));
+                if field.subfields().is_empty() {
+                    record.remove(i);
+                }
+            }
+            Kind::ReplaceSubfield {
+                ref code,
+                ref guard,
+                ref value,
+            } => {
+                let is_target = targets(code, guard.as_ref());
+                let i = target_field(
+                    record,
+                    &candidates,
+                    code,
+                    &is_target,
+                )?;
+
+                for subfield in record.fields_mut()[i].subfields_mut() {
+                    if is_target(subfield) {
+                        subfield.set_value(value.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the tag and occurrence of the field match.
+    fn is_match(&self, field: &Field) -> bool {
+        let occ = field.occurrence().map(OccurrenceRef::from);
+        self.tag.is_match(&TagRef::from(field.tag()))
+            && self.occurrence.is_match(occ.as_ref())
+    }
+}
+
+/// Returns the candidates whose subfields are equal to the guard.
+fn guarded(
+    record: &Record,
+    candidates: Vec<usize>,
+    guard: &Option<Vec<Subfield>>,
+) -> Result<Vec<usize>, Conflict> {
+    if candidates.is_empty() {
+        return Err(Conflict::FieldNotFound);
+    }
+
+    let Some(guard) = guard else {
+        return Ok(candidates);
+    };
+
+    let matches: Vec<usize> = candidates
+        .into_iter()
+        .filter(|&i| record.fields()[i].subfields() == guard.as_slice())
+        .collect();
+
+    if matches.is_empty() {
+        return Err(Conflict::GuardMismatch);
+    }
+
+    Ok(matches)
+}
+
+/// Returns a predicate, which selects the subfields with the given
+/// code and (optional) value.
+fn targets<'a>(
+    code: &'a SubfieldCode,
+    guard: Option<&'a SubfieldValue>,
+) -> impl Fn(&Subfield) -> bool + 'a {
+    move |subfield| {
+        subfield.code() == code
+            && guard.is_none_or(|value| subfield.value() == value)
+    }
+}
+
+/// Returns the only candidate, which contains a subfield selected by
+/// the predicate.
+fn target_field(
+    record: &Record,
+    candidates: &[usize],
+    code: &SubfieldCode,
+    is_target: &impl Fn(&Subfield) -> bool,
+) -> Result<usize, Conflict> {
+    if candidates.is_empty() {
+        return Err(Conflict::FieldNotFound);
+    }
+
+    let subfields = |i: usize| record.fields()[i].subfields().iter();
+    let matches: Vec<usize> = candidates
+        .iter()
+        .copied()
+        .filter(|&i| subfields(i).any(is_target))
+        .collect();
+
+    match matches[..] {
+        [i] => Ok(i),
+        [] if candidates
+            .iter()
+            .any(|&i| subfields(i).any(|s| s.code() == code)) =>
+        {
+            Err(Conflict::GuardMismatch)
+        }
+        [] => Err(Conflict::SubfieldNotFound),
+        _ => Err(Conflict::Ambiguous(matches.len())),
+    }
+}
+
+/// Parses a subfield value.
+fn parse_value(value: &str) -> Result<SubfieldValue, ParsePatchError> {
+    SubfieldValue::new(value)
+        .map_err(|e| ParsePatchError(e.to_string()))
+}
+
+/// Parses a list of subfields in PICA-Plain (e.g. `$aX$bY`).
+fn parse_subfields(
+    value: &str,
+) -> Result<Vec<Subfield>, ParsePatchError> {
+    let invalid =
+        || ParsePatchError(format!("invalid subfields '{value}'"));
+
+    let chars: Vec<char> = value.chars().collect();
+    let mut rest = &chars[..];
+    let mut current: Option<(char, String)> = None;
+    let mut subfields = vec![];
+
+    loop {
+        match rest {
+            [] => break,
+            ['$', '$', tail @ ..] => {
+                current.as_mut().ok_or_else(invalid)?.1.push('$');
+                rest = tail;
+            }
+            ['$', code, tail @ ..] => {
+                subfields
+                    .extend(current.replace((*code, String::new())));
+                rest = tail;
+            }
+            ['$'] => return Err(invalid()),
+            [c, tail @ ..] => {
+                current.as_mut().ok_or_else(invalid)?.1.push(*c);
+                rest = tail;
+            }
+        }
+    }
+
+    subfields
+        .into_iter()
+        .chain(current)
+        .map(|(code, value)| {
+            Subfield::new(code, &value).map_err(|_| invalid())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestResult = anyhow::Result<()>;
+
+    fn record() -> Record {
+        Record::new(vec![
+            ("003@", None, vec![('0', "123")]),
+            ("028A", None, vec![('a', "Lovelace"), ('d', "Ada")]),
+            ("044H", None, vec![('a', "x"), ('9', "1")]),
+            ("044H", None, vec![('a', "y"), ('9', "2")]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_subfields() -> TestResult {
+        assert_eq!(
+            parse_subfields("$aUS-$$$bfoo")?,
+            vec![
+                Subfield::new('a', "US-$")?,
+                Subfield::new('b', "foo")?
+            ]
+        );
+
+        assert!(parse_subfields("abc").is_err());
+        assert!(parse_subfields("$").is_err());
+        assert!(parse_subfields("$$a").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_field() -> TestResult {
+        let mut record = record();
+
+        let change = Change::new(
+            Operation::Add,
+            "060R",
+            None,
+            None,
+            Some("$a1815"),
+        )?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(record.fields()[4].tag(), &"060R");
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::FieldExists)
+        );
+
+        let change = Change::new(
+            Operation::Replace,
+            "044H",
+            None,
+            None,
+            Some("$az"),
+        )?;
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::Ambiguous(2))
+        );
+
+        let change = Change::new(
+            Operation::Replace,
+            "044H",
+            None,
+            Some("$ay$92"),
+            Some("$az"),
+        )?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(record.fields()[3].subfields().len(), 1);
+
+        let change = Change::new(
+            Operation::Remove,
+            "044H",
+            None,
+            Some("$ay$92"),
+            None,
+        )?;
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::GuardMismatch)
+        );
+
+        let change =
+            Change::new(Operation::Remove, "044H/*", None, None, None)?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(record.fields().len(), 3);
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::FieldNotFound)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_subfield() -> TestResult {
+        let mut record = record();
+
+        let change = Change::new(
+            Operation::Add,
+            "044H",
+            Some('b'),
+            None,
+            Some("z"),
+        )?;
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::Ambiguous(2))
+        );
+
+        let change = Change::new(
+            Operation::Add,
+            "028A",
+            Some('c'),
+            None,
+            Some("Lady"),
+        )?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::SubfieldExists)
+        );
+
+        let change = Change::new(
+            Operation::Replace,
+            "044H",
+            Some('9'),
+            Some("3"),
+            Some("4"),
+        )?;
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::GuardMismatch)
+        );
+
+        let change = Change::new(
+            Operation::Replace,
+            "044H",
+            Some('9'),
+            None,
+            Some("4"),
+        )?;
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::Ambiguous(2))
+        );
+
+        let change = Change::new(
+            Operation::Replace,
+            "044H",
+            Some('9'),
+            Some("2"),
+            Some("4"),
+        )?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(record.fields()[3].subfields()[1].value(), "4");
+        assert_eq!(record.fields()[2].subfields()[1].value(), "1");
+
+        let change = Change::new(
+            Operation::Remove,
+            "044H",
+            Some('9'),
+            None,
+            None,
+        )?;
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::Ambiguous(2))
+        );
+
+        let change = Change::new(
+            Operation::Remove,
+            "044H",
+            Some('9'),
+            Some("1"),
+            None,
+        )?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(record.fields()[2].subfields().len(), 1);
+        assert_eq!(record.fields()[3].subfields().len(), 2);
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::GuardMismatch)
+        );
+
+        let change = Change::new(
+            Operation::Remove,
+            "044H",
+            Some('a'),
+            Some("x"),
+            None,
+        )?;
+        assert!(change.apply(&mut record).is_ok());
+        assert_eq!(record.fields().len(), 3);
+        assert_eq!(
+            change.apply(&mut record),
+            Err(Conflict::GuardMismatch)
+        );
+
+        Ok(())
+    }
+}
@@ -14,7 +14,10 @@ use crate::matcher::subfield::parser::parse_subfield_matcher;
 use crate::matcher::tag::parse_tag_matcher;
 use crate::matcher::{MatcherOptions, OccurrenceMatcher, TagMatcher};
 use crate::parser::{parse_subfield_codes, ws};
-use crate::primitives::{FieldRef, RecordRef, SubfieldCode};
+use crate::primitives::{
+    Field, FieldRef, OccurrenceRef, Record, RecordRef, SubfieldCode,
+    TagRef,
+};
 
 /// An error that can occur when parsing a path expression.
 #[derive(Debug, thiserror::Error)]
@@ -196,6 +199,76 @@ impl<'de> serde::Deserialize<'de> for Path {
     }
 }
 
+/// An expression, which selects fields by tag, occurrence and an
+/// optional subfield matcher (e.g. a [Path] or a
+/// [Format](crate::prelude::Format)).
+pub(crate) trait FieldSelector {
+    fn tag_matcher(&self) -> &TagMatcher;
+    fn occurrence_matcher(&self) -> &OccurrenceMatcher;
+    fn subfield_matcher(&self) -> Option<&SubfieldMatcher>;
+
+    /// Returns `true` if the field is selected by the expression.
+    fn selects(
+        &self,
+        field: &FieldRef,
+        options: &MatcherOptions,
+    ) -> bool {
+        self.tag_matcher().is_match(field.tag())
+            && self.occurrence_matcher().is_match(field.occurrence())
+            && self.subfield_matcher().is_none_or(|matcher| {
+                matcher.is_match(field.subfields(), options)
+            })
+    }
+
+    /// Returns `true` if the owned field is selected by the
+    /// expression.
+    ///
+    /// The field is converted into a [FieldRef] only if the tag and
+    /// occurrence match and the subfields must be checked.
+    fn selects_owned(
+        &self,
+        field: &Field,
+        options: &MatcherOptions,
+    ) -> bool {
+        self.tag_matcher().is_match(&TagRef::from(field.tag()))
+            && self.occurrence_matcher().is_match(
+                field.occurrence().map(OccurrenceRef::from).as_ref(),
+            )
+            && self.subfield_matcher().is_none_or(|matcher| {
+                matcher.is_match(
+                    FieldRef::from(field).subfields(),
+                    options,
+                )
+            })
+    }
+}
+
+impl FieldSelector for Path {
+    #[inline]
+    fn tag_matcher(&self) -> &TagMatcher {
+        &self.tag_matcher
+    }
+
+    #[inline]
+    fn occurrence_matcher(&self) -> &OccurrenceMatcher {
+        &self.occurrence_matcher
+    }
+
+    #[inline]
+    fn subfield_matcher(&self) -> Option<&SubfieldMatcher> {
+        self.subfield_matcher.as_ref()
+    }
+}
+
+impl Path {
+    /// Returns `true` if the path selects subfields with the given
+    /// code.
+    #[inline]
+    fn selects_code(&self, code: &SubfieldCode) -> bool {
+        self.codes.iter().any(|codes| codes.contains(code))
+    }
+}
+
 pub trait PathExt {
     type Value: ?Sized;
 
@@ -286,31 +359,49 @@ impl PathExt for RecordRef<'_> {
     ) -> impl Iterator<Item = &Self::Value> {
         self.fields()
             .iter()
-            .filter(|field| {
-                let retval = path.tag_matcher.is_match(field.tag())
-                    && path
-                        .occurrence_matcher
-                        .is_match(field.occurrence());
-
-                if let Some(ref matcher) = path.subfield_matcher {
-                    retval
-                        && matcher.is_match(field.subfields(), options)
-                } else {
-                    retval
-                }
-            })
+            .filter(|field| path.selects(field, options))
             .flat_map(FieldRef::subfields)
-            .filter_map(|subfield| {
-                if path
-                    .codes
-                    .iter()
-                    .any(|codes| codes.contains(subfield.code()))
-                {
-                    Some(subfield.value().as_bstr())
-                } else {
-                    None
-                }
-            })
+            .filter(|subfield| path.selects_code(subfield.code()))
+            .map(|subfield| subfield.value().as_bstr())
+    }
+}
+
+impl PathExt for Record {
+    type Value = BStr;
+
+    /// Returns the path values of an owned record as an iterator over
+    /// byte slices.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    /// use pica_record::primitives::Record;
+    ///
+    /// let record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    /// ])?;
+    ///
+    /// let path = Path::new("002@.0")?;
+    /// let values: Vec<_> =
+    ///     record.path(&path, &Default::default()).collect();
+    /// assert_eq!(values, vec!["Tp1"]);
+    /// assert_eq!(record.ppn().unwrap(), "123456789X");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn path(
+        &self,
+        path: &Path,
+        options: &MatcherOptions,
+    ) -> impl Iterator<Item = &Self::Value> {
+        self.fields()
+            .iter()
+            .filter(|field| path.selects_owned(field, options))
+            .flat_map(Field::subfields)
+            .filter(|subfield| path.selects_code(subfield.code()))
+            .map(|subfield| subfield.value().as_bstr())
     }
 }
 
@@ -0,0 +1,216 @@
+use std::io::{self, BufRead, BufWriter, Write};
+
+use bstr::ByteSlice;
+
+use crate::ByteRecord;
+use crate::primitives::ParsePicaError;
+use crate::reader::DecodeError;
+use crate::writer::ByteRecordWrite;
+
+/// A streaming decoder for PICA-Plain.
+///
+/// PICA-Plain is a human-readable serialization, in which each field
+/// is written on a separate line (`TAG[/OCC] $aVALUE$bVALUE`). A `$`
+/// within a subfield value is escaped as `$$`. Records are separated
+/// by one or more empty lines.
+pub(crate) struct PlainDecoder<R: BufRead> {
+    inner: R,
+    line: usize,
+    position: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> PlainDecoder<R> {
+    /// Creates a new decoder from a buffered reader.
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            buf: Vec::new(),
+            position: 0,
+            line: 0,
+            inner,
+        }
+    }
+
+    /// Consumes the decoder and returns the underlying reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the line on which the last decoded record starts.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Decodes the next record and appends the normalized PICA+ data
+    /// to `out`. Returns `false` if there are no further records.
+    pub(crate) fn decode_record(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, DecodeError> {
+        let mut result = Ok(());
+        self.position = 0;
+
+        loop {
+            self.buf.clear();
+            if self.inner.read_until(b'\n', &mut self.buf)? == 0 {
+                break;
+            }
+
+            self.line += 1;
+
+            let line = trim_newline(&self.buf);
+            if line.is_empty() {
+                if self.position > 0 {
+                    break;
+                }
+
+                continue;
+            }
+
+            if self.position == 0 {
+                self.position = self.line;
+            }
+
+            // After an error, the remaining lines of the record are
+            // skipped, so that the decoder can continue with the next
+            // record.
+            if result.is_ok() {
+                result = decode_field(line, out);
+            }
+        }
+
+        if self.position == 0 {
+            return Ok(false);
+        }
+
+        result?;
+        out.push(b'\n');
+        Ok(true)
+    }
+}
+
+/// Decodes a single line into a normalized PICA+ field.
+fn decode_field(
+    line: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), ParsePicaError> {
+    let Some(pos) = line.iter().position(|b| *b == b' ') else {
+        return Err(invalid_field(line, "missing subfields"));
+    };
+
+    let (header, mut rest) = (&line[..pos], &line[pos + 1..]);
+    if !rest.starts_with(b"$") {
+        return Err(invalid_field(line, "missing subfields"));
+    }
+
+    out.extend_from_slice(header);
+    out.push(b' ');
+
+    loop {
+        match rest {
+            [] => break,
+            [b'$', b'$', tail @ ..] => {
+                out.push(b'$');
+                rest = tail;
+            }
+            [b'$', code, tail @ ..] => {
+                out.extend_from_slice(&[b'\x1f', *code]);
+                rest = tail;
+            }
+            [b'$'] => {
+                return Err(invalid_field(
+                    line,
+                    "missing subfield code",
+                ));
+            }
+            [b'\x1e' | b'\x1f', ..] => {
+                return Err(invalid_field(line, "invalid character"));
+            }
+            [b, tail @ ..] => {
+                out.push(*b);
+                rest = tail;
+            }
+        }
+    }
+
+    out.push(b'\x1e');
+    Ok(())
+}
+
+/// Removes a trailing line feed (and carriage return).
+#[inline]
+pub(crate) fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+#[inline]
+fn invalid_field(line: &[u8], reason: &str) -> ParsePicaError {
+    ParsePicaError::new(format!(
+        "invalid field '{}': {reason}",
+        String::from_utf8_lossy(line)
+    ))
+}
+
+/// A [ByteRecord] writer that encodes records as PICA-Plain.
+///
+/// The records are written without a separating empty line, which is
+/// the output of `pica convert --to plain`. In order to read the
+/// records back one by one, they must be separated by an empty line.
+pub struct PlainTextWriter<W: Write>(BufWriter<W>);
+
+impl<W: Write> PlainTextWriter<W> {
+    /// Creates a new [PlainTextWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::PlainTextWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut out = Vec::new();
+    /// let mut writer = PlainTextWriter::new(&mut out);
+    /// let record = ByteRecord::from_bytes(b"021A \x1faUS-$\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// assert_eq!(String::from_utf8(out)?, "021A $aUS-$$\n");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self(BufWriter::new(inner))
+    }
+}
+
+impl<W: Write> ByteRecordWrite for PlainTextWriter<W> {
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        for field in record.fields() {
+            field.tag().write_to(&mut self.0)?;
+            if let Some(occurrence) = field.occurrence() {
+                occurrence.write_to(&mut self.0)?;
+            }
+
+            self.0.write_all(b" ")?;
+
+            for subfield in field.subfields() {
+                self.0.write_all(&[b'$', subfield.code().as_byte()])?;
+                self.0.write_all(
+                    &subfield.value().replace(b"$", b"$$"),
+                )?;
+            }
+
+            self.0.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
@@ -22,7 +22,8 @@ pub use crate::matcher::{
     RecordMatcherBuilder, TagMatcher,
 };
 pub use crate::path::{Path, PathExt};
+pub use crate::primitives::AsRecordRef;
 pub use crate::query::{Query, QueryExt, QueryOptions};
 pub use crate::reader::{ReadPicaError, ReaderBuilder, RecordsIter};
 pub use crate::writer::{ByteRecordWrite, WriterBuilder};
-pub use crate::{ByteRecord, Error, StringRecord};
+pub use crate::{ByteRecord, Error, HashOptions, StringRecord};
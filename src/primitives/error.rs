@@ -1,6 +1,165 @@
+use std::fmt::{self, Display};
+
 use thiserror::Error;
 
+/// The kind of a [ParsePicaError].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParsePicaErrorKind {
+    /// The tag of a field is invalid.
+    InvalidTag,
+    /// The occurrence of a field is invalid.
+    InvalidOccurrence,
+    /// A subfield code is invalid or missing.
+    InvalidSubfieldCode,
+    /// A subfield value contains a field or subfield separator.
+    InvalidSubfieldValue,
+    /// A field isn't terminated by a field separator (`\x1e`).
+    MissingFieldSeparator,
+    /// A newline occurs within a field.
+    UnexpectedNewline,
+    /// A record isn't terminated by a newline.
+    MissingNewline,
+    /// A record exceeds the maximum record size.
+    RecordTooLarge,
+    /// A record can't be decoded unambiguously from the input
+    /// encoding.
+    InvalidEncoding,
+    /// Any other error (e.g. a syntax error of a non-PICA+ format).
+    Other,
+}
+
+impl ParsePicaErrorKind {
+    /// Returns the kind as a (kebab-case) string slice.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::ParsePicaErrorKind;
+    ///
+    /// let kind = ParsePicaErrorKind::MissingFieldSeparator;
+    /// assert_eq!(kind.as_str(), "missing-field-separator");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidTag => "invalid-tag",
+            Self::InvalidOccurrence => "invalid-occurrence",
+            Self::InvalidSubfieldCode => "invalid-subfield-code",
+            Self::InvalidSubfieldValue => "invalid-subfield-value",
+            Self::MissingFieldSeparator => "missing-field-separator",
+            Self::UnexpectedNewline => "unexpected-newline",
+            Self::MissingNewline => "missing-newline",
+            Self::RecordTooLarge => "record-too-large",
+            Self::InvalidEncoding => "invalid-encoding",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl Display for ParsePicaErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// An error that can occur when parsing PICA+ records.
-#[derive(Debug, Error)]
-#[error("{0}")]
-pub struct ParsePicaError(pub(crate) String);
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{}", .0.msg)]
+pub struct ParsePicaError(Box<Inner>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Inner {
+    msg: String,
+    kind: ParsePicaErrorKind,
+    offset: Option<usize>,
+    tag: Option<String>,
+}
+
+impl ParsePicaError {
+    /// Creates a new error of kind [ParsePicaErrorKind::Other].
+    pub(crate) fn new<S: Into<String>>(msg: S) -> Self {
+        Self::with_kind(ParsePicaErrorKind::Other, msg)
+    }
+
+    /// Creates a new error of the given kind.
+    pub(crate) fn with_kind<S: Into<String>>(
+        kind: ParsePicaErrorKind,
+        msg: S,
+    ) -> Self {
+        Self(Box::new(Inner {
+            msg: msg.into(),
+            offset: None,
+            tag: None,
+            kind,
+        }))
+    }
+
+    /// Sets the byte offset of the error.
+    pub(crate) fn with_offset(mut self, offset: usize) -> Self {
+        self.0.offset = Some(offset);
+        self
+    }
+
+    /// Sets the tag of the field, which contains the error.
+    pub(crate) fn with_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.0.tag = Some(tag.into());
+        self
+    }
+
+    /// Returns the kind of the error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{ParsePicaErrorKind, RecordRef};
+    ///
+    /// let err = RecordRef::from_bytes(b"003@ \x1f0123\n").unwrap_err();
+    /// assert_eq!(err.kind(), ParsePicaErrorKind::UnexpectedNewline);
+    /// ```
+    #[inline]
+    pub fn kind(&self) -> ParsePicaErrorKind {
+        self.0.kind
+    }
+
+    /// Returns the byte offset of the error within the parsed data,
+    /// if known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let err =
+    ///     RecordRef::from_bytes(b"003@ \x1f0123\x1e002@ \x1f!\x1e\n")
+    ///         .unwrap_err();
+    /// assert_eq!(err.byte_offset(), Some(17));
+    /// ```
+    #[inline]
+    pub fn byte_offset(&self) -> Option<usize> {
+        self.0.offset
+    }
+
+    /// Returns the tag of the field, which contains the error, if
+    /// known. In case of an invalid tag, the invalid tag is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let err =
+    ///     RecordRef::from_bytes(b"003@ \x1f0123\x1e002@ \x1f!\x1e\n")
+    ///         .unwrap_err();
+    /// assert_eq!(err.field_tag(), Some("002@"));
+    /// ```
+    #[inline]
+    pub fn field_tag(&self) -> Option<&str> {
+        self.0.tag.as_deref()
+    }
+
+    /// Returns the error message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.0.msg
+    }
+}
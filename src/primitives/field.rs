@@ -1,12 +1,14 @@
+use std::cmp::Ordering;
 use std::io::{self, Write};
-use std::iter;
 use std::str::Utf8Error;
+use std::{iter, mem};
 
+use bstr::ByteSlice;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use winnow::Parser;
 
-use super::parse::parse_field_ref;
+use super::parse::{diagnose_field_ref, parse_field_ref};
 use super::{
     Level, Occurrence, OccurrenceRef, ParsePicaError, Subfield,
     SubfieldRef, Tag, TagRef,
@@ -89,9 +91,9 @@ impl<'a> FieldRef<'a> {
     {
         let bytes = field.as_ref();
 
-        parse_field_ref.parse(bytes).map_err(|_| {
-            ParsePicaError(format!("invalid field '{bytes:?}'"))
-        })
+        parse_field_ref
+            .parse(bytes)
+            .map_err(|_| diagnose_field_ref(bytes))
     }
 
     /// Returns a reference to the [TagRef] of the field.
@@ -353,6 +355,279 @@ impl Field {
         })
     }
 
+    /// Returns the tag of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Field;
+    ///
+    /// let field = Field::new("003@", None, vec![('0', "123456789X")])?;
+    /// assert_eq!(field.tag(), &"003@");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn tag(&self) -> &Tag {
+        &self.tag
+    }
+
+    /// Returns the occurrence of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Field;
+    ///
+    /// let field = Field::new("012A", Some("01"), vec![('a', "b")])?;
+    /// assert_eq!(field.occurrence().unwrap(), &"01");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn occurrence(&self) -> Option<&Occurrence> {
+        self.occurrence.as_ref()
+    }
+
+    /// Returns the subfields of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Subfield};
+    ///
+    /// let field = Field::new("012A", None, vec![('a', "b")])?;
+    /// assert_eq!(field.subfields(), [Subfield::new('a', "b")?]);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn subfields(&self) -> &[Subfield] {
+        &self.subfields
+    }
+
+    /// Returns a mutable slice of the subfields of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, SubfieldValue};
+    ///
+    /// let mut field = Field::new("012A", None, vec![('a', "b")])?;
+    /// for subfield in field.subfields_mut() {
+    ///     subfield.set_value(SubfieldValue::new("c")?);
+    /// }
+    ///
+    /// assert_eq!(field, Field::new("012A", None, vec![('a', "c")])?);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn subfields_mut(&mut self) -> &mut [Subfield] {
+        &mut self.subfields
+    }
+
+    /// Returns the level of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Level};
+    ///
+    /// let field = Field::new("203@", Some("01"), vec![('0', "1")])?;
+    /// assert_eq!(field.level(), Level::Copy);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn level(&self) -> Level {
+        self.tag.level()
+    }
+
+    /// Sets the tag of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Tag};
+    ///
+    /// let mut field = Field::new("028A", None, vec![('a', "b")])?;
+    /// field.set_tag(Tag::new("028@")?);
+    /// assert_eq!(field.tag(), &"028@");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn set_tag(&mut self, tag: Tag) {
+        self.tag = tag;
+    }
+
+    /// Sets (or removes) the occurrence of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Occurrence};
+    ///
+    /// let mut field = Field::new("047A", None, vec![('a', "b")])?;
+    /// field.set_occurrence(Some(Occurrence::new("03")?));
+    /// assert_eq!(field.occurrence().unwrap(), &"03");
+    ///
+    /// field.set_occurrence(None);
+    /// assert!(field.occurrence().is_none());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn set_occurrence(&mut self, occurrence: Option<Occurrence>) {
+        self.occurrence = occurrence;
+    }
+
+    /// Appends a subfield to the end of the field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Subfield};
+    ///
+    /// let mut field = Field::new("012A", None, vec![('a', "b")])?;
+    /// field.push(Subfield::new('c', "d")?);
+    ///
+    /// assert_eq!(
+    ///     field,
+    ///     Field::new("012A", None, vec![('a', "b"), ('c', "d")])?
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn push(&mut self, subfield: Subfield) {
+        self.subfields.push(subfield);
+    }
+
+    /// Inserts a subfield at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of subfields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Subfield};
+    ///
+    /// let mut field = Field::new("012A", None, vec![('c', "d")])?;
+    /// field.insert(0, Subfield::new('a', "b")?);
+    ///
+    /// assert_eq!(
+    ///     field,
+    ///     Field::new("012A", None, vec![('a', "b"), ('c', "d")])?
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, index: usize, subfield: Subfield) {
+        self.subfields.insert(index, subfield);
+    }
+
+    /// Removes and returns the subfield at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Subfield};
+    ///
+    /// let mut field =
+    ///     Field::new("012A", None, vec![('a', "b"), ('c', "d")])?;
+    /// assert_eq!(field.remove(0), Subfield::new('a', "b")?);
+    /// assert_eq!(field, Field::new("012A", None, vec![('c', "d")])?);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Subfield {
+        self.subfields.remove(index)
+    }
+
+    /// Replaces the subfield at the given position and returns the
+    /// previous subfield.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Subfield};
+    ///
+    /// let mut field = Field::new("012A", None, vec![('a', "b")])?;
+    /// let old = field.replace(0, Subfield::new('c', "d")?);
+    ///
+    /// assert_eq!(old, Subfield::new('a', "b")?);
+    /// assert_eq!(field, Field::new("012A", None, vec![('c', "d")])?);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn replace(
+        &mut self,
+        index: usize,
+        subfield: Subfield,
+    ) -> Subfield {
+        mem::replace(&mut self.subfields[index], subfield)
+    }
+
+    /// Retains only the subfields specified by the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Field;
+    ///
+    /// let mut field =
+    ///     Field::new("012A", None, vec![('a', "b"), ('c', "d")])?;
+    /// field.retain(|subfield| subfield.code() != 'a');
+    /// assert_eq!(field, Field::new("012A", None, vec![('c', "d")])?);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(&Subfield) -> bool>(&mut self, f: F) {
+        self.subfields.retain(f);
+    }
+
+    /// Compares two fields by their canonical order, i.e. by level,
+    /// tag and (numeric) occurrence. A field without an occurrence
+    /// comes before any field with an occurrence. Fields on the copy
+    /// level are compared by occurrence first, so that the fields of a
+    /// copy stay together.
+    pub(crate) fn canonical_cmp(&self, other: &Field) -> Ordering {
+        let occurrence = |field: &Field| {
+            field.occurrence.as_ref().map(|occ| {
+                let digits =
+                    occ.as_bytes().trim_start_with(|c| c == '0');
+                (digits.len(), digits.to_vec())
+            })
+        };
+
+        let tag = || self.tag.as_bytes().cmp(other.tag.as_bytes());
+        let occ = || occurrence(self).cmp(&occurrence(other));
+
+        match self.level().cmp(&other.level()) {
+            Ordering::Equal if self.level() == Level::Copy => {
+                occ().then_with(tag)
+            }
+            Ordering::Equal => tag().then_with(occ),
+            ordering => ordering,
+        }
+    }
+
     /// Write the [Field] into the given writer.
     ///
     /// # Example
@@ -411,6 +686,35 @@ impl From<FieldRef<'_>> for Field {
     }
 }
 
+impl<'a> From<&'a Field> for FieldRef<'a> {
+    /// Creates a [FieldRef], which borrows from a [Field].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, FieldRef};
+    ///
+    /// let field = Field::new("012A", Some("01"), vec![('a', "b")])?;
+    /// assert_eq!(FieldRef::from(&field), field);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn from(field: &'a Field) -> Self {
+        FieldRef {
+            tag: TagRef::from(&field.tag),
+            occurrence: field
+                .occurrence
+                .as_ref()
+                .map(OccurrenceRef::from),
+            subfields: field
+                .subfields
+                .iter()
+                .map(SubfieldRef::from)
+                .collect(),
+        }
+    }
+}
+
 impl PartialEq<Field> for FieldRef<'_> {
     fn eq(&self, field: &Field) -> bool {
         let occ_eq = match (&self.occurrence, &field.occurrence) {
@@ -0,0 +1,323 @@
+//! Hierarchical views on the local and copy levels of a record.
+//!
+//! A PICA+ record consists of the main data (level 0), followed by
+//! zero or more local data blocks (level 1), each starting with a
+//! `101@` field. Every local data block is followed by zero or more
+//! copies (level 2), each starting with a `203@` field. The fields of
+//! a copy share the same occurrence.
+//!
+//! A new local data block starts with a `101@` field, if the current
+//! block already contains one, or with the first local field after a
+//! copy, if the `101@` field is missing.
+
+use std::iter::Peekable;
+use std::slice::Iter;
+
+use super::{FieldRef, Level, OccurrenceRef};
+
+/// An iterator over the local data blocks of a record.
+///
+/// This struct is created by [RecordRef::locals](super::RecordRef::locals).
+#[derive(Debug, Clone)]
+pub struct Locals<'r, 'a> {
+    iter: Peekable<Iter<'r, FieldRef<'a>>>,
+}
+
+impl<'r, 'a> Locals<'r, 'a> {
+    pub(super) fn new(fields: &'r [FieldRef<'a>]) -> Self {
+        Self {
+            iter: fields.iter().peekable(),
+        }
+    }
+}
+
+impl<'r, 'a> Iterator for Locals<'r, 'a> {
+    type Item = LocalBlock<'r, 'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut fields = vec![];
+        let mut prev = None;
+
+        while let Some(field) = self.iter.peek() {
+            match field.level() {
+                Level::Main => {
+                    self.iter.next();
+                }
+                Level::Local
+                    if prev == Some(Level::Copy)
+                        || (prev.is_some()
+                            && field.tag() == "101@") =>
+                {
+                    break;
+                }
+                level => {
+                    prev = Some(level);
+                    fields.extend(self.iter.next());
+                }
+            }
+        }
+
+        if fields.is_empty() {
+            None
+        } else {
+            Some(LocalBlock { fields })
+        }
+    }
+}
+
+/// A local data block (level 1) of a record, including its copies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalBlock<'r, 'a> {
+    fields: Vec<&'r FieldRef<'a>>,
+}
+
+impl<'r, 'a> LocalBlock<'r, 'a> {
+    /// Returns all fields of the local data block, including the
+    /// fields of its copies, in the order of the record.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    /// ])?;
+    ///
+    /// let local = record.locals().next().unwrap();
+    /// assert_eq!(local.fields().len(), 2);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn fields(&self) -> &[&'r FieldRef<'a>] {
+        &self.fields
+    }
+
+    /// Returns an iterator over the fields of the local data block,
+    /// which are on the local level (level 1).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    /// ])?;
+    ///
+    /// let local = record.locals().next().unwrap();
+    /// let mut iter = local.local_fields();
+    /// assert_eq!(iter.next().unwrap().tag(), "101@");
+    /// assert!(iter.next().is_none());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn local_fields(
+        &self,
+    ) -> impl Iterator<Item = &'r FieldRef<'a>> + '_ {
+        self.fields
+            .iter()
+            .copied()
+            .filter(|field| field.level() == Level::Local)
+    }
+
+    /// Returns an iterator over the copies (level 2) of the local data
+    /// block.
+    ///
+    /// Consecutive copy fields with the same occurrence belong to the
+    /// same copy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    ///     ("209A", Some("01"), vec![('a', "ABC")]),
+    ///     ("203@", Some("02"), vec![('0', "5678")]),
+    /// ])?;
+    ///
+    /// let local = record.locals().next().unwrap();
+    /// let copies: Vec<_> = local.copies().collect();
+    /// assert_eq!(copies.len(), 2);
+    /// assert_eq!(copies[0].fields().len(), 2);
+    /// assert_eq!(*copies[1].occurrence().unwrap(), "02");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn copies(&self) -> impl Iterator<Item = CopyBlock<'r, 'a>> {
+        let fields: Vec<&'r FieldRef<'a>> = self
+            .fields
+            .iter()
+            .copied()
+            .filter(|field| field.level() == Level::Copy)
+            .collect();
+
+        fields
+            .chunk_by(|a, b| a.occurrence() == b.occurrence())
+            .map(|fields| CopyBlock {
+                fields: fields.to_vec(),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// A copy (level 2) of a local data block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyBlock<'r, 'a> {
+    fields: Vec<&'r FieldRef<'a>>,
+}
+
+impl<'r, 'a> CopyBlock<'r, 'a> {
+    /// Returns the fields of the copy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    ///     ("209A", Some("01"), vec![('a', "ABC")]),
+    /// ])?;
+    ///
+    /// let local = record.locals().next().unwrap();
+    /// let copy = local.copies().next().unwrap();
+    /// assert_eq!(copy.fields()[1].tag(), "209A");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn fields(&self) -> &[&'r FieldRef<'a>] {
+        &self.fields
+    }
+
+    /// Returns the occurrence of the copy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    /// ])?;
+    ///
+    /// let local = record.locals().next().unwrap();
+    /// let copy = local.copies().next().unwrap();
+    /// assert_eq!(*copy.occurrence().unwrap(), "01");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn occurrence(&self) -> Option<&'r OccurrenceRef<'a>> {
+        self.fields.first().copied().and_then(FieldRef::occurrence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::RecordRef;
+
+    type TestResult = anyhow::Result<()>;
+
+    fn tags<'a>(fields: &[&FieldRef<'a>]) -> Vec<String> {
+        fields.iter().map(|field| field.tag().to_string()).collect()
+    }
+
+    #[test]
+    fn test_locals() -> TestResult {
+        let record = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123456789X")]),
+            ("101@", None, vec![('a', "1")]),
+            ("144Z", None, vec![('a', "x")]),
+            ("203@", Some("01"), vec![('0', "1")]),
+            ("101@", None, vec![('a', "2")]),
+            ("203@", Some("01"), vec![('0', "2")]),
+            ("209A", Some("01"), vec![('a', "y")]),
+        ])?;
+
+        let locals: Vec<_> = record.locals().collect();
+        assert_eq!(locals.len(), 2);
+        assert_eq!(tags(locals[0].fields()), ["101@", "144Z", "203@"]);
+        assert_eq!(tags(locals[1].fields()), ["101@", "203@", "209A"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_locals_without_copies() -> TestResult {
+        let record = RecordRef::new(vec![
+            ("003@", None, vec![('0', "123456789X")]),
+            ("101@", None, vec![('a', "1")]),
+            ("144Z", None, vec![('a', "x")]),
+            ("101@", None, vec![('a', "2")]),
+            ("101@", None, vec![('a', "3")]),
+            ("203@", Some("01"), vec![('0', "3")]),
+        ])?;
+
+        let locals: Vec<_> = record.locals().collect();
+        assert_eq!(locals.len(), 3);
+        assert_eq!(tags(locals[0].fields()), ["101@", "144Z"]);
+        assert_eq!(tags(locals[1].fields()), ["101@"]);
+        assert_eq!(tags(locals[2].fields()), ["101@", "203@"]);
+        assert_eq!(locals[0].copies().count(), 0);
+        assert_eq!(locals[1].copies().count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_locals_missing_101() -> TestResult {
+        let record = RecordRef::new(vec![
+            ("101@", None, vec![('a', "1")]),
+            ("203@", Some("01"), vec![('0', "1")]),
+            ("144Z", None, vec![('a', "x")]),
+            ("203@", Some("01"), vec![('0', "2")]),
+        ])?;
+
+        let locals: Vec<_> = record.locals().collect();
+        assert_eq!(locals.len(), 2);
+        assert_eq!(tags(locals[1].fields()), ["144Z", "203@"]);
+        assert!(
+            RecordRef::new(vec![("003@", None, vec![('0', "1")])])?
+                .locals()
+                .next()
+                .is_none()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_copies() -> TestResult {
+        let record = RecordRef::new(vec![
+            ("101@", None, vec![('a', "1")]),
+            ("144Z", None, vec![('a', "y")]),
+            ("203@", Some("01"), vec![('0', "1")]),
+            ("209A", Some("01"), vec![('a', "x")]),
+            ("203@", Some("02"), vec![('0', "2")]),
+        ])?;
+
+        let local = record.locals().next().unwrap();
+        assert_eq!(
+            tags(&local.local_fields().collect::<Vec<_>>()),
+            ["101@", "144Z"]
+        );
+
+        let copies: Vec<_> = local.copies().collect();
+        assert_eq!(copies.len(), 2);
+        assert_eq!(tags(copies[0].fields()), ["203@", "209A"]);
+        assert_eq!(*copies[1].occurrence().unwrap(), "02");
+        Ok(())
+    }
+}
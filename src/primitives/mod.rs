@@ -1,9 +1,10 @@
 //! Low-level primitives to work with (normalized) PICA+ records.
 
-pub use error::ParsePicaError;
+pub use error::{ParsePicaError, ParsePicaErrorKind};
 pub use field::{Field, FieldRef};
+pub use holdings::{CopyBlock, LocalBlock, Locals};
 pub use occurrence::{Occurrence, OccurrenceRef};
-pub use record::{Record, RecordRef};
+pub use record::{AsRecordRef, Record, RecordBuilder, RecordRef};
 pub use subfield::{
     Subfield, SubfieldCode, SubfieldRef, SubfieldValue,
     SubfieldValueRef,
@@ -12,6 +13,7 @@ pub use tag::{Level, Tag, TagRef};
 
 mod error;
 mod field;
+mod holdings;
 mod occurrence;
 pub(crate) mod parse;
 mod record;
@@ -6,8 +6,8 @@ use bstr::{BStr, BString};
 use serde::{Deserialize, Serialize};
 use winnow::Parser;
 
-use super::ParsePicaError;
 use super::parse::parse_occurrence_ref;
+use super::{ParsePicaError, ParsePicaErrorKind};
 
 /// An immutable occurrence.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
@@ -84,7 +84,10 @@ impl<'a> OccurrenceRef<'a> {
         let bytes = occurrence.as_ref();
 
         parse_occurrence_ref.parse(bytes).map_err(|_| {
-            ParsePicaError(format!("invalid occurrence '{bytes:?}'"))
+            ParsePicaError::with_kind(
+                ParsePicaErrorKind::InvalidOccurrence,
+                format!("invalid occurrence '{bytes:?}'"),
+            )
         })
     }
 
@@ -264,6 +267,26 @@ impl From<OccurrenceRef<'_>> for Occurrence {
     }
 }
 
+impl<'a> From<&'a Occurrence> for OccurrenceRef<'a> {
+    /// Creates an [OccurrenceRef], which borrows from an
+    /// [Occurrence].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Occurrence, OccurrenceRef};
+    ///
+    /// let occ = Occurrence::new("001")?;
+    /// assert_eq!(OccurrenceRef::from(&occ), "001");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    fn from(occurrence: &'a Occurrence) -> Self {
+        Self::from_unchecked(occurrence.as_bytes())
+    }
+}
+
 impl PartialEq<&str> for Occurrence {
     /// Compares a [Occurrence] with a string slice.
     ///
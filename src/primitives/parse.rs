@@ -4,8 +4,8 @@ use winnow::stream::AsChar;
 use winnow::token::{one_of, take_till, take_while};
 
 use super::{
-    FieldRef, OccurrenceRef, RecordRef, SubfieldCode, SubfieldRef,
-    SubfieldValueRef, TagRef,
+    FieldRef, OccurrenceRef, ParsePicaError, ParsePicaErrorKind,
+    RecordRef, SubfieldCode, SubfieldRef, SubfieldValueRef, TagRef,
 };
 
 /// Parses a [SubfieldCode] from a byte slice.
@@ -91,6 +91,202 @@ pub(crate) fn parse_record_ref<'a>(
         .parse_next(i)
 }
 
+/// Locates the first error of an invalid record.
+///
+/// This function is called only after [parse_record_ref] has failed,
+/// in order to determine the kind and the byte offset of the error.
+pub(crate) fn diagnose_record(bytes: &[u8]) -> ParsePicaError {
+    use ParsePicaErrorKind::*;
+
+    let mut pos = 0;
+    loop {
+        match bytes.get(pos) {
+            None if pos == 0 => {
+                return ParsePicaError::with_kind(
+                    MissingNewline,
+                    "empty record",
+                )
+                .with_offset(0);
+            }
+            None => {
+                return ParsePicaError::with_kind(
+                    MissingNewline,
+                    format!("missing newline at byte offset {pos}"),
+                )
+                .with_offset(pos);
+            }
+            Some(b'\n') if pos == 0 => {
+                return ParsePicaError::with_kind(
+                    UnexpectedNewline,
+                    "unexpected newline at byte offset 0",
+                )
+                .with_offset(0);
+            }
+            Some(b'\n') if pos + 1 < bytes.len() => {
+                return ParsePicaError::new(format!(
+                    "unexpected data after newline at byte offset {pos}",
+                ))
+                .with_offset(pos + 1);
+            }
+            Some(b'\n') => {
+                return ParsePicaError::new("invalid record");
+            }
+            Some(_) => match diagnose_field(bytes, pos) {
+                Ok(end) => pos = end,
+                Err(e) => return e,
+            },
+        }
+    }
+}
+
+/// Locates the first error of an invalid field.
+pub(crate) fn diagnose_field_ref(bytes: &[u8]) -> ParsePicaError {
+    match diagnose_field(bytes, 0) {
+        Ok(end) if end < bytes.len() => ParsePicaError::new(format!(
+            "unexpected data after field at byte offset {end}"
+        ))
+        .with_offset(end),
+        Ok(_) => ParsePicaError::new("invalid field"),
+        Err(e) => e,
+    }
+}
+
+/// Checks the field starting at `start` and returns the position after
+/// the field separator.
+fn diagnose_field(
+    bytes: &[u8],
+    start: usize,
+) -> Result<usize, ParsePicaError> {
+    use ParsePicaErrorKind::*;
+
+    let error = |kind, msg: String, pos: usize, tag: &[u8]| {
+        ParsePicaError::with_kind(
+            kind,
+            format!("{msg} at byte offset {pos}"),
+        )
+        .with_offset(pos)
+        .with_tag(String::from_utf8_lossy(tag))
+    };
+
+    let end = bytes[start..]
+        .iter()
+        .position(|b| {
+            matches!(b, b' ' | b'/' | b'\x1e' | b'\x1f' | b'\n')
+        })
+        .map_or(bytes.len(), |n| start + n);
+
+    let tag = &bytes[start..end];
+    if parse_tag_ref.parse(tag).is_err() {
+        let msg =
+            format!("invalid tag '{}'", String::from_utf8_lossy(tag));
+        return Err(error(InvalidTag, msg, start, tag));
+    }
+
+    let mut pos = end;
+    if bytes.get(pos) == Some(&b'/') {
+        let end = bytes[pos + 1..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |n| pos + 1 + n);
+
+        let occurrence = &bytes[pos + 1..end];
+        if parse_occurrence_ref.parse(occurrence).is_err() {
+            let msg = format!(
+                "invalid occurrence '{}'",
+                String::from_utf8_lossy(occurrence)
+            );
+
+            return Err(error(InvalidOccurrence, msg, pos + 1, tag));
+        }
+
+        pos = end;
+    }
+
+    match bytes.get(pos) {
+        Some(b' ') => pos += 1,
+        Some(b'\n') => {
+            let msg = "unexpected newline".into();
+            return Err(error(UnexpectedNewline, msg, pos, tag));
+        }
+        Some(b) => {
+            let msg =
+                format!("unexpected character '{}'", b.escape_ascii());
+            return Err(error(Other, msg, pos, tag));
+        }
+        None => {
+            let msg = "missing field separator".into();
+            return Err(error(MissingFieldSeparator, msg, pos, tag));
+        }
+    }
+
+    loop {
+        match bytes.get(pos) {
+            Some(b'\x1e') => return Ok(pos + 1),
+            Some(b'\x1f') => {
+                let code = bytes.get(pos + 1);
+                if !code.is_some_and(u8::is_ascii_alphanumeric) {
+                    let msg = match code {
+                        Some(code) => format!(
+                            "invalid subfield code '{}'",
+                            code.escape_ascii()
+                        ),
+                        None => "missing subfield code".into(),
+                    };
+
+                    return Err(error(
+                        InvalidSubfieldCode,
+                        msg,
+                        pos + 1,
+                        tag,
+                    ));
+                }
+
+                pos += 2;
+                while let Some(b) = bytes.get(pos) {
+                    if matches!(b, b'\x1e' | b'\x1f') {
+                        break;
+                    }
+
+                    pos += 1;
+                }
+
+                // A subfield value may contain a newline, but a
+                // newline at the end of the data means that the field
+                // was split by a newline.
+                if pos == bytes.len() && bytes.ends_with(b"\n") {
+                    let msg = "unexpected newline".into();
+                    return Err(error(
+                        UnexpectedNewline,
+                        msg,
+                        pos - 1,
+                        tag,
+                    ));
+                }
+            }
+            Some(b'\n') => {
+                let msg = "unexpected newline".into();
+                return Err(error(UnexpectedNewline, msg, pos, tag));
+            }
+            Some(b) => {
+                let msg = format!(
+                    "unexpected character '{}'",
+                    b.escape_ascii()
+                );
+                return Err(error(Other, msg, pos, tag));
+            }
+            None => {
+                let msg = "missing field separator".into();
+                return Err(error(
+                    MissingFieldSeparator,
+                    msg,
+                    pos,
+                    tag,
+                ));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bstr::{ByteSlice, ByteVec};
@@ -195,4 +391,64 @@ mod tests {
 
         assert_eq!(parse_record_ref.parse(&bytes).unwrap(), record);
     }
+
+    #[test]
+    fn test_diagnose_record() {
+        use ParsePicaErrorKind::*;
+
+        macro_rules! diagnose {
+            ($input:expr, $kind:expr, $offset:expr, $tag:expr) => {
+                let input: &[u8] = $input;
+                assert!(parse_record_ref.parse(input).is_err());
+
+                let err = diagnose_record(input);
+                assert_eq!(err.kind(), $kind);
+                assert_eq!(err.byte_offset(), Some($offset));
+                assert_eq!(err.field_tag(), $tag);
+            };
+        }
+
+        diagnose!(b"003! \x1f0123\x1e\n", InvalidTag, 0, Some("003!"));
+        diagnose!(
+            b"003@ \x1f0123\x1e00A@ \x1f0123\x1e\n",
+            InvalidTag,
+            11,
+            Some("00A@")
+        );
+        diagnose!(
+            b"003@/0 \x1f0123\x1e\n",
+            InvalidOccurrence,
+            5,
+            Some("003@")
+        );
+        diagnose!(
+            b"003@ \x1f!123\x1e\n",
+            InvalidSubfieldCode,
+            6,
+            Some("003@")
+        );
+        diagnose!(
+            b"003@ \x1f0123\x1f\x1e\n",
+            InvalidSubfieldCode,
+            11,
+            Some("003@")
+        );
+        diagnose!(
+            b"003@ \x1f0123",
+            MissingFieldSeparator,
+            10,
+            Some("003@")
+        );
+        diagnose!(
+            b"003@ \x1f0123\n",
+            UnexpectedNewline,
+            10,
+            Some("003@")
+        );
+        diagnose!(b"003@ \n", UnexpectedNewline, 5, Some("003@"));
+        diagnose!(b"\n", UnexpectedNewline, 0, None);
+        diagnose!(b"003@ \x1f0123\x1e", MissingNewline, 11, None);
+        diagnose!(b"", MissingNewline, 0, None);
+        diagnose!(b"003@ 0123\x1e\n", Other, 5, Some("003@"));
+    }
 }
@@ -1,12 +1,18 @@
+use std::borrow::Cow;
 use std::io::{self, Write};
+use std::mem;
 use std::str::Utf8Error;
 
+use bstr::ByteSlice;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use winnow::Parser;
 
-use super::parse::parse_record_ref;
-use super::{Field, FieldRef, ParsePicaError};
+use super::holdings::Locals;
+use super::parse::{diagnose_record, parse_record_ref};
+use super::{Field, FieldRef, Level, ParsePicaError, SubfieldValue};
+use crate::HashOptions;
 
 /// An immutable PICA+ record.
 #[derive(Debug, PartialEq, Clone)]
@@ -72,9 +78,9 @@ impl<'a> RecordRef<'a> {
     {
         let bytes = record.as_ref();
 
-        parse_record_ref.parse(bytes).map_err(|_| {
-            ParsePicaError(format!("invalid record: '{bytes:?}'"))
-        })
+        parse_record_ref
+            .parse(bytes)
+            .map_err(|_| diagnose_record(bytes))
     }
 
     /// Returns the fields of the record.
@@ -95,6 +101,62 @@ impl<'a> RecordRef<'a> {
         &self.0
     }
 
+    /// Returns an iterator over the fields of the main data (level 0).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    /// ])?;
+    ///
+    /// let mut iter = record.main();
+    /// assert_eq!(iter.next().unwrap().tag(), "003@");
+    /// assert!(iter.next().is_none());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn main(&self) -> impl Iterator<Item = &FieldRef<'a>> {
+        self.0.iter().filter(|field| field.level() == Level::Main)
+    }
+
+    /// Returns an iterator over the local data blocks (level 1) of
+    /// the record.
+    ///
+    /// A local data block contains all local and copy fields up to the
+    /// next local field, which follows a copy field. Fields of the main
+    /// data are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordRef;
+    ///
+    /// let record = RecordRef::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    ///     ("101@", None, vec![('a', "2")]),
+    ///     ("203@", Some("01"), vec![('0', "5678")]),
+    ///     ("203@", Some("02"), vec![('0', "9012")]),
+    /// ])?;
+    ///
+    /// let locals: Vec<_> = record.locals().collect();
+    /// assert_eq!(locals.len(), 2);
+    /// assert_eq!(locals[0].copies().count(), 1);
+    /// assert_eq!(locals[1].copies().count(), 2);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn locals(&self) -> Locals<'_, 'a> {
+        Locals::new(&self.0)
+    }
+
     /// Returns `true` if the [RecordRef] contains no fields, otherwise
     /// `false`.
     ///
@@ -194,11 +256,442 @@ impl<'a> RecordRef<'a> {
 }
 
 /// A mutable record.
-#[derive(Debug, Clone)]
+///
+/// A record is modified through its fields (see [Field] and
+/// [Subfield](super::Subfield)), which are valid by construction. A
+/// modified record can be written via [Record::write_to] or converted
+/// into a [RecordRef], which borrows from the record.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Record(Vec<Field>);
 
 impl Record {
+    /// Creates a new [Record].
+    ///
+    /// # Errors
+    ///
+    /// This function fails if either the tag, occcurrence or any
+    /// subfield is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Record;
+    ///
+    /// let record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    /// ])?;
+    ///
+    /// assert_eq!(record.fields().len(), 2);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new<'a, T>(
+        fields: Vec<(&str, Option<&str>, T)>,
+    ) -> Result<Self, ParsePicaError>
+    where
+        T: IntoIterator<Item = (char, &'a str)>,
+    {
+        let fields = fields
+            .into_iter()
+            .map(|(tag, occ, subfields)| {
+                Field::new(tag, occ, subfields)
+            })
+            .collect::<Result<Vec<Field>, _>>()?;
+
+        Ok(Self(fields))
+    }
+
+    /// Returns the fields of the record.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Record};
+    ///
+    /// let record = Record::new(vec![("003@", None, vec![('0', "1")])])?;
+    /// assert_eq!(record.fields(), [Field::new("003@", None, vec![('0', "1")])?]);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn fields(&self) -> &[Field] {
+        &self.0
+    }
+
+    /// Returns a mutable slice of the fields of the record.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Record, Subfield};
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("028A", None, vec![('a', "Lovelace")]),
+    /// ])?;
+    ///
+    /// for field in record.fields_mut() {
+    ///     if field.tag() == &"028A" {
+    ///         field.push(Subfield::new('d', "Ada")?);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(record.fields()[1].subfields().len(), 2);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn fields_mut(&mut self) -> &mut [Field] {
+        &mut self.0
+    }
+
+    /// Returns `true` if the record contains no fields, otherwise
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Record;
+    ///
+    /// assert!(Record::default().is_empty());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Appends a field to the end of the record.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Record};
+    ///
+    /// let mut record = Record::default();
+    /// record.push(Field::new("003@", None, vec![('0', "1")])?);
+    /// assert_eq!(record.fields().len(), 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn push(&mut self, field: Field) {
+        self.0.push(field);
+    }
+
+    /// Inserts a field at its canonical position.
+    ///
+    /// The field is inserted into the last run of consecutive fields
+    /// of the same level (i.e. the main data, the last local data
+    /// block or the last copy), after all fields of this run whose tag
+    /// and occurrence are less than or equal to the tag and occurrence
+    /// of the new field. If the record has no fields of this level, a
+    /// main-level field is inserted at the beginning and any other
+    /// field is appended to the end of the record. In order to start
+    /// a new local data block or copy, use [Record::push].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Record};
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("028A", None, vec![('a', "Lovelace")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("203@", Some("01"), vec![('0', "0123")]),
+    /// ])?;
+    ///
+    /// record.insert(Field::new("008A", None, vec![('a', "s")])?);
+    /// record.insert(Field::new("144Z", None, vec![('a', "x")])?);
+    ///
+    /// let tags = record
+    ///     .fields()
+    ///     .iter()
+    ///     .map(|field| field.tag().to_string())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(tags, ["003@", "008A", "028A", "101@", "144Z", "203@"]);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn insert(&mut self, field: Field) {
+        let level = field.level();
+        let end = self
+            .0
+            .iter()
+            .rposition(|f| f.level() == level)
+            .map(|i| i + 1);
+
+        let pos = match end {
+            None if level == Level::Main => 0,
+            None => self.0.len(),
+            Some(end) => {
+                let start = self.0[..end]
+                    .iter()
+                    .rposition(|f| f.level() != level)
+                    .map_or(0, |i| i + 1);
+
+                start
+                    + self.0[start..end].partition_point(|f| {
+                        f.canonical_cmp(&field).is_le()
+                    })
+            }
+        };
+
+        self.0.insert(pos, field);
+    }
+
+    /// Removes and returns the field at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Record};
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    /// ])?;
+    ///
+    /// assert_eq!(
+    ///     record.remove(1),
+    ///     Field::new("002@", None, vec![('0', "Tp1")])?
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Field {
+        self.0.remove(index)
+    }
+
+    /// Replaces the field at the given position and returns the
+    /// previous field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Field, Record};
+    ///
+    /// let mut record = Record::new(vec![("002@", None, vec![('0', "Tp1")])])?;
+    /// let old = record.replace(0, Field::new("002@", None, vec![('0', "Tpz")])?);
+    ///
+    /// assert_eq!(old, Field::new("002@", None, vec![('0', "Tp1")])?);
+    /// assert_eq!(record.fields()[0].subfields()[0].value(), "Tpz");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn replace(&mut self, index: usize, field: Field) -> Field {
+        mem::replace(&mut self.0[index], field)
+    }
+
+    /// Retains only the fields specified by the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Record;
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    /// ])?;
+    ///
+    /// record.retain(|field| field.tag() == &"003@");
+    /// assert_eq!(record.fields().len(), 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn retain<F: FnMut(&Field) -> bool>(&mut self, f: F) {
+        self.0.retain(f);
+    }
+
+    /// Removes leading and trailing whitespace from all subfield
+    /// values. Afterwards, subfields with an empty value and fields
+    /// without subfields are removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Record;
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("003@", None, vec![('0', " 123456789X ")]),
+    ///     ("012A", None, vec![('a', "  ")]),
+    ///     ("028A", None, vec![('a', "Lovelace"), ('d', "")]),
+    /// ])?;
+    ///
+    /// record.trim();
+    ///
+    /// assert_eq!(
+    ///     record,
+    ///     Record::new(vec![
+    ///         ("003@", None, vec![('0', "123456789X")]),
+    ///         ("028A", None, vec![('a', "Lovelace")]),
+    ///     ])?
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn trim(&mut self) {
+        for field in self.0.iter_mut() {
+            for subfield in field.subfields_mut() {
+                let value = subfield.value().trim();
+                if value.len() != subfield.value().len() {
+                    let value = SubfieldValue::from_unchecked(value);
+                    subfield.set_value(value);
+                }
+            }
+
+            field.retain(|subfield| !subfield.value().is_empty());
+        }
+
+        self.0.retain(|field| !field.subfields().is_empty());
+    }
+
+    /// Sorts the fields of the record into canonical order.
+    ///
+    /// The fields of the main data are sorted by tag and (numeric)
+    /// occurrence and moved to the beginning of the record. The local
+    /// data blocks keep their order; within a block, the local fields
+    /// are sorted by tag, followed by the copies sorted by occurrence
+    /// and the fields of each copy sorted by tag. The sort is stable,
+    /// i.e. repeated fields keep their relative order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::Record;
+    ///
+    /// let mut record = Record::new(vec![
+    ///     ("028A", None, vec![('a', "Lovelace")]),
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("101@", None, vec![('a', "1")]),
+    ///     ("209A", Some("02"), vec![('a', "DEF")]),
+    ///     ("203@", Some("02"), vec![('0', "5678")]),
+    ///     ("203@", Some("01"), vec![('0', "1234")]),
+    /// ])?;
+    ///
+    /// record.sort();
+    ///
+    /// assert_eq!(
+    ///     record,
+    ///     Record::new(vec![
+    ///         ("003@", None, vec![('0', "123456789X")]),
+    ///         ("028A", None, vec![('a', "Lovelace")]),
+    ///         ("101@", None, vec![('a', "1")]),
+    ///         ("203@", Some("01"), vec![('0', "1234")]),
+    ///         ("203@", Some("02"), vec![('0', "5678")]),
+    ///         ("209A", Some("02"), vec![('a', "DEF")]),
+    ///     ])?
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sort(&mut self) {
+        let (mut main, rest): (Vec<_>, Vec<_>) = mem::take(&mut self.0)
+            .into_iter()
+            .partition(|field| field.level() == Level::Main);
+
+        main.sort_by(Field::canonical_cmp);
+        self.0 = main;
+
+        let mut block: Vec<Field> = vec![];
+        let mut prev = None;
+
+        for field in rest {
+            let level = field.level();
+            if level == Level::Local
+                && (prev == Some(Level::Copy)
+                    || (*field.tag() == "101@"
+                        && block.iter().any(|f| *f.tag() == "101@")))
+            {
+                block.sort_by(Field::canonical_cmp);
+                self.0.append(&mut block);
+            }
+
+            prev = Some(level);
+            block.push(field);
+        }
+
+        block.sort_by(Field::canonical_cmp);
+        self.0.append(&mut block);
+    }
+
+    /// Returns the SHA-256 hash of the record.
+    ///
+    /// The hash is computed over the record in normalized PICA+,
+    /// including the trailing newline, which is the same hash as
+    /// [ByteRecord::sha256](crate::ByteRecord::sha256).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::ByteRecord;
+    /// use pica_record::primitives::{Record, RecordRef};
+    ///
+    /// let data = b"012A \x1fa123\x1e\n";
+    /// let record = Record::from(RecordRef::from_bytes(data)?);
+    /// assert_eq!(record.sha256(), ByteRecord::from_bytes(data)?.sha256());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sha256(&self) -> Vec<u8> {
+        let mut data = Vec::<u8>::new();
+        let _ = self.write_to(&mut data);
+
+        Sha256::digest(data).to_vec()
+    }
+
+    /// Returns the SHA-256 hash of the record, which is computed
+    /// according to the given [HashOptions].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    /// use pica_record::primitives::Record;
+    ///
+    /// let a = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123")]),
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    /// ])?;
+    ///
+    /// let b = Record::new(vec![
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    ///     ("003@", None, vec![('0', "123")]),
+    /// ])?;
+    ///
+    /// let options = HashOptions::new().canonical(true);
+    /// assert_ne!(a.sha256(), b.sha256());
+    /// assert_eq!(a.sha256_with(&options), b.sha256_with(&options));
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sha256_with(&self, options: &HashOptions) -> Vec<u8> {
+        if options.is_identity() {
+            return self.sha256();
+        }
+
+        let mut record = self.clone();
+        options.apply(&mut record);
+        record.sha256()
+    }
+
     /// Write the record into the given writer.
     ///
     /// # Example
@@ -254,6 +747,198 @@ impl From<RecordRef<'_>> for Record {
     }
 }
 
+/// A record, which can be viewed as a [RecordRef].
+///
+/// This trait abstracts over borrowed records ([RecordRef],
+/// [ByteRecord](crate::ByteRecord) and
+/// [StringRecord](crate::StringRecord)) and owned records ([Record]),
+/// so that matchers, paths, queries and formats can be applied to
+/// both kinds of records.
+///
+/// # Example
+///
+/// ```rust
+/// use pica_record::matcher::{MatcherOptions, RecordMatcher};
+/// use pica_record::primitives::{AsRecordRef, Record, RecordRef};
+///
+/// fn is_person<R: AsRecordRef>(record: &R) -> bool {
+///     let matcher = RecordMatcher::new("002@.0 =^ 'Tp'").unwrap();
+///     matcher.is_match(record, &MatcherOptions::default())
+/// }
+///
+/// let record = RecordRef::from_bytes(b"002@ \x1f0Tp1\x1e\n")?;
+/// assert!(is_person(&record));
+/// assert!(is_person(&Record::from(record)));
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait AsRecordRef {
+    /// Returns the record as a [RecordRef].
+    ///
+    /// A borrowed record is returned as is. For an owned record, a
+    /// [RecordRef] is created, which borrows the data from the record.
+    fn as_record_ref(&self) -> Cow<'_, RecordRef<'_>>;
+}
+
+impl<T: AsRecordRef + ?Sized> AsRecordRef for &T {
+    #[inline]
+    fn as_record_ref(&self) -> Cow<'_, RecordRef<'_>> {
+        (**self).as_record_ref()
+    }
+}
+
+impl AsRecordRef for RecordRef<'_> {
+    #[inline]
+    fn as_record_ref(&self) -> Cow<'_, RecordRef<'_>> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl AsRecordRef for Record {
+    #[inline]
+    fn as_record_ref(&self) -> Cow<'_, RecordRef<'_>> {
+        Cow::Owned(RecordRef::from(self))
+    }
+}
+
+impl<'a> From<&'a Record> for RecordRef<'a> {
+    /// Creates a [RecordRef], which borrows from a [Record].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Record, RecordRef};
+    /// use pica_record::prelude::*;
+    ///
+    /// let record = Record::new(vec![("003@", None, vec![('0', "1")])])?;
+    /// let record = ByteRecord::from(RecordRef::from(&record));
+    /// assert_eq!(record.ppn().unwrap(), "1");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn from(record: &'a Record) -> Self {
+        Self(record.0.iter().map(FieldRef::from).collect())
+    }
+}
+
+/// A builder for [Record]s.
+///
+/// # Example
+///
+/// ```rust
+/// use pica_record::primitives::{Field, RecordBuilder};
+///
+/// let record = RecordBuilder::new()
+///     .field("003@", None, [('0', "123456789X")])
+///     .field("012A", Some("01"), [('a', "1"), ('b', "2")])
+///     .push(Field::new("028A", None, [('a', "Lovelace")])?)
+///     .build()?;
+///
+/// assert_eq!(record.fields().len(), 3);
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct RecordBuilder {
+    fields: Vec<Field>,
+    err: Option<ParsePicaError>,
+}
+
+impl RecordBuilder {
+    /// Creates a new [RecordBuilder] without any fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a new field to the record.
+    ///
+    /// If the tag, occurrence or any subfield is invalid, the error
+    /// is reported by [RecordBuilder::build].
+    pub fn field<'a, T>(
+        mut self,
+        tag: &str,
+        occ: Option<&str>,
+        subfields: T,
+    ) -> Self
+    where
+        T: IntoIterator<Item = (char, &'a str)>,
+    {
+        if self.err.is_none() {
+            match Field::new(tag, occ, subfields) {
+                Ok(field) => self.fields.push(field),
+                Err(e) => self.err = Some(e),
+            }
+        }
+
+        self
+    }
+
+    /// Appends an existing field to the record.
+    pub fn push(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Builds the record.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a field passed to
+    /// [RecordBuilder::field] is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::RecordBuilder;
+    ///
+    /// let result = RecordBuilder::new()
+    ///     .field("003@", None, [('0', "123456789X")])
+    ///     .field("0A", None, [('a', "b")])
+    ///     .build();
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn build(self) -> Result<Record, ParsePicaError> {
+        match self.err {
+            Some(e) => Err(e),
+            None => Ok(Record(self.fields)),
+        }
+    }
+}
+
+/// Creates a [Record](crate::primitives::Record) from a list of
+/// fields.
+///
+/// Each field is given as a tuple of tag, optional occurrence and a
+/// list of subfields (see [Record::new]). This macro is intended to be
+/// used in tests and examples.
+///
+/// # Panics
+///
+/// Panics if any field is invalid.
+///
+/// # Example
+///
+/// ```rust
+/// use pica_record::record;
+///
+/// let record = record![
+///     ("003@", None, [('0', "123456789X")]),
+///     ("012A", Some("01"), [('a', "1"), ('b', "2")]),
+/// ];
+///
+/// assert_eq!(record.fields().len(), 2);
+/// ```
+#[macro_export]
+macro_rules! record {
+    ($(($tag:expr, $occ:expr, $subfields:expr)),* $(,)?) => {
+        $crate::primitives::RecordBuilder::new()
+            $(.field($tag, $occ, $subfields))*
+            .build()
+            .expect("valid record")
+    };
+}
+
 impl PartialEq<Record> for RecordRef<'_> {
     fn eq(&self, other: &Record) -> bool {
         self.0 == other.0
@@ -276,3 +961,101 @@ impl quickcheck::Arbitrary for Record {
         Self(fields)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_ref_from_record() -> Result<(), ParsePicaError> {
+        let data = b"003@ \x1f0123456789X\x1e012A/01 \x1fab\x1fc\x1e\n";
+        let record = Record::from(RecordRef::from_bytes(data)?);
+
+        let mut buf = vec![];
+        RecordRef::from(&record).write_to(&mut buf).unwrap();
+        assert_eq!(buf, data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_local_blocks() -> Result<(), ParsePicaError> {
+        let mut record = Record::new(vec![
+            ("101@", None, vec![('a', "2")]),
+            ("144Z", None, vec![('a', "x")]),
+            ("101@", None, vec![('a', "1")]),
+        ])?;
+
+        let expected = record.clone();
+        record.sort();
+        assert_eq!(record, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_canonical() -> Result<(), ParsePicaError> {
+        let mut record = Record::new(vec![
+            ("144Z", None, vec![('a', "2")]),
+            ("101@", None, vec![('a', "1")]),
+            ("047A", Some("10"), vec![('a', "y")]),
+            ("203@", Some("01"), vec![('0', "1")]),
+            ("003@", None, vec![('0', "1")]),
+            ("047A", Some("02"), vec![('a', "z")]),
+            ("101@", None, vec![('a', "2")]),
+            ("209A", Some("02"), vec![('a', "4")]),
+            ("203@", Some("02"), vec![('0', "3")]),
+            ("209A", Some("01"), vec![('a', "5")]),
+            ("203@", Some("01"), vec![('0', "6")]),
+        ])?;
+
+        record.sort();
+
+        let expected = Record::new(vec![
+            ("003@", None, vec![('0', "1")]),
+            ("047A", Some("02"), vec![('a', "z")]),
+            ("047A", Some("10"), vec![('a', "y")]),
+            ("101@", None, vec![('a', "1")]),
+            ("144Z", None, vec![('a', "2")]),
+            ("203@", Some("01"), vec![('0', "1")]),
+            ("101@", None, vec![('a', "2")]),
+            ("203@", Some("01"), vec![('0', "6")]),
+            ("209A", Some("01"), vec![('a', "5")]),
+            ("203@", Some("02"), vec![('0', "3")]),
+            ("209A", Some("02"), vec![('a', "4")]),
+        ])?;
+
+        assert_eq!(record, expected);
+
+        record.sort();
+        assert_eq!(record, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_canonical() -> Result<(), ParsePicaError> {
+        let mut record = Record::default();
+        record.push(Field::new("101@", None, [('a', "1")])?);
+        record.push(Field::new("203@", Some("01"), [('0', "1")])?);
+        record.insert(Field::new("209A", Some("01"), [('a', "2")])?);
+        record.insert(Field::new("144Z", None, [('a', "3")])?);
+        record.insert(Field::new("028A", None, [('a', "x")])?);
+        record.insert(Field::new("003@", None, [('0', "1")])?);
+        record.insert(Field::new("047A", Some("10"), [('a', "y")])?);
+        record.insert(Field::new("047A", Some("02"), [('a', "z")])?);
+        record.insert(Field::new("047A", None, [('a', "w")])?);
+
+        let expected = Record::new(vec![
+            ("003@", None, [('0', "1")]),
+            ("028A", None, [('a', "x")]),
+            ("047A", None, [('a', "w")]),
+            ("047A", Some("02"), [('a', "z")]),
+            ("047A", Some("10"), [('a', "y")]),
+            ("101@", None, [('a', "1")]),
+            ("144Z", None, [('a', "3")]),
+            ("203@", Some("01"), [('0', "1")]),
+            ("209A", Some("01"), [('a', "2")]),
+        ])?;
+
+        assert_eq!(record, expected);
+        Ok(())
+    }
+}
@@ -9,8 +9,8 @@ use bstr::{BStr, BString};
 use serde::{Deserialize, Serialize};
 use winnow::Parser;
 
-use super::ParsePicaError;
 use super::parse::{parse_subfield_ref, parse_subfield_value_ref};
+use super::{ParsePicaError, ParsePicaErrorKind};
 
 /// A subfield code.
 ///
@@ -40,9 +40,10 @@ impl SubfieldCode {
     /// ```
     pub fn new(code: char) -> Result<Self, ParsePicaError> {
         if !code.is_ascii_alphanumeric() {
-            return Err(ParsePicaError(format!(
-                "'{code}' is not a valid subfield code"
-            )));
+            return Err(ParsePicaError::with_kind(
+                ParsePicaErrorKind::InvalidSubfieldCode,
+                format!("'{code}' is not a valid subfield code"),
+            ));
         };
 
         Ok(Self(code))
@@ -185,9 +186,10 @@ impl<'a> SubfieldValueRef<'a> {
     ) -> Result<Self, ParsePicaError> {
         let value = value.as_ref();
         if value.contains('\x1f') || value.contains('\x1e') {
-            return Err(ParsePicaError(format!(
-                "invalid subfield value '{value}'"
-            )));
+            return Err(ParsePicaError::with_kind(
+                ParsePicaErrorKind::InvalidSubfieldValue,
+                format!("invalid subfield value '{value}'"),
+            ));
         }
 
         Ok(Self(value.into()))
@@ -237,9 +239,10 @@ impl<'a> SubfieldValueRef<'a> {
         let bytes = bytes.as_ref();
 
         parse_subfield_value_ref.parse(bytes).map_err(|_| {
-            ParsePicaError(format!(
-                "invalid subfield value '{bytes:?}'"
-            ))
+            ParsePicaError::with_kind(
+                ParsePicaErrorKind::InvalidSubfieldValue,
+                format!("invalid subfield value '{bytes:?}'"),
+            )
         })
     }
 
@@ -552,7 +555,7 @@ impl<'a> SubfieldRef<'a> {
         let bytes = bytes.as_ref();
 
         parse_subfield_ref.parse(bytes).map_err(|_| {
-            ParsePicaError(format!("invalid subfield {bytes:?}"))
+            ParsePicaError::new(format!("invalid subfield {bytes:?}"))
         })
     }
 
@@ -726,6 +729,42 @@ impl Subfield {
         &self.1
     }
 
+    /// Sets the code of the subfield.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Subfield, SubfieldCode};
+    ///
+    /// let mut subfield = Subfield::new('a', "abc")?;
+    /// subfield.set_code(SubfieldCode::new('b')?);
+    /// assert_eq!(subfield.code(), 'b');
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn set_code(&mut self, code: SubfieldCode) {
+        self.0 = code;
+    }
+
+    /// Sets the value of the subfield.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Subfield, SubfieldValue};
+    ///
+    /// let mut subfield = Subfield::new('a', "abc")?;
+    /// subfield.set_value(SubfieldValue::new("def")?);
+    /// assert_eq!(subfield.value(), "def");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn set_value(&mut self, value: SubfieldValue) {
+        self.1 = value;
+    }
+
     /// Write the subfield into the given writer.
     ///
     /// # Example
@@ -775,6 +814,28 @@ impl From<SubfieldRef<'_>> for Subfield {
     }
 }
 
+impl<'a> From<&'a Subfield> for SubfieldRef<'a> {
+    /// Creates a [SubfieldRef], which borrows from a [Subfield].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Subfield, SubfieldRef};
+    ///
+    /// let subfield = Subfield::new('0', "123456789X")?;
+    /// assert_eq!(SubfieldRef::from(&subfield), subfield);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    fn from(subfield: &'a Subfield) -> Self {
+        Self(
+            subfield.0.clone(),
+            SubfieldValueRef::from_unchecked(subfield.1.as_bytes()),
+        )
+    }
+}
+
 impl PartialEq<Subfield> for SubfieldRef<'_> {
     /// Compare a [SubfieldRef] with a [Subfield].
     ///
@@ -8,11 +8,13 @@ use bstr::{BStr, BString};
 use serde::{Deserialize, Serialize};
 use winnow::Parser;
 
-use super::ParsePicaError;
 use super::parse::parse_tag_ref;
+use super::{ParsePicaError, ParsePicaErrorKind};
 
 /// The level (main, local, copy) of a field (or tag).
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Level {
     #[default]
@@ -107,7 +109,10 @@ impl<'a> TagRef<'a> {
         let bytes = tag.as_ref();
 
         parse_tag_ref.parse(bytes).map_err(|_| {
-            ParsePicaError(format!("invalid tag '{bytes:?}'"))
+            ParsePicaError::with_kind(
+                ParsePicaErrorKind::InvalidTag,
+                format!("invalid tag '{bytes:?}'"),
+            )
         })
     }
 
@@ -270,6 +275,24 @@ impl Tag {
         self.0.as_ref()
     }
 
+    /// Returns the level of the tag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Level, Tag};
+    ///
+    /// assert_eq!(Tag::new("003@")?.level(), Level::Main);
+    /// assert_eq!(Tag::new("101@")?.level(), Level::Local);
+    /// assert_eq!(Tag::new("203@")?.level(), Level::Copy);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn level(&self) -> Level {
+        TagRef::from_unchecked(self.as_bytes()).level()
+    }
+
     /// Write the [Tag] into the given writer.
     ///
     /// # Example
@@ -313,6 +336,25 @@ impl From<TagRef<'_>> for Tag {
     }
 }
 
+impl<'a> From<&'a Tag> for TagRef<'a> {
+    /// Creates a [TagRef], which borrows from a [Tag].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::primitives::{Tag, TagRef};
+    ///
+    /// let tag = Tag::new("003@")?;
+    /// assert_eq!(TagRef::from(&tag), "003@");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    fn from(tag: &'a Tag) -> Self {
+        Self::from_unchecked(tag.as_bytes())
+    }
+}
+
 impl PartialEq<&str> for Tag {
     /// Compares a [Tag] with a string slice.
     ///
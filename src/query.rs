@@ -10,7 +10,7 @@ use crate::fmt::{Format, FormatExt, FormatOptions, parse_format};
 use crate::matcher::MatcherOptions;
 use crate::parser::{parse_string, ws};
 use crate::path::{Path, parse_path};
-use crate::primitives::RecordRef;
+use crate::primitives::{AsRecordRef, Record, RecordRef};
 
 /// An error that can occur when parsing a query expression.
 #[derive(thiserror::Error, Debug, Clone, PartialEq)]
@@ -463,6 +463,38 @@ impl QueryExt for RecordRef<'_> {
     }
 }
 
+impl QueryExt for Record {
+    type Value = BString;
+
+    /// Run the query against the owned [Record] and return the
+    /// corresponding [Outcome].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    /// use pica_record::primitives::Record;
+    ///
+    /// let record = Record::new(vec![
+    ///     ("003@", None, vec![('0', "123456789X")]),
+    ///     ("002@", None, vec![('0', "Tp1")]),
+    /// ])?;
+    ///
+    /// let query = Query::new("003@.0, 002@.0")?;
+    /// let outcome = record.query(&query, &Default::default());
+    /// assert_eq!(outcome.into_inner(), vec![vec!["123456789X", "Tp1"]]);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn query(
+        &self,
+        query: &Query,
+        options: &QueryOptions,
+    ) -> Outcome<Self::Value> {
+        self.as_record_ref().query(query, options)
+    }
+}
+
 impl QueryExt for StringRecord<'_> {
     type Value = String;
 
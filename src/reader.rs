@@ -1,19 +1,45 @@
 use std::ffi::OsStr;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Read, stdin};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, stdin};
 use std::path::Path;
 use std::str::Utf8Error;
 
-use flate2::read::GzDecoder;
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use liblzma::read::XzDecoder;
 
+use crate::archive::{MultiReader, Sources};
+use crate::bgzf::BgzfReader;
+use crate::binary::BinaryDecoder;
+use crate::encoding::Encoding;
+use crate::import::ImportDecoder;
+use crate::json::JsonDecoder;
+use crate::mmap::MmapReader;
+use crate::plain::PlainDecoder;
 use crate::primitives::ParsePicaError;
-use crate::{ByteRecord, StringRecord};
+use crate::repair::{ParseMode, Policy, Repairs, is_blank};
+use crate::writer::Compression;
+use crate::xml::XmlDecoder;
+use crate::{ByteRecord, Provenance, StringRecord};
 
 /// An error that can occur when reading records.
 #[derive(thiserror::Error, Debug)]
 pub enum ReadPicaError {
     #[error("parse error: {msg}")]
-    Parse { msg: String, err: ParsePicaError },
+    Parse {
+        /// A human-readable description of the error.
+        msg: String,
+        /// The underlying error, which contains the kind, the tag and
+        /// the byte offset of the error within the record.
+        err: ParsePicaError,
+        /// The name of the input (e.g. the filename), if known.
+        input: Option<String>,
+        /// The (1-based) number of the record within the input, if
+        /// known.
+        record: Option<usize>,
+        /// The line on which the record starts, if known.
+        line: Option<usize>,
+    },
     #[error("parse erorr: {msg}")]
     Utf8 { msg: String, err: Utf8Error },
     #[error(transparent)]
@@ -21,6 +47,27 @@ pub enum ReadPicaError {
 }
 
 impl ReadPicaError {
+    /// Creates a new parse error for the record on the given line.
+    pub(crate) fn parse(
+        source: Option<&str>,
+        line: usize,
+        err: ParsePicaError,
+    ) -> Self {
+        let msg = if let Some(src) = source {
+            format!("invalid record on line {line} ({src}): {err}.")
+        } else {
+            format!("invalid record on line {line}: {err}.")
+        };
+
+        Self::Parse {
+            input: source.map(ToString::to_string),
+            record: Some(line),
+            line: Some(line),
+            msg,
+            err,
+        }
+    }
+
     /// Returns true if the error variant is a parse error and the
     /// `skip_invalid` flag is true.
     #[inline(always)]
@@ -29,9 +76,128 @@ impl ReadPicaError {
     }
 }
 
+/// The capacity of the read buffer. The beginning of the input (up to
+/// this size) is used to detect the input format (see
+/// [ReaderBuilder::detect_format]).
+pub(crate) const CAPACITY: usize = 64 * 1024;
+
+/// The format of the input data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Normalized PICA+ (one record per line).
+    #[default]
+    Plus,
+    /// PICA-XML (`info:srw/schema/5/picaXML-v1.0`).
+    Xml,
+    /// PICA-JSON (an array of records or JSON Lines).
+    Json,
+    /// PICA-Plain (`TAG[/OCC] $aVALUE`).
+    Plain,
+    /// The PICA import format.
+    Import,
+    /// Binary PICA.
+    Binary,
+}
+
+impl InputFormat {
+    /// Returns the input format derived from the extension of the
+    /// given path (`.xml`, `.json`, `.jsonl`, `.plain` or `.import`).
+    /// The extension of a compression format is skipped. If the
+    /// extension is unknown, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::InputFormat;
+    ///
+    /// assert_eq!(
+    ///     InputFormat::from_path("dump.xml.gz"),
+    ///     Some(InputFormat::Xml)
+    /// );
+    /// assert_eq!(
+    ///     InputFormat::from_path("dump.jsonl"),
+    ///     Some(InputFormat::Json)
+    /// );
+    /// assert_eq!(InputFormat::from_path("dump.dat.gz"), None);
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let mut path = path.as_ref();
+        if Compression::from_path(path) != Compression::None {
+            path = Path::new(path.file_stem()?);
+        }
+
+        match path.extension().and_then(OsStr::to_str) {
+            Some("xml") => Some(Self::Xml),
+            Some("json" | "jsonl" | "ndjson") => Some(Self::Json),
+            Some("plain") => Some(Self::Plain),
+            Some("import") => Some(Self::Import),
+            _ => None,
+        }
+    }
+
+    /// Returns the input format derived from the beginning of the
+    /// given data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::InputFormat;
+    ///
+    /// assert_eq!(InputFormat::from_magic(b"<?xml"), InputFormat::Xml);
+    /// assert_eq!(
+    ///     InputFormat::from_magic(b"[[[\"003@\""),
+    ///     InputFormat::Json
+    /// );
+    /// assert_eq!(
+    ///     InputFormat::from_magic(b"003@ $0123"),
+    ///     InputFormat::Plain
+    /// );
+    /// assert_eq!(InputFormat::from_magic(b"\x1d\n"), InputFormat::Import);
+    /// assert_eq!(
+    ///     InputFormat::from_magic(b"003@ \x1f0123\x1e\x1d"),
+    ///     InputFormat::Binary
+    /// );
+    /// assert_eq!(
+    ///     InputFormat::from_magic(b"003@ \x1f0123\x1e\n"),
+    ///     InputFormat::Plus
+    /// );
+    /// ```
+    pub fn from_magic(data: &[u8]) -> Self {
+        let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+        match data.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'<') => return Self::Xml,
+            Some(b'[') => return Self::Json,
+            Some(b'\x1d' | b'\x1e') => return Self::Import,
+            _ => {}
+        }
+
+        if data.starts_with(b"'\x1d") {
+            return Self::Import;
+        }
+
+        let line = match data.iter().position(|b| *b == b'\n') {
+            Some(pos) => &data[..pos],
+            None => data,
+        };
+
+        if line.contains(&b'\x1d') {
+            Self::Binary
+        } else if !line.contains(&b'\x1f') && line.contains(&b'$') {
+            Self::Plain
+        } else {
+            Self::Plus
+        }
+    }
+}
+
 /// Configures and builda a PICA+ reader.
-#[derive(Debug, Default)]
-pub struct ReaderBuilder();
+#[derive(Debug, Default, Clone)]
+pub struct ReaderBuilder {
+    pub(crate) format: Option<InputFormat>,
+    pub(crate) detect_format: bool,
+    pub(crate) policy: Policy,
+    pub(crate) glob: Option<String>,
+}
 
 impl ReaderBuilder {
     /// Creates a new [ReaderBuilder].
@@ -54,6 +220,229 @@ impl ReaderBuilder {
         Self::default()
     }
 
+    /// Sets the format of the input data.
+    ///
+    /// If no format is set, the format is derived from the extension
+    /// of the source (see [InputFormat::from_path]). Inputs without a
+    /// known extension are read as normalized PICA+, unless format
+    /// detection is enabled (see [ReaderBuilder::detect_format]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::InputFormat;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut reader = ReaderBuilder::new()
+    ///     .format(InputFormat::Xml)
+    ///     .from_path("tests/data/ada.xml")?;
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "119232022");
+    ///
+    /// let mut reader = ReaderBuilder::new()
+    ///     .format(InputFormat::Json)
+    ///     .from_path("tests/data/ada.json")?;
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "119232022");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn format(mut self, format: InputFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Whether to detect the format of the input data or not.
+    ///
+    /// When this flag is set and neither a format is set explicitly
+    /// nor the extension of the source is known, the format is
+    /// detected from the beginning of the (decompressed) input (see
+    /// [InputFormat::from_magic]). This flag is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ $0123456789X\n");
+    /// let mut reader = ReaderBuilder::new()
+    ///     .detect_format(true)
+    ///     .from_reader(data, None);
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "123456789X");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn detect_format(mut self, yes: bool) -> Self {
+        self.detect_format = yes;
+        self
+    }
+
+    /// Sets the parsing mode.
+    ///
+    /// In [ParseMode::Strict] (the default), every defect of a record
+    /// results in an error. In [ParseMode::Lenient], recoverable
+    /// defects (e.g. CRLF line endings or a missing newline) are
+    /// repaired on the fly and counted (see [Reader::repairs]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::io::ParseMode;
+    /// use pica_record::prelude::*;
+    ///
+    /// let data =
+    ///     Cursor::new(b"003@ \x1f0123\x1e\r\n\n012A \x1e003@ \x1f0456");
+    /// let mut reader = ReaderBuilder::new()
+    ///     .mode(ParseMode::Lenient)
+    ///     .from_reader(data, None);
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "123");
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "456");
+    /// assert_eq!(record.fields().len(), 1);
+    ///
+    /// assert!(reader.next_byte_record().is_none());
+    /// assert_eq!(reader.repairs().total(), 5);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.policy.mode = mode;
+        self
+    }
+
+    /// Sets the maximum size of a record in bytes (including the
+    /// trailing newline).
+    ///
+    /// A record that exceeds the maximum size results in a parse error
+    /// and the remaining bytes of the record are skipped, so that
+    /// reading can continue with the next record. By default, the size
+    /// of a record is not limited.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::prelude::*;
+    ///
+    /// let data =
+    ///     Cursor::new(b"003@ \x1f0123456789X\x1e\n003@ \x1f01\x1e\n");
+    /// let mut reader = ReaderBuilder::new()
+    ///     .max_record_size(10)
+    ///     .from_reader(data, None);
+    ///
+    /// assert!(reader.next_byte_record().unwrap().is_err());
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "1");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn max_record_size(mut self, size: usize) -> Self {
+        self.policy.max_record_size = Some(size);
+        self
+    }
+
+    /// Whether to remove subfields with an empty value or not.
+    ///
+    /// This flag can be used in both parsing modes and is disabled by
+    /// default. In [ParseMode::Lenient], a field that contains only
+    /// empty subfields is removed as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ \x1f0123\x1fa\x1e\n");
+    /// let mut reader = ReaderBuilder::new()
+    ///     .drop_empty_subfields(true)
+    ///     .from_reader(data, None);
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.fields()[0].subfields().len(), 1);
+    /// assert_eq!(reader.repairs().empty_subfields, 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn drop_empty_subfields(mut self, yes: bool) -> Self {
+        self.policy.drop_empty_subfields = yes;
+        self
+    }
+
+    /// Sets the character encoding of the input data.
+    ///
+    /// Records of a legacy encoding are transcoded to UTF-8 before
+    /// they are parsed; a record that can't be decoded unambiguously
+    /// results in a parse error of kind
+    /// [InvalidEncoding](crate::primitives::ParsePicaErrorKind::InvalidEncoding)
+    /// (see [Encoding]). The encoding is applied to the decoded
+    /// records of all input formats, but JSON and XML input must be
+    /// valid UTF-8 anyway. By default, the input is expected to be
+    /// UTF-8 encoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::io::Encoding;
+    /// use pica_record::prelude::*;
+    ///
+    /// let data =
+    ///     Cursor::new(b"003@ \x1f0123\x1e028A \x1fdJ\xfcrgen\x1e\n");
+    /// let mut reader = ReaderBuilder::new()
+    ///     .encoding(Encoding::Latin1)
+    ///     .from_reader(data, None);
+    ///
+    /// let record = reader.next_string_record().unwrap()?;
+    /// let subfield = &record.fields()[1].subfields()[0];
+    /// assert_eq!(subfield.value(), "Jürgen");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.policy.encoding = encoding;
+        self
+    }
+
+    /// Sets a glob pattern, which is used to select the files of a
+    /// directory or the members of an archive (see [Sources]).
+    ///
+    /// The pattern is matched against the path relative to the
+    /// directory resp. the name of the archive member; a `*` matches
+    /// a path separator as well. Files, which are given explicitly,
+    /// are always read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let sources = ReaderBuilder::new()
+    ///     .glob("*.dat.gz")
+    ///     .sources("tests/data/archive.tar")?;
+    /// assert_eq!(sources.count(), 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn glob<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.glob = Some(pattern.into());
+        self
+    }
+
     /// Creates a new [ReaderBuilder] from an existing reader.
     ///
     /// # Example
@@ -92,6 +481,8 @@ impl ReaderBuilder {
     /// let reader = ReaderBuilder::new().from_path("-")?;
     /// let reader =
     ///     ReaderBuilder::new().from_path("tests/data/DUMP.dat.gz")?;
+    /// let reader =
+    ///     ReaderBuilder::new().from_path("tests/data/DUMP.dat.zst")?;
     ///
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
@@ -101,52 +492,603 @@ impl ReaderBuilder {
     ) -> io::Result<Reader<Box<dyn Read>>> {
         let path = path.as_ref();
         let source = path.to_str().map(ToString::to_string);
-        let reader: Box<dyn Read> = match path
-            .extension()
-            .and_then(OsStr::to_str)
-        {
-            Some("gz") => Box::new(GzDecoder::new(File::open(path)?)),
-            _ => {
-                if path.to_str() != Some("-") {
-                    Box::new(File::open(path)?)
-                } else {
-                    Box::new(stdin().lock())
-                }
-            }
-        };
+        let reader = open(path, 1)?;
 
         Ok(self.from_reader(reader, source))
     }
+
+    /// Creates an iterator over the inputs of a path, which yields a
+    /// [Reader] for each input.
+    ///
+    /// The path can be a single file (or "-" for stdin), a directory,
+    /// which is traversed recursively, or a tar or zip archive (see
+    /// [Sources]).
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the glob pattern is invalid or if the
+    /// directory can't be traversed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// for reader in
+    ///     ReaderBuilder::new().sources("tests/data/archive.zip")?
+    /// {
+    ///     let reader = reader?;
+    ///     assert!(reader.source().unwrap().contains("archive.zip!/"));
+    /// }
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sources<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<Sources> {
+        Sources::new(self, path.as_ref())
+    }
+
+    /// Creates a new reader, which reads the records of all inputs of
+    /// a path one after another (see [ReaderBuilder::sources]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut reader = ReaderBuilder::new()
+    ///     .glob("*.dat*")
+    ///     .from_sources("tests/data/archive.tar.gz")?;
+    ///
+    /// let mut count = 0;
+    /// while let Some(result) = reader.next_byte_record() {
+    ///     let _record = result?;
+    ///     count += 1;
+    /// }
+    ///
+    /// assert_eq!(count, 2);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_sources<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<MultiReader> {
+        Ok(MultiReader::new(self.sources(path)?))
+    }
+
+    /// Creates a new memory-mapped reader from a path.
+    ///
+    /// # Note
+    ///
+    /// Only uncompressed files can be memory-mapped. It's undefined
+    /// behavior if the file is modified while the reader exists.
+    ///
+    /// The records of a memory-mapped reader borrow from the mapping
+    /// and can't be rewritten. Therefore, it's an error to use a
+    /// parsing mode, a maximum record size, the removal of empty
+    /// subfields or an encoding other than the defaults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ParseMode;
+    /// use pica_record::prelude::*;
+    ///
+    /// let reader =
+    ///     ReaderBuilder::new().from_path_mmap("tests/data/ada.dat")?;
+    /// assert_eq!(reader.records().count(), 1);
+    ///
+    /// assert!(ReaderBuilder::new().from_path_mmap("-").is_err());
+    /// assert!(
+    ///     ReaderBuilder::new()
+    ///         .mode(ParseMode::Lenient)
+    ///         .from_path_mmap("tests/data/ada.dat")
+    ///         .is_err()
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_path_mmap<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> io::Result<MmapReader> {
+        if self.policy != Policy::default() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "unable to memory-map with a non-default parsing policy",
+            ));
+        }
+
+        MmapReader::from_path(path.as_ref())
+    }
+}
+
+/// Opens the given path for reading.
+///
+/// A path equal to "-" means reading from stdin. Compressed data is
+/// decoded transparently; the compression format is derived from the
+/// file extension or, if the extension is unknown, from the magic
+/// number at the beginning of the data. BGZF compressed data is
+/// decompressed on the given number of threads.
+pub(crate) fn open(
+    path: &Path,
+    threads: usize,
+) -> io::Result<Box<dyn Read>> {
+    let reader: Box<dyn Read> = if path.to_str() != Some("-") {
+        Box::new(File::open(path)?)
+    } else {
+        Box::new(stdin().lock())
+    };
+
+    decompress(reader, path, threads)
+}
+
+/// Wraps the reader into a decoder, if the data is compressed.
+///
+/// The compression format is derived from the extension of the given
+/// path or, if the extension is unknown, from the magic number at the
+/// beginning of the data.
+pub(crate) fn decompress(
+    reader: Box<dyn Read>,
+    path: &Path,
+    threads: usize,
+) -> io::Result<Box<dyn Read>> {
+    match Compression::from_path(path) {
+        Compression::None => {
+            let mut reader = BufReader::with_capacity(CAPACITY, reader);
+            let compression =
+                Compression::from_magic(reader.fill_buf()?);
+            decode(reader, compression, threads)
+        }
+        compression => decode(reader, compression, threads),
+    }
+}
+
+/// Reads the next line of a PICA+ input and returns the number of
+/// bytes consumed.
+///
+/// If a limit is given, at most `limit + 1` bytes are stored in `buf`;
+/// the remaining bytes of an over-long line are skipped.
+fn read_line<R: BufRead>(
+    inner: &mut R,
+    buf: &mut Vec<u8>,
+    limit: Option<usize>,
+) -> io::Result<usize> {
+    let Some(limit) = limit else {
+        return inner.read_until(b'\n', buf);
+    };
+
+    let mut n = inner
+        .by_ref()
+        .take(limit as u64 + 1)
+        .read_until(b'\n', buf)?;
+
+    if buf.len() > limit && buf.last() != Some(&b'\n') {
+        n += inner.skip_until(b'\n')?;
+    }
+
+    Ok(n)
+}
+
+/// Wraps the reader into a decoder of the given compression format.
+fn decode<R: Read + 'static>(
+    reader: R,
+    compression: Compression,
+    threads: usize,
+) -> io::Result<Box<dyn Read>> {
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        Compression::Bgzf => {
+            Box::new(BgzfReader::new(reader).threads(threads))
+        }
+        Compression::Zstd => Box::new(zstd::Decoder::new(reader)?),
+        Compression::Xz => {
+            Box::new(XzDecoder::new_multi_decoder(reader))
+        }
+        Compression::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+    })
 }
 
 pub struct Reader<R: Read> {
-    inner: BufReader<R>,
+    inner: Decoder<R>,
     source: Option<String>,
     line: usize,
+    index: usize,
+    offset: u64,
+    position: u64,
+    policy: Policy,
+    repairs: Repairs,
+    scratch: Vec<u8>,
     buf: Vec<u8>,
 }
 
+/// The decoder of a [Reader], which depends on the input format.
+enum Decoder<R: Read> {
+    Plus(BufReader<R>),
+    Xml(Box<XmlDecoder<BufReader<R>>>),
+    Json(Box<JsonDecoder<BufReader<R>>>),
+    Plain(PlainDecoder<BufReader<R>>),
+    Import(ImportDecoder<BufReader<R>>),
+    Binary(BinaryDecoder<BufReader<R>>),
+}
+
+/// An error that can occur when decoding a record of a non-PICA+
+/// input format.
+#[derive(Debug)]
+pub(crate) enum DecodeError {
+    /// A (fatal) error, after which no further records can be read.
+    IO(io::Error),
+    /// The current record is invalid, but the decoder can continue
+    /// with the next record.
+    Parse(ParsePicaError),
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        Self::IO(err)
+    }
+}
+
+impl From<ParsePicaError> for DecodeError {
+    fn from(err: ParsePicaError) -> Self {
+        Self::Parse(err)
+    }
+}
+
 impl<R: Read> Reader<R> {
     /// Creates a new [Reader].
     pub(crate) fn new(
-        _builder: &ReaderBuilder,
+        builder: &ReaderBuilder,
         reader: R,
         source: Option<String>,
     ) -> Self {
         let source = source
             .map(|s| if s == "-" { "<stdin>".to_string() } else { s });
 
+        let mut reader = BufReader::with_capacity(CAPACITY, reader);
+        let format = builder
+            .format
+            .or_else(|| {
+                source.as_deref().and_then(InputFormat::from_path)
+            })
+            .unwrap_or_else(|| {
+                if !builder.detect_format {
+                    return InputFormat::Plus;
+                }
+
+                // If the beginning of the input can't be read, the
+                // error is reported on the first read of a record.
+                reader
+                    .fill_buf()
+                    .map(InputFormat::from_magic)
+                    .unwrap_or_default()
+            });
+
+        let inner = match format {
+            InputFormat::Plus => Decoder::Plus(reader),
+            InputFormat::Xml => {
+                Decoder::Xml(Box::new(XmlDecoder::new(reader)))
+            }
+            InputFormat::Json => {
+                Decoder::Json(Box::new(JsonDecoder::new(reader)))
+            }
+            InputFormat::Plain => {
+                Decoder::Plain(PlainDecoder::new(reader))
+            }
+            InputFormat::Import => {
+                Decoder::Import(ImportDecoder::new(reader))
+            }
+            InputFormat::Binary => {
+                Decoder::Binary(BinaryDecoder::new(reader))
+            }
+        };
+
         Self {
-            inner: BufReader::new(reader),
+            policy: builder.policy,
+            repairs: Repairs::default(),
+            scratch: Vec::new(),
             buf: Vec::new(),
+            position: 0,
+            offset: 0,
+            index: 0,
             line: 0,
+            inner,
             source,
         }
     }
 
+    /// Returns the source of the reader (e.g. the filename), if known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let reader =
+    ///     ReaderBuilder::new().from_path("tests/data/ada.dat")?;
+    /// assert_eq!(reader.source(), Some("tests/data/ada.dat"));
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns the counters of the defects, which were repaired so
+    /// far.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::io::ParseMode;
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ \x1f0123\x1e\r\n");
+    /// let mut reader = ReaderBuilder::new()
+    ///     .mode(ParseMode::Lenient)
+    ///     .from_reader(data, None);
+    ///
+    /// let _record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(reader.repairs().crlf, 1);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn repairs(&self) -> &Repairs {
+        &self.repairs
+    }
+
+    /// Returns the byte offset of the next record.
+    ///
+    /// The offset refers to the (decompressed) input and is tracked
+    /// for PICA+ input only; for all other input formats the offset
+    /// is always zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ \x1f01\x1e\n003@ \x1f02\x1e\n");
+    /// let mut reader = ReaderBuilder::new().from_reader(data, None);
+    /// assert_eq!(reader.position(), 0);
+    ///
+    /// let _record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(reader.position(), 10);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
     /// Consumes the reader and returns the underlying [BufReader].
     pub fn into_inner(self) -> BufReader<R> {
-        self.inner
+        match self.inner {
+            Decoder::Plus(inner) => inner,
+            Decoder::Xml(inner) => inner.into_inner(),
+            Decoder::Json(inner) => inner.into_inner(),
+            Decoder::Plain(inner) => inner.into_inner(),
+            Decoder::Import(inner) => inner.into_inner(),
+            Decoder::Binary(inner) => inner.into_inner(),
+        }
+    }
+
+    /// Reads the next record into the internal buffer. Returns `None`
+    /// if there are no further records.
+    pub(crate) fn read_next(
+        &mut self,
+    ) -> Option<Result<(), ReadPicaError>> {
+        self.buf.clear();
+        self.line += 1;
+
+        let result = match self.inner {
+            Decoder::Plus(ref mut inner) => loop {
+                match read_line(
+                    inner,
+                    &mut self.buf,
+                    self.policy.max_record_size,
+                ) {
+                    Ok(n) => {
+                        self.offset = self.position;
+                        self.position += n as u64;
+                        if self.policy.mode == ParseMode::Lenient
+                            && is_blank(&self.buf)
+                        {
+                            self.repairs.blank_lines += 1;
+                            self.buf.clear();
+                            self.line += 1;
+                            continue;
+                        }
+
+                        break Ok(n > 0);
+                    }
+                    Err(e) => break Err(DecodeError::from(e)),
+                }
+            },
+            Decoder::Xml(ref mut inner) => inner
+                .decode_record(&mut self.buf)
+                .map_err(DecodeError::from),
+            Decoder::Json(ref mut inner) => {
+                inner.decode_record(&mut self.buf)
+            }
+            Decoder::Plain(ref mut inner) => {
+                inner.decode_record(&mut self.buf)
+            }
+            Decoder::Import(ref mut inner) => {
+                inner.decode_record(&mut self.buf)
+            }
+            Decoder::Binary(ref mut inner) => inner
+                .decode_record(&mut self.buf)
+                .map_err(DecodeError::from),
+        };
+
+        match result {
+            Err(DecodeError::IO(e)) => {
+                let e = match (&self.inner, &self.source) {
+                    (Decoder::Plus(_), _) | (_, None) => e,
+                    (_, Some(src)) => {
+                        io::Error::new(e.kind(), format!("{e} ({src})"))
+                    }
+                };
+
+                Some(Err(ReadPicaError::from(e)))
+            }
+            Err(DecodeError::Parse(err)) => {
+                self.index += 1;
+                Some(Err(self.parse_error(err)))
+            }
+            Ok(false) => None,
+            Ok(true) => {
+                self.index += 1;
+
+                if let Err(err) = self.policy.apply(
+                    &mut self.buf,
+                    &mut self.scratch,
+                    &mut self.repairs,
+                ) {
+                    return Some(Err(self.parse_error(err)));
+                }
+
+                Some(Ok(()))
+            }
+        }
+    }
+
+    /// Parses the record, which was read by [Reader::read_next].
+    pub(crate) fn byte_record(
+        &self,
+    ) -> Result<ByteRecord<'_>, ReadPicaError> {
+        ByteRecord::from_bytes(&self.buf)
+            .map(|record| record.with_provenance(self.provenance()))
+            .map_err(|err| self.parse_error(err))
+    }
+
+    /// Returns the [Provenance] of the record, which was read by
+    /// [Reader::read_next].
+    fn provenance(&self) -> Provenance<'_> {
+        let offset = match self.inner {
+            Decoder::Plus(_) => Some(self.offset),
+            _ => None,
+        };
+
+        Provenance::new(self.source.as_deref(), self.index, offset)
+    }
+
+    /// Parses the record, which was read by [Reader::read_next], and
+    /// validates its UTF-8 encoding.
+    pub(crate) fn string_record(
+        &self,
+    ) -> Result<StringRecord<'_>, ReadPicaError> {
+        StringRecord::try_from(self.byte_record()?).map_err(|err| {
+            let msg = format!("invalid record on line {}.", self.line);
+            ReadPicaError::Utf8 { msg, err }
+        })
+    }
+
+    /// Creates an error for the current (invalid) record.
+    fn parse_error(&self, err: ParsePicaError) -> ReadPicaError {
+        let source = self.source.as_deref();
+        match self.inner {
+            Decoder::Plus(_) => {
+                ReadPicaError::parse(source, self.line, err)
+            }
+            ref inner => {
+                let mut msg = format!("invalid record {}", self.line);
+                let line = match inner {
+                    Decoder::Json(inner) => {
+                        let (line, column) = inner.position();
+                        msg.push_str(&format!(
+                            " at line {line}, column {column}"
+                        ));
+                        Some(line)
+                    }
+                    Decoder::Plain(inner) => {
+                        msg.push_str(&format!(
+                            " at line {}",
+                            inner.position()
+                        ));
+                        Some(inner.position())
+                    }
+                    Decoder::Import(inner) => {
+                        msg.push_str(&format!(
+                            " at line {}",
+                            inner.position()
+                        ));
+                        Some(inner.position())
+                    }
+                    _ => None,
+                };
+
+                if let Some(src) = source {
+                    msg.push_str(&format!(" ({src})"));
+                }
+
+                msg.push_str(&format!(": {err}."));
+                ReadPicaError::Parse {
+                    input: self.source.clone(),
+                    record: Some(self.line),
+                    line,
+                    msg,
+                    err,
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> Reader<R> {
+    /// Seeks to the given byte offset, so that the next record is read
+    /// from this position.
+    ///
+    /// Line numbers in error messages and the ordinal positions of the
+    /// records (see [Provenance]) are counted from the last seek
+    /// position.
+    ///
+    /// # Errors
+    ///
+    /// Seeking is only supported for (uncompressed) PICA+ input; this
+    /// function fails for all other input formats.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ \x1f01\x1e\n003@ \x1f02\x1e\n");
+    /// let mut reader = ReaderBuilder::new().from_reader(data, None);
+    /// reader.seek(10)?;
+    ///
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// assert_eq!(record.ppn().unwrap(), "2");
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn seek(&mut self, offset: u64) -> io::Result<()> {
+        let Decoder::Plus(ref mut inner) = self.inner else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "seeking is only supported for PICA+ input",
+            ));
+        };
+
+        inner.seek(SeekFrom::Start(offset))?;
+        self.position = offset;
+        self.index = 0;
+        self.line = 0;
+        Ok(())
     }
 }
 
@@ -204,28 +1146,11 @@ impl<R: Read> RecordsIter for Reader<R> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     fn next_byte_record(&mut self) -> Option<Self::ByteItem<'_>> {
-        self.buf.clear();
-        self.line += 1;
-
-        match self.inner.read_until(b'\n', &mut self.buf) {
-            Err(e) => Some(Err(ReadPicaError::from(e))),
-            Ok(0) => None,
-            Ok(_) => match ByteRecord::from_bytes(&self.buf) {
-                Ok(record) => Some(Ok(record)),
-                Err(err) => {
-                    let msg = if let Some(ref src) = self.source {
-                        format!(
-                            "invalid record on line {} ({src}).",
-                            self.line
-                        )
-                    } else {
-                        format!("invalid record on line {}.", self.line)
-                    };
-
-                    Some(Err(ReadPicaError::Parse { msg, err }))
-                }
-            },
+        if let Err(e) = self.read_next()? {
+            return Some(Err(e));
         }
+
+        Some(self.byte_record())
     }
 
     /// Advance the iterator and return the next [StringRecord].
@@ -258,17 +1183,10 @@ impl<R: Read> RecordsIter for Reader<R> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     fn next_string_record(&mut self) -> Option<Self::StringItem<'_>> {
-        let line = self.line;
-        match self.next_byte_record() {
-            Some(Ok(record)) => match StringRecord::try_from(record) {
-                Ok(record) => Some(Ok(record)),
-                Err(err) => {
-                    let msg = format!("invalid record on line {line}.");
-                    Some(Err(ReadPicaError::Utf8 { msg, err }))
-                }
-            },
-            Some(Err(err)) => Some(Err(err)),
-            None => None,
+        if let Err(e) = self.read_next()? {
+            return Some(Err(e));
         }
+
+        Some(self.string_record())
     }
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
 use std::io::{self, Cursor, Write};
 use std::ops::{Deref, DerefMut};
@@ -7,8 +8,59 @@ use std::str::Utf8Error;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use crate::HashOptions;
 use crate::matcher::{OccurrenceMatcher, TagMatcher};
-use crate::primitives::{FieldRef, ParsePicaError, RecordRef};
+use crate::primitives::{
+    AsRecordRef, FieldRef, ParsePicaError, Record, RecordRef,
+};
+
+/// The origin of a record within the input.
+///
+/// The provenance of a record is known for records yielded by a
+/// [RecordsIter](crate::io::RecordsIter) only (see
+/// [ByteRecord::provenance]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Provenance<'a> {
+    source: Option<&'a str>,
+    index: usize,
+    offset: Option<u64>,
+}
+
+impl<'a> Provenance<'a> {
+    /// Creates a new [Provenance].
+    pub(crate) fn new(
+        source: Option<&'a str>,
+        index: usize,
+        offset: Option<u64>,
+    ) -> Self {
+        Self {
+            source,
+            index,
+            offset,
+        }
+    }
+
+    /// Returns the source of the record (e.g. the filename), if known.
+    #[inline]
+    pub fn source(&self) -> Option<&'a str> {
+        self.source
+    }
+
+    /// Returns the (1-based) ordinal position of the record within
+    /// its source.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the byte offset of the record within the
+    /// (decompressed) source. The offset is known for PICA+ input
+    /// only.
+    #[inline]
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+}
 
 /// A record, that may contain invalid UTF-8 data.
 #[derive(Debug)]
@@ -16,6 +68,8 @@ use crate::primitives::{FieldRef, ParsePicaError, RecordRef};
 pub struct ByteRecord<'a> {
     raw_data: Option<&'a [u8]>,
     record: RecordRef<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    provenance: Option<Provenance<'a>>,
 }
 
 impl<'a> ByteRecord<'a> {
@@ -35,15 +89,61 @@ impl<'a> ByteRecord<'a> {
     ///
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn from_bytes<B: AsRef<[u8]>>(
+    pub fn from_bytes<B: AsRef<[u8]> + ?Sized>(
         bytes: &'a B,
     ) -> Result<Self, ParsePicaError> {
         Ok(Self {
             record: RecordRef::from_bytes(bytes)?,
             raw_data: Some(bytes.as_ref()),
+            provenance: None,
         })
     }
 
+    /// Attaches the given [Provenance] to the record.
+    #[inline]
+    pub(crate) fn with_provenance(
+        mut self,
+        provenance: Provenance<'a>,
+    ) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Returns the [Provenance] of the record (source, ordinal
+    /// position and byte offset), if known.
+    ///
+    /// The provenance is attached to all records yielded by a
+    /// [RecordsIter](crate::io::RecordsIter); records created by other
+    /// means have no provenance.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use pica_record::prelude::*;
+    ///
+    /// let data = Cursor::new(b"003@ \x1f01\x1e\n003@ \x1f02\x1e\n");
+    /// let mut reader =
+    ///     ReaderBuilder::new().from_reader(data, Some("a.dat".into()));
+    ///
+    /// let _ = reader.next_byte_record().unwrap()?;
+    /// let record = reader.next_byte_record().unwrap()?;
+    /// let provenance = record.provenance().unwrap();
+    /// assert_eq!(provenance.source(), Some("a.dat"));
+    /// assert_eq!(provenance.index(), 2);
+    /// assert_eq!(provenance.offset(), Some(10));
+    ///
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f01\x1e\n")?;
+    /// assert!(record.provenance().is_none());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn provenance(&self) -> Option<&Provenance<'a>> {
+        self.provenance.as_ref()
+    }
+
     /// Write the [ByteRecord] into the given writer.
     ///
     /// # Example
@@ -208,6 +308,38 @@ impl<'a> ByteRecord<'a> {
         let result = hasher.finalize();
         result.to_vec()
     }
+
+    /// Returns the SHA-256 hash of the record, which is computed
+    /// according to the given [HashOptions].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let record = ByteRecord::from_bytes(
+    ///     b"001B \x1f01999:01-01-24\x1e012A \x1fa123\x1e\n",
+    /// )?;
+    ///
+    /// let options = HashOptions::new()
+    ///     .keep(vec![(TagMatcher::new("012A")?, OccurrenceMatcher::None)]);
+    ///
+    /// assert_eq!(
+    ///     record.sha256_with(&options),
+    ///     ByteRecord::from_bytes(b"012A \x1fa123\x1e\n")?.sha256()
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sha256_with(&self, options: &HashOptions) -> Vec<u8> {
+        if options.is_identity() {
+            return self.sha256();
+        }
+
+        let mut record = Record::from(self.record.clone());
+        options.apply(&mut record);
+        record.sha256()
+    }
 }
 
 impl<'a> Deref for ByteRecord<'a> {
@@ -226,6 +358,13 @@ impl DerefMut for ByteRecord<'_> {
     }
 }
 
+impl AsRecordRef for ByteRecord<'_> {
+    #[inline]
+    fn as_record_ref(&self) -> Cow<'_, RecordRef<'_>> {
+        Cow::Borrowed(&self.record)
+    }
+}
+
 impl PartialEq<ByteRecord<'_>> for ByteRecord<'_> {
     /// Compare two [ByteRecord]s.
     ///
@@ -282,6 +421,7 @@ impl<'a> From<RecordRef<'a>> for ByteRecord<'a> {
     fn from(record: RecordRef<'a>) -> Self {
         ByteRecord {
             raw_data: None,
+            provenance: None,
             record,
         }
     }
@@ -349,3 +489,10 @@ impl DerefMut for StringRecord<'_> {
         &mut self.0
     }
 }
+
+impl AsRecordRef for StringRecord<'_> {
+    #[inline]
+    fn as_record_ref(&self) -> Cow<'_, RecordRef<'_>> {
+        self.0.as_record_ref()
+    }
+}
@@ -0,0 +1,267 @@
+use crate::encoding::{Encoding, transcode};
+use crate::primitives::{ParsePicaError, ParsePicaErrorKind};
+
+/// The parsing mode of a reader.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Accept normalized PICA+ only; every defect results in an error.
+    #[default]
+    Strict,
+    /// Repair recoverable defects on the fly.
+    ///
+    /// In lenient mode, CRLF line endings are converted, a missing
+    /// newline or a missing field separator at the end of a record is
+    /// added, fields without any subfields are removed and empty lines
+    /// between records are skipped. All repairs are counted (see
+    /// [Repairs]).
+    Lenient,
+}
+
+/// The parsing policy of a reader.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Policy {
+    pub(crate) mode: ParseMode,
+    pub(crate) max_record_size: Option<usize>,
+    pub(crate) drop_empty_subfields: bool,
+    pub(crate) encoding: Encoding,
+}
+
+impl Policy {
+    /// Returns `true` if records must be rewritten before parsing.
+    #[inline]
+    pub(crate) fn rewrites(&self) -> bool {
+        self.mode == ParseMode::Lenient || self.drop_empty_subfields
+    }
+
+    /// Checks the size of a record.
+    pub(crate) fn check_size(
+        &self,
+        size: usize,
+    ) -> Result<(), ParsePicaError> {
+        match self.max_record_size {
+            Some(max) if size > max => Err(ParsePicaError::with_kind(
+                ParsePicaErrorKind::RecordTooLarge,
+                format!(
+                    "record exceeds the maximum size of {max} bytes"
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the policy to the record in `buf`.
+    ///
+    /// The size of the record is checked, the record is transcoded to
+    /// UTF-8 and, if necessary, the record is rewritten (see
+    /// [repair]).
+    pub(crate) fn apply(
+        &self,
+        buf: &mut Vec<u8>,
+        scratch: &mut Vec<u8>,
+        repairs: &mut Repairs,
+    ) -> Result<(), ParsePicaError> {
+        self.check_size(buf.len())?;
+        transcode(buf, scratch, self.encoding)?;
+
+        if self.rewrites() {
+            repair(buf, scratch, self, repairs);
+        }
+
+        Ok(())
+    }
+}
+
+/// Counters of the defects, which were repaired while reading.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Repairs {
+    /// The number of CRLF line endings, which were converted.
+    pub crlf: usize,
+    /// The number of records without a trailing newline.
+    pub missing_newline: usize,
+    /// The number of records without a trailing field separator.
+    pub missing_field_separator: usize,
+    /// The number of empty lines, which were skipped.
+    pub blank_lines: usize,
+    /// The number of fields without any subfields, which were removed.
+    pub empty_fields: usize,
+    /// The number of subfields with an empty value, which were
+    /// removed.
+    pub empty_subfields: usize,
+}
+
+impl Repairs {
+    /// Returns the total number of repairs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Repairs;
+    ///
+    /// let repairs = Repairs {
+    ///     crlf: 2,
+    ///     empty_fields: 1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(repairs.total(), 3);
+    /// ```
+    pub fn total(&self) -> usize {
+        self.crlf
+            + self.missing_newline
+            + self.missing_field_separator
+            + self.blank_lines
+            + self.empty_fields
+            + self.empty_subfields
+    }
+}
+
+/// Returns `true` if the line is empty (apart from the line ending).
+#[inline]
+pub(crate) fn is_blank(line: &[u8]) -> bool {
+    matches!(line, b"\n" | b"\r\n")
+}
+
+/// Rewrites the record in `buf` according to the given policy.
+///
+/// The `scratch` buffer is used to avoid an allocation per record. A
+/// record that can't be repaired is left to the parser, which reports
+/// the (remaining) defects.
+pub(crate) fn repair(
+    buf: &mut Vec<u8>,
+    scratch: &mut Vec<u8>,
+    policy: &Policy,
+    repairs: &mut Repairs,
+) {
+    let lenient = policy.mode == ParseMode::Lenient;
+
+    if lenient {
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+            if buf.last() == Some(&b'\r') {
+                buf.pop();
+                repairs.crlf += 1;
+            }
+        } else {
+            repairs.missing_newline += 1;
+        }
+
+        if !buf.is_empty() && buf.last() != Some(&b'\x1e') {
+            buf.push(b'\x1e');
+            repairs.missing_field_separator += 1;
+        }
+
+        buf.push(b'\n');
+    }
+
+    let Some(body) = buf.strip_suffix(b"\n") else {
+        return;
+    };
+
+    scratch.clear();
+
+    for field in body.split_inclusive(|b| *b == b'\x1e') {
+        let Some(field) = field.strip_suffix(b"\x1e") else {
+            // The last field isn't terminated by a field separator,
+            // which is reported by the parser.
+            scratch.extend_from_slice(field);
+            continue;
+        };
+
+        let mut parts = field.split(|b| *b == b'\x1f');
+        let header = parts.next().unwrap_or_default();
+        let start = scratch.len();
+        let mut subfields = 0;
+
+        scratch.extend_from_slice(header);
+        for subfield in parts {
+            if policy.drop_empty_subfields && subfield.len() == 1 {
+                repairs.empty_subfields += 1;
+                continue;
+            }
+
+            scratch.push(b'\x1f');
+            scratch.extend_from_slice(subfield);
+            subfields += 1;
+        }
+
+        if lenient && subfields == 0 && header.ends_with(b" ") {
+            scratch.truncate(start);
+            repairs.empty_fields += 1;
+            continue;
+        }
+
+        scratch.push(b'\x1e');
+    }
+
+    scratch.push(b'\n');
+    std::mem::swap(buf, scratch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(data: &[u8], policy: &Policy) -> (Vec<u8>, Repairs) {
+        let mut repairs = Repairs::default();
+        let mut buf = data.to_vec();
+        repair(&mut buf, &mut vec![], policy, &mut repairs);
+        (buf, repairs)
+    }
+
+    #[test]
+    fn test_repair_lenient() {
+        let policy = Policy {
+            mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+
+        let (buf, repairs) = rewrite(b"003@ \x1f0123\x1e\r\n", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1e\n");
+        assert_eq!(repairs.crlf, 1);
+        assert_eq!(repairs.total(), 1);
+
+        let (buf, repairs) = rewrite(b"003@ \x1f0123", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1e\n");
+        assert_eq!(repairs.missing_newline, 1);
+        assert_eq!(repairs.missing_field_separator, 1);
+
+        let (buf, repairs) =
+            rewrite(b"012A \x1e003@ \x1f0123\x1e012B \x1e\n", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1e\n");
+        assert_eq!(repairs.empty_fields, 2);
+        assert_eq!(repairs.total(), 2);
+
+        let (buf, repairs) =
+            rewrite(b"003@ \x1f0123\x1f\x1e\n", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1f\x1e\n");
+        assert_eq!(repairs.total(), 0);
+    }
+
+    #[test]
+    fn test_repair_drop_empty_subfields() {
+        let policy = Policy {
+            drop_empty_subfields: true,
+            ..Default::default()
+        };
+
+        let (buf, repairs) =
+            rewrite(b"003@ \x1f0123\x1fa\x1e012A \x1fa\x1e\n", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1e012A \x1e\n");
+        assert_eq!(repairs.empty_subfields, 2);
+        assert_eq!(repairs.empty_fields, 0);
+
+        let (buf, _) = rewrite(b"003@ \x1f0123\x1e\r\n", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1e\r\n");
+
+        let policy = Policy {
+            mode: ParseMode::Lenient,
+            ..policy
+        };
+
+        let (buf, repairs) =
+            rewrite(b"003@ \x1f0123\x1e012A \x1fa\x1e\n", &policy);
+        assert_eq!(buf, b"003@ \x1f0123\x1e\n");
+        assert_eq!(repairs.empty_subfields, 1);
+        assert_eq!(repairs.empty_fields, 1);
+    }
+}
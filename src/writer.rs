@@ -3,10 +3,12 @@ use std::fs::OpenOptions;
 use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
-use flate2::Compression;
+use bzip2::write::BzEncoder;
 use flate2::write::GzEncoder;
+use liblzma::write::XzEncoder;
 
 use crate::ByteRecord;
+use crate::bgzf::BgzfWriter;
 
 /// A trait that permits writing [ByteRecord]s.
 pub trait ByteRecordWrite {
@@ -20,11 +22,268 @@ pub trait ByteRecordWrite {
     fn finish(&mut self) -> io::Result<()>;
 }
 
+impl<W: ByteRecordWrite + ?Sized> ByteRecordWrite for Box<W> {
+    #[inline]
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        (**self).write_byte_record(record)
+    }
+
+    #[inline]
+    fn finish(&mut self) -> io::Result<()> {
+        (**self).finish()
+    }
+}
+
+/// A compression format.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// No compression.
+    #[default]
+    None,
+    /// The gzip format (`.gz`).
+    Gzip,
+    /// The blocked gzip format (`.bgz`), which is readable by any
+    /// gzip decoder, but allows parallel decompression and random
+    /// access (see [BgzfWriter](crate::io::BgzfWriter)).
+    Bgzf,
+    /// The Zstandard format (`.zst`).
+    Zstd,
+    /// The xz format (`.xz`).
+    Xz,
+    /// The bzip2 format (`.bz2`).
+    Bzip2,
+}
+
+impl Compression {
+    /// Returns the compression format derived from the extension of
+    /// the given path.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Compression;
+    ///
+    /// assert_eq!(Compression::from_path("a.dat.zst"), Compression::Zstd);
+    /// assert_eq!(Compression::from_path("a.dat.gz"), Compression::Gzip);
+    /// assert_eq!(Compression::from_path("a.dat.bgz"), Compression::Bgzf);
+    /// assert_eq!(Compression::from_path("a.dat"), Compression::None);
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(OsStr::to_str) {
+            Some("gz") => Self::Gzip,
+            Some("bgz") => Self::Bgzf,
+            Some("zst") => Self::Zstd,
+            Some("xz") => Self::Xz,
+            Some("bz2") => Self::Bzip2,
+            _ => Self::None,
+        }
+    }
+
+    /// Returns the compression format derived from the magic number
+    /// at the beginning of the given data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Compression;
+    ///
+    /// assert_eq!(
+    ///     Compression::from_magic(b"\x28\xb5\x2f\xfd..."),
+    ///     Compression::Zstd
+    /// );
+    /// assert_eq!(Compression::from_magic(b"003@ "), Compression::None);
+    /// ```
+    pub fn from_magic(data: &[u8]) -> Self {
+        if data.starts_with(b"\x1f\x8b\x08")
+            && data.len() >= 16
+            && data[3] & 0x04 != 0
+            && &data[12..16] == b"BC\x02\x00"
+        {
+            Self::Bgzf
+        } else if data.starts_with(b"\x1f\x8b") {
+            Self::Gzip
+        } else if data.starts_with(b"\x28\xb5\x2f\xfd") {
+            Self::Zstd
+        } else if data.starts_with(b"\xfd7zXZ\x00") {
+            Self::Xz
+        } else if data.starts_with(b"BZh") {
+            Self::Bzip2
+        } else {
+            Self::None
+        }
+    }
+
+    /// Creates an [Encoder], which compresses arbitrary data in this
+    /// compression format.
+    ///
+    /// If no level is given, the default level of the compression
+    /// format is used (see [WriterBuilder::level]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    ///
+    /// use pica_record::io::Compression;
+    ///
+    /// let mut encoder = Compression::Zstd.encoder(vec![], Some(19))?;
+    /// encoder.write_all(b"ppn,hash\n")?;
+    ///
+    /// let data = encoder.finish()?;
+    /// assert_eq!(Compression::from_magic(&data), Compression::Zstd);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encoder<W: Write>(
+        &self,
+        inner: W,
+        level: Option<u32>,
+    ) -> io::Result<Encoder<W>> {
+        Ok(Encoder(match self {
+            Self::None => EncoderKind::None(inner),
+            Self::Gzip => EncoderKind::Gzip(GzEncoder::new(
+                inner,
+                level.map_or_else(flate2::Compression::default, |l| {
+                    flate2::Compression::new(l.min(9))
+                }),
+            )),
+            Self::Bgzf => EncoderKind::Bgzf(BgzfWriter::with_level(
+                inner,
+                level.unwrap_or(6),
+            )),
+            Self::Zstd => EncoderKind::Zstd(zstd::Encoder::new(
+                inner,
+                level.map_or(0, |l| l.min(22) as i32),
+            )?),
+            Self::Xz => EncoderKind::Xz(XzEncoder::new(
+                inner,
+                level.map_or(6, |l| l.min(9)),
+            )),
+            Self::Bzip2 => EncoderKind::Bzip2(BzEncoder::new(
+                inner,
+                level.map_or_else(bzip2::Compression::default, |l| {
+                    bzip2::Compression::new(l.clamp(1, 9))
+                }),
+            )),
+        }))
+    }
+
+    /// Creates a [ByteRecord] writer, which encodes the records in
+    /// this compression format.
+    fn writer<W: Write + 'static>(
+        &self,
+        inner: W,
+        level: Option<u32>,
+    ) -> io::Result<Box<dyn ByteRecordWrite>> {
+        Ok(match (self, level) {
+            (Self::None, _) => Box::new(PlainWriter::new(inner)),
+            (Self::Gzip, None) => Box::new(GzipWriter::new(inner)),
+            (Self::Gzip, Some(level)) => {
+                Box::new(GzipWriter::with_level(inner, level))
+            }
+            (Self::Bgzf, None) => Box::new(BgzfWriter::new(inner)),
+            (Self::Bgzf, Some(level)) => {
+                Box::new(BgzfWriter::with_level(inner, level))
+            }
+            (Self::Zstd, None) => Box::new(ZstdWriter::new(inner)?),
+            (Self::Zstd, Some(level)) => {
+                Box::new(ZstdWriter::with_level(inner, level)?)
+            }
+            (Self::Xz, None) => Box::new(XzWriter::new(inner)),
+            (Self::Xz, Some(level)) => {
+                Box::new(XzWriter::with_level(inner, level))
+            }
+            (Self::Bzip2, None) => Box::new(Bzip2Writer::new(inner)),
+            (Self::Bzip2, Some(level)) => {
+                Box::new(Bzip2Writer::with_level(inner, level))
+            }
+        })
+    }
+}
+
+/// A writer, which compresses the written data.
+///
+/// An encoder is created by [Compression::encoder]. In contrast to the
+/// [ByteRecord] writers, an encoder accepts arbitrary data (e.g. CSV
+/// or TSV). The encoder must be finished by [Encoder::finish] in order
+/// to write the end of the compressed stream.
+pub struct Encoder<W: Write>(EncoderKind<W>);
+
+enum EncoderKind<W: Write> {
+    None(W),
+    Gzip(GzEncoder<W>),
+    Bgzf(BgzfWriter<W>),
+    Zstd(zstd::Encoder<'static, W>),
+    Xz(XzEncoder<W>),
+    Bzip2(BzEncoder<W>),
+}
+
+impl<W: Write> Encoder<W> {
+    /// Finishes the compressed stream and returns the underlying
+    /// writer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    ///
+    /// use pica_record::io::Compression;
+    ///
+    /// let mut encoder = Compression::Gzip.encoder(vec![], None)?;
+    /// encoder.write_all(b"ppn,hash\n")?;
+    ///
+    /// let data = encoder.finish()?;
+    /// assert_eq!(Compression::from_magic(&data), Compression::Gzip);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn finish(self) -> io::Result<W> {
+        match self.0 {
+            EncoderKind::None(inner) => Ok(inner),
+            EncoderKind::Gzip(inner) => inner.finish(),
+            EncoderKind::Bgzf(inner) => inner.into_inner(),
+            EncoderKind::Zstd(inner) => inner.finish(),
+            EncoderKind::Xz(inner) => inner.finish(),
+            EncoderKind::Bzip2(inner) => inner.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0 {
+            EncoderKind::None(ref mut inner) => inner.write(buf),
+            EncoderKind::Gzip(ref mut inner) => inner.write(buf),
+            EncoderKind::Bgzf(ref mut inner) => inner.write(buf),
+            EncoderKind::Zstd(ref mut inner) => inner.write(buf),
+            EncoderKind::Xz(ref mut inner) => inner.write(buf),
+            EncoderKind::Bzip2(ref mut inner) => inner.write(buf),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        match self.0 {
+            EncoderKind::None(ref mut inner) => inner.flush(),
+            EncoderKind::Gzip(ref mut inner) => inner.flush(),
+            EncoderKind::Bgzf(ref mut inner) => inner.flush(),
+            EncoderKind::Zstd(ref mut inner) => inner.flush(),
+            EncoderKind::Xz(ref mut inner) => inner.flush(),
+            EncoderKind::Bzip2(ref mut inner) => inner.flush(),
+        }
+    }
+}
+
 /// Configures and build a [ByteRecord] writer.
 #[derive(Default)]
 pub struct WriterBuilder {
-    append: bool,
-    gzip: bool,
+    pub(crate) append: bool,
+    pub(crate) compression: Option<Compression>,
+    pub(crate) level: Option<u32>,
 }
 
 impl WriterBuilder {
@@ -35,11 +294,36 @@ impl WriterBuilder {
 
     /// Builds a [ByteRecord] writer from this configuration that writes
     /// to the given path.
+    ///
+    /// If no compression format is set explicitly, the format is
+    /// derived from the extension of the path (`.gz`, `.bgz`, `.zst`,
+    /// `.xz` or `.bz2`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut file = tempfile::NamedTempFile::with_suffix(".dat.zst")?;
+    /// let mut writer = WriterBuilder::new().from_path(file.path())?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    ///
+    /// let mut reader = ReaderBuilder::new().from_path(file.path())?;
+    /// assert_eq!(reader.next_byte_record().unwrap()?, record);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
     pub fn from_path<P: AsRef<Path>>(
         &self,
         path: P,
     ) -> io::Result<Box<dyn ByteRecordWrite>> {
         let path = path.as_ref();
+        let compression = self
+            .compression
+            .unwrap_or_else(|| Compression::from_path(path));
+
         let file = OpenOptions::new()
             .write(true)
             .create(true)
@@ -47,13 +331,7 @@ impl WriterBuilder {
             .append(self.append)
             .open(path)?;
 
-        if self.gzip
-            || path.extension().and_then(OsStr::to_str) == Some("gz")
-        {
-            Ok(Box::new(GzipWriter::new(file)))
-        } else {
-            Ok(Box::new(PlainWriter::new(file)))
-        }
+        compression.writer(file, self.level)
     }
 
     /// Builds a [ByteRecord] writer from this configuration that writes
@@ -64,32 +342,44 @@ impl WriterBuilder {
     ) -> io::Result<Box<dyn ByteRecordWrite>> {
         match path {
             Some(path) => self.from_path(path),
-            None => {
-                if self.gzip {
-                    Ok(Box::new(GzipWriter::new(Box::new(
-                        io::stdout().lock(),
-                    ))))
-                } else {
-                    Ok(Box::new(PlainWriter::new(Box::new(
-                        io::stdout(),
-                    ))))
-                }
-            }
+            None => self
+                .compression
+                .unwrap_or_default()
+                .writer(io::stdout(), self.level),
         }
     }
 
     /// Whether to use a gzip encoder or not.
     ///
     /// When this flag is set, the writer encode the records in gzip
-    /// format. This flag is disabled by default and has no effect when
-    /// writing to `stdout`.
+    /// format. This flag is disabled by default.
+    pub fn gzip(self, yes: bool) -> Self {
+        if yes {
+            self.compression(Compression::Gzip)
+        } else {
+            self
+        }
+    }
+
+    /// Sets the compression format.
     ///
-    /// # Panics
+    /// If no compression format is set, the format is derived from the
+    /// extension of the output path. When writing to `stdout`, the
+    /// records are not compressed by default.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Sets the compression level.
     ///
-    /// It's an error to use this flag in append-mode.
-    pub fn gzip(mut self, yes: bool) -> Self {
-        assert!(!yes || (yes ^ self.append));
-        self.gzip = yes;
+    /// The range of valid levels depends on the compression format:
+    /// gzip, BGZF, xz and bzip2 support levels between 0 (resp. 1)
+    /// and 9;
+    /// Zstandard supports levels between 1 and 22. If no level is set,
+    /// the default level of the compression format is used.
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = Some(level);
         self
     }
 
@@ -100,12 +390,11 @@ impl WriterBuilder {
     /// no effect when writing to `stdout`. This option is disabled by
     /// default.
     ///
-    /// # Panics
-    ///
-    /// It's an error to use this flag in combination with a gzip
-    /// writer.
+    /// A compressed writer appends a new member (resp. frame or
+    /// stream) to the file. All supported compression formats allow
+    /// the concatenation of members, so that the file is decoded as a
+    /// whole.
     pub fn append(mut self, yes: bool) -> Self {
-        assert!(!yes || (yes ^ self.gzip));
         self.append = yes;
         self
     }
@@ -188,7 +477,27 @@ impl<W: Write> GzipWriter<W> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(inner: W) -> GzipWriter<W> {
-        Self(GzEncoder::new(inner, Compression::default()))
+        Self(GzEncoder::new(inner, flate2::Compression::default()))
+    }
+
+    /// Creates a new [GzipWriter] with the given compression level
+    /// (0-9).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::GzipWriter;
+    ///
+    /// let mut file = tempfile::tempfile()?;
+    /// let _writer = GzipWriter::with_level(file, 9);
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_level(inner: W, level: u32) -> GzipWriter<W> {
+        Self(GzEncoder::new(
+            inner,
+            flate2::Compression::new(level.min(9)),
+        ))
     }
 }
 
@@ -223,3 +532,160 @@ impl<W: Write> ByteRecordWrite for GzipWriter<W> {
         Ok(())
     }
 }
+
+/// A [ByteRecord] writer that encodes records in Zstandard format.
+pub struct ZstdWriter<W: Write>(zstd::Encoder<'static, W>);
+
+impl<W: Write> ZstdWriter<W> {
+    /// Creates a new [ZstdWriter] with the default compression level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ZstdWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut file = tempfile::tempfile()?;
+    /// let mut writer = ZstdWriter::new(file)?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> io::Result<Self> {
+        Self::with_level(inner, 0)
+    }
+
+    /// Creates a new [ZstdWriter] with the given compression level
+    /// (1-22). A level of `0` means the default level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::ZstdWriter;
+    ///
+    /// let mut file = tempfile::tempfile()?;
+    /// let _writer = ZstdWriter::with_level(file, 19)?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_level(inner: W, level: u32) -> io::Result<Self> {
+        let level = level.min(22) as i32;
+        Ok(Self(zstd::Encoder::new(inner, level)?))
+    }
+}
+
+impl<W: Write> ByteRecordWrite for ZstdWriter<W> {
+    /// Writes a [ByteRecord] to the [ZstdWriter].
+    #[inline(always)]
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        record.write_to(&mut self.0)
+    }
+
+    /// Finish the [ZstdWriter].
+    #[inline(always)]
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.do_finish()
+    }
+}
+
+/// A [ByteRecord] writer that encodes records in xz format.
+pub struct XzWriter<W: Write>(XzEncoder<W>);
+
+impl<W: Write> XzWriter<W> {
+    /// Creates a new [XzWriter] with the default compression level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::XzWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut file = tempfile::tempfile()?;
+    /// let mut writer = XzWriter::new(file);
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self::with_level(inner, 6)
+    }
+
+    /// Creates a new [XzWriter] with the given compression level
+    /// (0-9).
+    pub fn with_level(inner: W, level: u32) -> Self {
+        Self(XzEncoder::new(inner, level.min(9)))
+    }
+}
+
+impl<W: Write> ByteRecordWrite for XzWriter<W> {
+    /// Writes a [ByteRecord] to the [XzWriter].
+    #[inline(always)]
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        record.write_to(&mut self.0)
+    }
+
+    /// Finish the [XzWriter].
+    #[inline(always)]
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.try_finish()
+    }
+}
+
+/// A [ByteRecord] writer that encodes records in bzip2 format.
+pub struct Bzip2Writer<W: Write>(BzEncoder<W>);
+
+impl<W: Write> Bzip2Writer<W> {
+    /// Creates a new [Bzip2Writer] with the default compression level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::Bzip2Writer;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut file = tempfile::tempfile()?;
+    /// let mut writer = Bzip2Writer::new(file);
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> Self {
+        Self(BzEncoder::new(inner, bzip2::Compression::default()))
+    }
+
+    /// Creates a new [Bzip2Writer] with the given compression level
+    /// (1-9).
+    pub fn with_level(inner: W, level: u32) -> Self {
+        let level = bzip2::Compression::new(level.clamp(1, 9));
+        Self(BzEncoder::new(inner, level))
+    }
+}
+
+impl<W: Write> ByteRecordWrite for Bzip2Writer<W> {
+    /// Writes a [ByteRecord] to the [Bzip2Writer].
+    #[inline(always)]
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        record.write_to(&mut self.0)
+    }
+
+    /// Finish the [Bzip2Writer].
+    #[inline(always)]
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.try_finish()
+    }
+}
@@ -0,0 +1,406 @@
+use std::io::{self, BufRead, BufWriter, Write};
+
+use bstr::ByteSlice;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{
+    BytesDecl, BytesEnd, BytesRef, BytesStart, BytesText, Event,
+};
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::{NsReader, Writer};
+
+use crate::ByteRecord;
+use crate::writer::ByteRecordWrite;
+
+/// The namespace of PICA-XML documents.
+const NAMESPACE: &[u8] = b"info:srw/schema/5/picaXML-v1.0";
+
+/// A streaming decoder for PICA-XML (`info:srw/schema/5/picaXML-v1.0`).
+///
+/// The decoder translates one `<record>` element at a time into
+/// normalized PICA+. Elements that don't belong to the PICA-XML
+/// namespace (e.g. the envelope of a SRU response) are skipped.
+pub(crate) struct XmlDecoder<R: BufRead> {
+    reader: NsReader<R>,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> XmlDecoder<R> {
+    /// Creates a new decoder from a buffered reader.
+    pub(crate) fn new(inner: R) -> Self {
+        let mut reader = NsReader::from_reader(inner);
+        reader.config_mut().expand_empty_elements = true;
+
+        Self {
+            buf: Vec::new(),
+            reader,
+        }
+    }
+
+    /// Consumes the decoder and returns the underlying reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    /// Decodes the next `<record>` element and appends the normalized
+    /// PICA+ data to `out`. Returns `false` if there are no further
+    /// records.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the input isn't well-formed XML, if a
+    /// required attribute (`tag` or `code`) is missing or if the
+    /// content of a subfield contains a newline or a PICA+ separator.
+    pub(crate) fn decode_record(
+        &mut self,
+        out: &mut Vec<u8>,
+    ) -> io::Result<bool> {
+        let mut state = State::Outside;
+
+        loop {
+            self.buf.clear();
+
+            let (ns, event) = match self
+                .reader
+                .read_resolved_event_into(&mut self.buf)
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    let pos = self.reader.error_position();
+                    return Err(invalid_data(pos, e));
+                }
+            };
+
+            let is_pica = match ns {
+                ResolveResult::Unbound => true,
+                ResolveResult::Bound(Namespace(ns)) => ns == NAMESPACE,
+                ResolveResult::Unknown(_) => false,
+            };
+
+            match (state, event) {
+                (State::Outside, Event::Start(e))
+                    if is_pica
+                        && e.local_name().as_ref() == b"record" =>
+                {
+                    state = State::Record;
+                }
+                (State::Record, Event::Start(e))
+                    if is_pica
+                        && e.local_name().as_ref() == b"datafield" =>
+                {
+                    let pos = self.reader.buffer_position();
+                    out.extend_from_slice(&attribute(&e, b"tag", pos)?);
+                    if let Some(occurrence) = e
+                        .try_get_attribute("occurrence")
+                        .map_err(|e| invalid_data(pos, e))?
+                        .filter(|attr| !attr.value.is_empty())
+                    {
+                        out.push(b'/');
+                        out.extend_from_slice(&occurrence.value);
+                    }
+
+                    out.push(b' ');
+                    state = State::Field;
+                }
+                (State::Field, Event::Start(e))
+                    if is_pica
+                        && e.local_name().as_ref() == b"subfield" =>
+                {
+                    let pos = self.reader.buffer_position();
+                    out.push(b'\x1f');
+                    out.extend_from_slice(&attribute(
+                        &e, b"code", pos,
+                    )?);
+                    state = State::Subfield;
+                }
+                (State::Subfield, Event::Text(e)) => {
+                    let pos = self.reader.buffer_position();
+                    push_value(out, &e, pos - e.len() as u64)?;
+                }
+                (State::Subfield, Event::CData(e)) => {
+                    let pos = self.reader.buffer_position();
+                    push_value(out, &e, pos - e.len() as u64 - 3)?;
+                }
+                (State::Subfield, Event::GeneralRef(e)) => {
+                    let pos = self.reader.buffer_position();
+                    let pos = pos - e.len() as u64 - 2;
+                    let mut value = Vec::new();
+                    resolve(&e, &mut value, pos)?;
+                    push_value(out, &value, pos)?;
+                }
+                (State::Subfield, Event::End(_)) => {
+                    state = State::Field;
+                }
+                (State::Field, Event::End(_)) => {
+                    out.push(b'\x1e');
+                    state = State::Record;
+                }
+                (State::Record, Event::End(_)) => {
+                    out.push(b'\n');
+                    return Ok(true);
+                }
+                (_, Event::Eof) => {
+                    if state != State::Outside {
+                        return Err(invalid_data(
+                            self.reader.buffer_position(),
+                            "unexpected end of file",
+                        ));
+                    }
+
+                    return Ok(false);
+                }
+                (State::Outside, _) | (_, Event::Text(_)) => {}
+                (_, Event::Comment(_) | Event::PI(_)) => {}
+                (_, event) => {
+                    return Err(invalid_data(
+                        self.reader.buffer_position(),
+                        format!("unexpected event {event:?}"),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// The position of the decoder within a PICA-XML document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Outside,
+    Record,
+    Field,
+    Subfield,
+}
+
+/// Returns the (raw) value of a required attribute.
+fn attribute(
+    e: &BytesStart,
+    name: &[u8],
+    pos: u64,
+) -> io::Result<Vec<u8>> {
+    match e.try_get_attribute(name) {
+        Ok(Some(attr)) => Ok(attr.value.into_owned()),
+        Ok(None) => Err(invalid_data(
+            pos,
+            format!(
+                "missing attribute '{}'",
+                String::from_utf8_lossy(name)
+            ),
+        )),
+        Err(e) => Err(invalid_data(pos, e)),
+    }
+}
+
+/// Appends the content of a subfield to `out`. The content must not
+/// contain a newline or a PICA+ separator, which is reported at the
+/// given byte offset (plus the position within the content).
+fn push_value(
+    out: &mut Vec<u8>,
+    value: &[u8],
+    pos: u64,
+) -> io::Result<()> {
+    if let Some(i) = value.find_byteset(b"\n\x1e\x1f") {
+        return Err(invalid_data(
+            pos + i as u64,
+            format!(
+                "invalid character '{}' in subfield",
+                value[i].escape_ascii()
+            ),
+        ));
+    }
+
+    out.extend_from_slice(value);
+    Ok(())
+}
+
+/// Resolves a character or predefined entity reference.
+fn resolve(
+    e: &BytesRef,
+    out: &mut Vec<u8>,
+    pos: u64,
+) -> io::Result<()> {
+    if let Some(ch) =
+        e.resolve_char_ref().map_err(|e| invalid_data(pos, e))?
+    {
+        let mut buf = [0; 4];
+        out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+        return Ok(());
+    }
+
+    let name = e.decode().map_err(|e| invalid_data(pos, e))?;
+    match resolve_predefined_entity(&name) {
+        Some(value) => {
+            out.extend_from_slice(value.as_bytes());
+            Ok(())
+        }
+        None => Err(invalid_data(
+            pos,
+            format!("unknown entity reference '&{name};'"),
+        )),
+    }
+}
+
+#[inline]
+fn invalid_data<E: ToString>(pos: u64, err: E) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "invalid PICA-XML at byte offset {pos}: {}",
+            err.to_string()
+        ),
+    )
+}
+
+/// A [ByteRecord] writer that encodes records as PICA-XML.
+pub struct XmlWriter<W: Write> {
+    inner: Writer<BufWriter<W>>,
+}
+
+impl<W: Write> XmlWriter<W> {
+    /// Creates a new [XmlWriter].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pica_record::io::XmlWriter;
+    /// use pica_record::prelude::*;
+    ///
+    /// let mut out = Vec::new();
+    /// let mut writer = XmlWriter::new(&mut out)?;
+    /// let record = ByteRecord::from_bytes(b"003@ \x1f0123456789X\x1e\n")?;
+    /// writer.write_byte_record(&record)?;
+    /// writer.finish()?;
+    /// drop(writer);
+    ///
+    /// let out = String::from_utf8(out)?;
+    /// assert!(
+    ///     out.contains(r#"<subfield code="0">123456789X</subfield>"#)
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(inner: W) -> io::Result<Self> {
+        let mut inner =
+            Writer::new_with_indent(BufWriter::new(inner), b' ', 4);
+        inner.write_event(Event::Decl(BytesDecl::new(
+            "1.0",
+            Some("UTF-8"),
+            None,
+        )))?;
+
+        let attributes = [
+            ("targetNamespace", "info:srw/schema/5/picaXML-v1.0"),
+            ("xmlns:xs", "http://www.w3.org/2001/XMLSchema"),
+            ("xmlns", "info:srw/schema/5/picaXML-v1.0"),
+        ];
+
+        inner.write_event(Event::Start(
+            BytesStart::from_content("collection", 0)
+                .with_attributes(attributes),
+        ))?;
+
+        Ok(Self { inner })
+    }
+}
+
+impl<W: Write> ByteRecordWrite for XmlWriter<W> {
+    fn write_byte_record(
+        &mut self,
+        record: &ByteRecord,
+    ) -> io::Result<()> {
+        self.inner.create_element("record").write_inner_content(
+            |r| {
+                for field in record.fields() {
+                    let tag = field.tag().to_string();
+                    r.create_element("datafield")
+                        .with_attribute(("tag", tag.as_str()))
+                        .write_inner_content(|f| {
+                            for subfield in field.subfields() {
+                                let code = subfield.code().to_string();
+                                f.create_element("subfield")
+                                    .with_attribute((
+                                        "code",
+                                        code.as_str(),
+                                    ))
+                                    .write_text_content(
+                                        BytesText::new(to_str(
+                                            subfield.value(),
+                                        )?),
+                                    )?;
+                            }
+
+                            Ok(())
+                        })?;
+                }
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner
+            .write_event(Event::End(BytesEnd::new("collection")))?;
+        self.inner.get_mut().flush()
+    }
+}
+
+#[inline]
+fn to_str(value: &[u8]) -> io::Result<&str> {
+    value
+        .to_str()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(data: &str) -> io::Result<Vec<u8>> {
+        let mut decoder = XmlDecoder::new(data.as_bytes());
+        let mut out = Vec::new();
+        while decoder.decode_record(&mut out)? {}
+        Ok(out)
+    }
+
+    #[test]
+    fn test_decode_record() {
+        let data = "<collection><record>\
+            <datafield tag=\"003@\"><subfield code=\"0\">123</subfield>\
+            </datafield><datafield tag=\"047A\" occurrence=\"03\">\
+            <subfield code=\"e\">a &amp; <![CDATA[b]]>&#x63;</subfield>\
+            </datafield></record></collection>";
+
+        assert_eq!(
+            decode(data).unwrap(),
+            b"003@ \x1f0123\x1e047A/03 \x1fea & bc\x1e\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_value() {
+        let prefix =
+            "<record><datafield tag=\"003@\"><subfield code=\"0\">";
+        let offset = prefix.len() as u64 + 2;
+
+        for (value, offset) in [
+            ("12\n34", offset),
+            ("12&#10;34", offset),
+            ("12&#x1e;34", offset),
+            ("12&#x1F;34", offset),
+            ("12<![CDATA[\n]]>34", offset + 9),
+        ] {
+            let data = format!(
+                "{prefix}{value}</subfield></datafield></record>"
+            );
+            let err = decode(&data).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(
+                err.to_string().starts_with(&format!(
+                    "invalid PICA-XML at byte offset {offset}: "
+                )),
+                "{err}"
+            );
+        }
+    }
+}